@@ -0,0 +1,20 @@
+//! Compares the proving time of the polynomial range check of `range_check::example1` with
+//! the lookup range check of `range_check::example2`, both over 8 bits.
+//!
+//! cargo bench --features bench --bench range_check
+use std::time::Duration;
+use halo2_study::bench::{measure_lookup_range_check, measure_polynomial_range_check, Measurement};
+
+/// Proofs per approach; the reported time is their mean.
+const RUNS: u32 = 5;
+
+fn report(name: &str, measure: fn(u8) -> Measurement) {
+    let measurements: Vec<_> = (0..RUNS).map(|i| measure(200 + i as u8)).collect();
+    let mean = measurements.iter().map(|m| m.prove).sum::<Duration>() / RUNS;
+    println!("{:<12} k = {:<2}  prove: {:?}", name, measurements[0].k, mean);
+}
+
+fn main() {
+    report("polynomial", measure_polynomial_range_check);
+    report("lookup", measure_lookup_range_check);
+}
@@ -0,0 +1,50 @@
+//! Proving-time measurements for the benches under `benches/`, built with `--features bench`.
+use std::time::{Duration, Instant};
+use halo2_proofs::{
+    pasta::{EqAffine, Fp},
+    plonk::{keygen_pk, keygen_vk, Circuit},
+    poly::commitment::Params,
+};
+use crate::prover::serialize_proof;
+use crate::range_check::{example1, example2};
+use crate::utils::minimum_k;
+
+/// The circuit size and proving time of one measured circuit.
+#[derive(Debug, Clone, Copy)]
+pub struct Measurement {
+    pub k: u32,
+    /// The time to create a proof, excluding parameter and key generation.
+    pub prove: Duration,
+}
+
+/// Proves `circuit`, which has no instance columns, at the smallest `k` it fits in.
+fn measure<C: Circuit<Fp>>(circuit: C) -> Measurement {
+    let k = minimum_k(&circuit, vec![]);
+    let params: Params<EqAffine> = Params::new(k);
+    let vk = keygen_vk(&params, &circuit).expect("the verifying key is generated");
+    let pk = keygen_pk(&params, vk, &circuit).expect("the proving key is generated");
+
+    let start = Instant::now();
+    serialize_proof(&params, &pk, circuit, &[]).expect("the proof is created");
+    Measurement {
+        k,
+        prove: start.elapsed(),
+    }
+}
+
+/// Range checks `value` to 8 bits with example1's gate, the product over `[0, 256)` of degree 256.
+pub fn measure_polynomial_range_check(value: u8) -> Measurement {
+    measure(example1::MyCircuit::<Fp, 256> {
+        v: Fp::from(value as u64),
+    })
+}
+
+/// Range checks `value` to 8 bits with example2's lookup into a table of the 256 values.
+pub fn measure_lookup_range_check(value: u8) -> Measurement {
+    // a threshold of 1 bit leaves every 8-bit value to the lookup, and the gate at degree 2
+    measure(example2::MyCircuit::<Fp, 1> {
+        v: Fp::from(value as u64),
+        num_bits: 8,
+        v_override: None,
+    })
+}
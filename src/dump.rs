@@ -0,0 +1,151 @@
+//! Dumps the assignment table of a circuit to CSV, for spotting wrong witness values
+//! without the `dev-graph` renderer.
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::Value,
+    dev::MockProver,
+    pasta::Fp,
+    plonk::{Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, Fixed, FloorPlanner, Instance, Selector},
+};
+
+/// Records the value of every assigned cell, with the instance values read from `instances`.
+struct AssignmentRecorder<'a> {
+    instances: &'a [Vec<Fp>],
+    advice: Vec<Vec<Option<Fp>>>,
+    fixed: Vec<Vec<Option<Fp>>>,
+}
+
+impl<'a> AssignmentRecorder<'a> {
+    fn record<V, VR>(cells: &mut [Vec<Option<Fp>>], column: usize, row: usize, to: V) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fp>>,
+    {
+        let cell = cells[column].get_mut(row).ok_or(Error::BoundsFailure)?;
+        to().into_field().map(|value| *cell = Some(value.evaluate()));
+        Ok(())
+    }
+}
+
+impl<'a> Assignment<Fp> for AssignmentRecorder<'a> {
+    fn enter_region<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn exit_region(&mut self) {}
+
+    fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, _: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        Ok(())
+    }
+
+    fn query_instance(&self, column: Column<Instance>, row: usize) -> Result<Value<Fp>, Error> {
+        let value = self.instances
+            .get(column.index())
+            .and_then(|column| column.get(row))
+            .copied()
+            .unwrap_or_else(Fp::zero);
+        Ok(Value::known(value))
+    }
+
+    fn assign_advice<V, VR, A, AR>(&mut self, _: A, column: Column<Advice>, row: usize, to: V) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fp>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        Self::record(&mut self.advice, column.index(), row, to)
+    }
+
+    fn assign_fixed<V, VR, A, AR>(&mut self, _: A, column: Column<Fixed>, row: usize, to: V) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fp>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        Self::record(&mut self.fixed, column.index(), row, to)
+    }
+
+    fn copy(&mut self, _: Column<Any>, _: usize, _: Column<Any>, _: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn fill_from_row(&mut self, column: Column<Fixed>, row: usize, to: Value<Assigned<Fp>>) -> Result<(), Error> {
+        let cells = self.fixed[column.index()].get_mut(row..).ok_or(Error::BoundsFailure)?;
+        to.map(|value| cells.iter_mut().for_each(|cell| *cell = Some(value.evaluate())));
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {}
+}
+
+/// Small values are written in decimal, anything else as the field element's hex.
+fn format_cell(cell: Option<Fp>) -> String {
+    match cell {
+        None => String::new(),
+        Some(value) if Fp::from_u128(value.get_lower_128()) == value => value.get_lower_128().to_string(),
+        Some(value) => format!("{:?}", value),
+    }
+}
+
+/// Checks `circuit` with `MockProver`, then writes its `2^k` rows to `path` as CSV, one column
+/// per advice, fixed and instance column in that order. Unassigned cells are left empty.
+pub(crate) fn dump_assignments<C: Circuit<Fp>>(
+    k: u32,
+    circuit: &C,
+    instances: Vec<Vec<Fp>>,
+    path: impl AsRef<Path>,
+) -> io::Result<()> {
+    let to_io = |e: Error| io::Error::new(io::ErrorKind::Other, format!("{:?}", e));
+    MockProver::run(k, circuit, instances.clone()).map_err(to_io)?;
+
+    let n = 1usize << k;
+    let mut meta = ConstraintSystem::default();
+    let config = C::configure(&mut meta);
+
+    let mut recorder = AssignmentRecorder {
+        instances: &instances,
+        advice: vec![vec![None; n]; meta.num_advice_columns()],
+        fixed: vec![vec![None; n]; meta.num_fixed_columns()],
+    };
+    C::FloorPlanner::synthesize(&mut recorder, circuit, config, meta.constants().clone()).map_err(to_io)?;
+
+    let instance: Vec<Vec<Option<Fp>>> = (0..meta.num_instance_columns())
+        .map(|column| (0..n).map(|row| instances.get(column).and_then(|c| c.get(row)).copied()).collect())
+        .collect();
+
+    let mut file = File::create(path)?;
+    let header = ["advice", "fixed", "instance"]
+        .iter()
+        .zip([recorder.advice.len(), recorder.fixed.len(), instance.len()])
+        .flat_map(|(kind, count)| (0..count).map(move |i| format!("{}_{}", kind, i)));
+    writeln!(file, "row,{}", header.collect::<Vec<_>>().join(","))?;
+
+    for row in 0..n {
+        let cells = recorder.advice.iter()
+            .chain(recorder.fixed.iter())
+            .chain(instance.iter())
+            .map(|column| format_cell(column[row]));
+        writeln!(file, "{},{}", row, cells.collect::<Vec<_>>().join(","))?;
+    }
+
+    Ok(())
+}
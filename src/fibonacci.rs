@@ -1,4 +1,267 @@
-mod example1;
+use std::io;
+use std::marker::PhantomData;
+#[cfg(test)]
+use halo2_proofs::dev::MockProver;
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    pasta::{EqAffine, Fp},
+    plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, Error, SingleVerifier},
+    poly::commitment::Params,
+};
+use rand_core::OsRng;
+use crate::prover::{make_reader, make_writer};
+use crate::utils::minimum_k;
+
+mod accumulate;
+mod dot_product;
+pub(crate) mod example1;
 mod example2;
 mod example3;
 mod example4;
+mod horner;
+mod pow;
+
+/// Checks before proving that `instances` holds the two seeds `f(0)`, `f(1)` and the
+/// output at `expected_output_row`, so that a short instance vector is reported up front
+/// as `Error::InvalidInstances` rather than failing deep inside `assign`. The circuits
+/// cannot check it in `synthesize`, which only sees instance cells through the layouter:
+/// the provers pad the column with zeros first, so a missing output reads as 0. Every
+/// entry point, `prove_circuit` and `mock_circuit`, runs it instead.
+fn validate_instances<F: FieldExt>(instances: &[F], expected_output_row: usize) -> Result<(), Error> {
+    if instances.len() < 2 || instances.len() <= expected_output_row {
+        return Err(Error::InvalidInstances);
+    }
+    Ok(())
+}
+
+/// The layouts of the Fibonacci examples `prove_fib` can prove with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FibVariant {
+    /// `example1`: one add per row over three columns, rows linked by copies.
+    V1,
+    /// `example2`: two terms per row over two columns, in one region.
+    V2,
+    /// `example3`: one term per row down a single column.
+    V3,
+}
+
+impl FibVariant {
+    pub(crate) const ALL: [FibVariant; 3] = [FibVariant::V1, FibVariant::V2, FibVariant::V3];
+}
+
+/// The first `n` terms `f(0), .., f(n - 1)` of the sequence starting at `f(0), f(1) = seeds`,
+/// computed off-circuit so that tests derive expected outputs rather than hardcode them.
+pub(crate) fn fib_reference(n: usize, seeds: (u64, u64)) -> Vec<Fp> {
+    let mut terms = vec![Fp::from(seeds.0), Fp::from(seeds.1)];
+    while terms.len() < n {
+        terms.push(terms[terms.len() - 2] + terms[terms.len() - 1]);
+    }
+    terms.truncate(n);
+    terms
+}
+
+/// The term `f(n)` of the sequence starting at `f(0), f(1) = seeds`.
+pub(crate) fn fib_term(n: usize, seeds: (u64, u64)) -> Fp {
+    fib_reference(n + 1, seeds)[n]
+}
+
+/// The public inputs `[f(0), f(1), f(n - 1)]` of a table of the first `n` terms.
+#[cfg(test)]
+pub(crate) fn fib_instances(n: usize, seeds: (u64, u64)) -> Vec<Fp> {
+    let terms = fib_reference(n, seeds);
+    vec![terms[0], terms[1], terms[n - 1]]
+}
+
+/// Runs `circuit` on the `MockProver` at `k` against `instance`, the seeds followed by the
+/// output, after checking it as `prove_circuit` does.
+#[cfg(test)]
+pub(crate) fn mock_circuit<C: Circuit<Fp>>(k: u32, circuit: &C, instance: Vec<Fp>) -> Result<MockProver<Fp>, Error> {
+    validate_instances(&instance, 2)?;
+    MockProver::run(k, circuit, vec![instance])
+}
+
+/// Proves `circuit` at the smallest `k` it fits in, and verifies the proof against `instance`,
+/// the seeds followed by the output.
+fn prove_circuit<C: Circuit<Fp>>(circuit: C, instance: &[Fp]) -> Result<Vec<u8>, Error> {
+    validate_instances(instance, 2)?;
+
+    let k = minimum_k(&circuit, vec![instance.to_vec()]);
+    let params: Params<EqAffine> = Params::new(k);
+    let vk = keygen_vk(&params, &circuit)?;
+    let pk = keygen_pk(&params, vk, &circuit)?;
+
+    let mut transcript = make_writer();
+    create_proof(&params, &pk, &[circuit], &[&[instance]], OsRng, &mut transcript)?;
+    let proof = transcript.finalize();
+
+    let strategy = SingleVerifier::new(&params);
+    let mut transcript = make_reader(&proof);
+    verify_proof(&params, pk.get_vk(), strategy, &[&[instance]], &mut transcript)?;
+
+    Ok(proof)
+}
+
+/// Proves with the layout of `variant` that `f(n)` is the `n`-th term of the sequence starting
+/// at `seeds`, verifies the proof against the public inputs `[f(0), f(1), f(n)]`, and returns
+/// `f(n)` with the proof bytes. `n` is at least 3, the shortest sequence every layout fits;
+/// a smaller one is an `InvalidInput` error.
+pub(crate) fn prove_fib(variant: FibVariant, seeds: (u64, u64), n: usize) -> Result<(Fp, Vec<u8>), Error> {
+    if n < 3 {
+        let message = format!("the layouts need at least f(3), not f({})", n);
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, message).into());
+    }
+
+    let out = fib_term(n, seeds);
+    let instance = vec![Fp::from(seeds.0), Fp::from(seeds.1), out];
+
+    let proof = match variant {
+        FibVariant::V1 => prove_circuit(
+            example1::MyLengthCircuit { n, _marker: PhantomData },
+            &instance
+        )?,
+        FibVariant::V2 => prove_circuit(
+            example2::MyLengthCircuit { n, _marker: PhantomData },
+            &instance
+        )?,
+        FibVariant::V3 => prove_circuit(
+            example3::MyLengthCircuit { n, _marker: PhantomData },
+            &instance
+        )?,
+    };
+
+    Ok((out, proof))
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::Error;
+    use crate::fibonacci::{
+        example1, example2, example3, example4, fib_instances, fib_reference, fib_term, mock_circuit, prove_circuit,
+        prove_fib, FibVariant,
+    };
+    use crate::utils::{proof_size_bytes, region_rows, report_stats};
+
+    #[test]
+    fn test_report_stats() {
+        let stats = [
+            report_stats(4, &example1::MyCircuit::<Fp>::default()),
+            report_stats(4, &example2::MyCircuit::<Fp>::default()),
+            report_stats(4, &example3::MyCircuit::<Fp>::default()),
+            report_stats(4, &example4::MyCircuit::<Fp>::default()),
+        ];
+        for (i, stats) in stats.iter().enumerate() {
+            println!("example{}: {:?}", i + 1, stats);
+        }
+
+        // example3 lays the sequence out down a single column
+        assert_eq!(stats[0].advice_columns, 3);
+        assert_eq!(stats[2].advice_columns, 1);
+        assert!(stats[2].advice_columns < stats[0].advice_columns);
+        assert!(stats.iter().all(|stats| stats.gates >= 1));
+    }
+
+    #[test]
+    fn test_fib_reference() {
+        assert_eq!(fib_reference(10, (1, 1))[9], Fp::from(55));
+        assert_eq!(fib_reference(10, (2, 3))[9], Fp::from(144));
+        assert_eq!(fib_reference(1, (1, 1)), vec![Fp::one()]);
+        assert_eq!(fib_instances(10, (1, 1)), vec![Fp::one(), Fp::one(), Fp::from(55)]);
+        assert_eq!(fib_term(9, (1, 1)), Fp::from(55));
+    }
+
+    #[test]
+    fn test_short_instances() {
+        // the output row is missing, which is reported before proving or mock proving
+        let instance = vec![Fp::one(), Fp::one()];
+
+        let result = prove_circuit(example1::MyCircuit::<Fp>::default(), &instance);
+        assert!(matches!(result, Err(Error::InvalidInstances)));
+        let result = prove_circuit(example2::MyCircuit::<Fp>::default(), &instance);
+        assert!(matches!(result, Err(Error::InvalidInstances)));
+        let result = prove_circuit(example3::MyCircuit::<Fp>::default(), &instance);
+        assert!(matches!(result, Err(Error::InvalidInstances)));
+
+        let result = mock_circuit(4, &example1::MyCircuit::<Fp>::default(), instance.clone());
+        assert!(matches!(result, Err(Error::InvalidInstances)));
+        let result = mock_circuit(4, &example2::MyCircuit::<Fp>::default(), instance.clone());
+        assert!(matches!(result, Err(Error::InvalidInstances)));
+        let result = mock_circuit(4, &example3::MyCircuit::<Fp>::default(), instance);
+        assert!(matches!(result, Err(Error::InvalidInstances)));
+    }
+
+    #[test]
+    fn test_prove_fib() {
+        for (seeds, n) in [((1, 1), 10), ((2, 3), 11)] {
+            for variant in FibVariant::ALL {
+                let (out, proof) = prove_fib(variant, seeds, n).unwrap();
+                assert_eq!(out, fib_term(n, seeds), "{:?}", variant);
+                assert!(!proof.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_prove_fib_short() {
+        for n in 0..3 {
+            for variant in FibVariant::ALL {
+                let result = prove_fib(variant, (1, 1), n);
+                assert!(matches!(result, Err(Error::Transcript(_))), "{:?} f({})", variant, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_proof_size() {
+        let instances = vec![fib_instances(10, (1, 1))];
+        let size1 = proof_size_bytes(4, &example1::MyCircuit::<Fp>::default(), instances.clone());
+        let size3 = proof_size_bytes(
+            4,
+            &example3::MyCircuit::<Fp>::default(),
+            instances.clone()
+        );
+
+        // a few dozen commitments and evaluations of 32 bytes each
+        for size in [size1, size3] {
+            assert!((1 << 9..1 << 14).contains(&size), "{} bytes", size);
+        }
+    }
+
+    /// The regions each example lays out, with the rows each spans, as `region_rows` reports
+    /// them. The layouts are compared region by region rather than as a hash of the rendered
+    /// `dev-graph` image, whose pixels also change with the plotters and font versions.
+    ///
+    /// To regenerate after an intended layout change, run
+    /// `cargo test --lib fibonacci::tests::test_layouts` and replace the entry of the example
+    /// the failing assertion names with the `left` value it prints.
+    const LAYOUTS: [&[(&str, usize)]; 4] = [
+        &[
+            ("first row", 1),
+            ("next row", 1),
+            ("next row", 1),
+            ("next row", 1),
+            ("next row", 1),
+            ("next row", 1),
+            ("next row", 1),
+            ("next row", 1),
+        ],
+        &[("entire fibonacci table", 5)],
+        &[("entire fibonacci table", 10)],
+        &[("f(a, b, c) = if a=b {c} else {a-b}", 1)],
+    ];
+
+    #[test]
+    fn test_layouts() {
+        let layouts = [
+            region_rows(&example1::MyCircuit::<Fp>::default()),
+            region_rows(&example2::MyCircuit::<Fp>::default()),
+            region_rows(&example3::MyCircuit::<Fp>::default()),
+            region_rows(&example4::MyCircuit::<Fp>::default()),
+        ];
+
+        for (i, (layout, expected)) in layouts.iter().zip(LAYOUTS).enumerate() {
+            let expected: Vec<_> = expected.iter().map(|(name, rows)| (name.to_string(), *rows)).collect();
+            assert_eq!(*layout, expected, "the layout of example{} changed", i + 1);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+/// Sums a column of values with a running sum down the rows: the first row starts the
+/// sum, and every following row adds its value to the sum of the row above.
+///```txt
+///        value  |  acc              |  q_first  |  q_rest  |  instance
+///       ---------------------------------------------------------------
+///         v_0   |  v_0              |     1     |    0     |    sum
+///         v_1   |  acc_prev + v_1   |     0     |    1     |
+///         ..    |   ..              |     0     |    1     |
+///         v_n   |  sum(v_i)         |     0     |    1     |
+///```
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AccumulateConfig {
+    value: Column<Advice>,
+    acc: Column<Advice>,
+    q_first: Selector,
+    q_rest: Selector,
+    instance: Column<Instance>,
+}
+
+pub(crate) struct AccumulateChip<F: FieldExt> {
+    config: AccumulateConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> AccumulateChip<F> {
+    pub(crate) fn construct(config: AccumulateConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> AccumulateConfig {
+        let value = meta.advice_column();
+        let acc = meta.advice_column();
+        let q_first = meta.selector();
+        let q_rest = meta.selector();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(acc);
+        meta.enable_equality(instance);
+
+        meta.create_gate("accumulate", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let q_rest = meta.query_selector(q_rest);
+            let value = meta.query_advice(value, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+
+            vec![
+                q_first * (acc.clone() - value.clone()),
+                q_rest * (acc - (acc_prev + value)),
+            ]
+        });
+
+        AccumulateConfig {
+            value,
+            acc,
+            q_first,
+            q_rest,
+            instance,
+        }
+    }
+
+    /// Sums `values`, returning the cell of the sum.
+    pub(crate) fn assign(&self, mut layouter: impl Layouter<F>, values: &[Value<F>])
+        -> Result<AssignedCell<F, F>, Error> {
+        assert!(!values.is_empty());
+
+        layouter.assign_region(
+            || "accumulate",
+            |mut region| {
+                let mut acc = Value::known(F::zero());
+                let mut cell = None;
+                for (row, value) in values.iter().enumerate() {
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_rest.enable(&mut region, row)?;
+                    }
+
+                    region.assign_advice(|| "value", self.config.value, row, || *value)?;
+
+                    acc = acc + *value;
+                    cell = Some(region.assign_advice(|| "acc", self.config.acc, row, || acc)?);
+                }
+
+                Ok(cell.unwrap())
+            }
+        )
+    }
+
+    pub(crate) fn expose_public(&self, mut layouter: impl Layouter<F>, cell: &AssignedCell<F, F>, row: usize)
+        -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    values: Vec<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = AccumulateConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            values: vec![F::zero(); self.values.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        AccumulateChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = AccumulateChip::construct(config);
+
+        let values: Vec<_> = self.values.iter().map(|v| Value::known(*v)).collect();
+        let sum = chip.assign(layouter.namespace(|| "accumulate"), &values)?;
+
+        chip.expose_public(layouter.namespace(|| "expose sum"), &sum, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::fibonacci::accumulate::MyCircuit;
+
+    #[test]
+    fn test_accumulate() {
+        let circuit = MyCircuit {
+            values: vec![Fp::from(1), Fp::from(2), Fp::from(3), Fp::from(4)],
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(10)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(11)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_single_value() {
+        let circuit = MyCircuit {
+            values: vec![Fp::from(7)],
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(7)]]).unwrap();
+        prover.assert_satisfied();
+    }
+}
@@ -0,0 +1,204 @@
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+/// Computes the dot product of two vectors with a multiply-accumulate gate: like
+/// `AccumulateChip`, but each row adds the product `a_i * b_i` to the running sum.
+///```txt
+///        a    |  b    |  acc                    |  q_first  |  q_rest  |  instance
+///       ---------------------------------------------------------------------------
+///        a_0  |  b_0  |  a_0 * b_0              |     1     |    0     |    out
+///        a_1  |  b_1  |  acc_prev + a_1 * b_1   |     0     |    1     |
+///        ..   |  ..   |   ..                    |     0     |    1     |
+///        a_n  |  b_n  |  sum(a_i * b_i)         |     0     |    1     |
+///```
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DotProductConfig {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    acc: Column<Advice>,
+    q_first: Selector,
+    q_rest: Selector,
+    instance: Column<Instance>,
+}
+
+pub(crate) struct DotProductChip<F: FieldExt> {
+    config: DotProductConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> DotProductChip<F> {
+    pub(crate) fn construct(config: DotProductConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> DotProductConfig {
+        let [a, b, acc] = [(); 3].map(|_| meta.advice_column());
+        let q_first = meta.selector();
+        let q_rest = meta.selector();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(acc);
+        meta.enable_equality(instance);
+
+        meta.create_gate("multiply accumulate", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let q_rest = meta.query_selector(q_rest);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+
+            vec![
+                q_first * (acc.clone() - a.clone() * b.clone()),
+                q_rest * (acc - (acc_prev + a * b)),
+            ]
+        });
+
+        DotProductConfig {
+            a,
+            b,
+            acc,
+            q_first,
+            q_rest,
+            instance,
+        }
+    }
+
+    /// Returns the cell of `sum(a[i] * b[i])`.
+    pub(crate) fn assign(&self, layouter: impl Layouter<F>, a: &[F], b: &[F]) -> Result<AssignedCell<F, F>, Error> {
+        let sums: Vec<_> = a
+            .iter()
+            .zip(b)
+            .scan(F::zero(), |acc, (a, b)| {
+                *acc += *a * b;
+                Some(Value::known(*acc))
+            })
+            .collect();
+        self.assign_sums(layouter, a, b, &sums)
+    }
+
+    /// `assign` with the running sums witnessed as `sums`.
+    fn assign_sums(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &[F],
+        b: &[F],
+        sums: &[Value<F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(!a.is_empty());
+        assert_eq!(a.len(), b.len());
+        assert_eq!(a.len(), sums.len());
+
+        layouter.assign_region(
+            || "dot product",
+            |mut region| {
+                let mut cell = None;
+                for (row, ((a, b), sum)) in a.iter().zip(b).zip(sums).enumerate() {
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_rest.enable(&mut region, row)?;
+                    }
+
+                    region.assign_advice(|| "a", self.config.a, row, || Value::known(*a))?;
+                    region.assign_advice(|| "b", self.config.b, row, || Value::known(*b))?;
+                    cell = Some(region.assign_advice(|| "acc", self.config.acc, row, || *sum)?);
+                }
+
+                Ok(cell.unwrap())
+            }
+        )
+    }
+
+    pub(crate) fn expose_public(&self, mut layouter: impl Layouter<F>, cell: &AssignedCell<F, F>, row: usize)
+        -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Exposes `a . b` at instance row 0. `corrupt` adds one to the running sum at that row.
+#[derive(Default)]
+struct MyCircuit<F> {
+    a: Vec<F>,
+    b: Vec<F>,
+    corrupt: Option<usize>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = DotProductConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            a: vec![F::zero(); self.a.len()],
+            b: vec![F::zero(); self.b.len()],
+            corrupt: self.corrupt,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        DotProductChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = DotProductChip::construct(config);
+
+        let out = match self.corrupt {
+            None => chip.assign(layouter.namespace(|| "dot product"), &self.a, &self.b)?,
+            Some(row) => {
+                let mut acc = F::zero();
+                let sums: Vec<_> = self.a.iter().zip(&self.b).enumerate().map(|(i, (a, b))| {
+                    acc += *a * b;
+                    if i == row {
+                        acc += F::one();
+                    }
+                    Value::known(acc)
+                }).collect();
+                chip.assign_sums(layouter.namespace(|| "dot product"), &self.a, &self.b, &sums)?
+            }
+        };
+
+        chip.expose_public(layouter.namespace(|| "expose out"), &out, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::fibonacci::dot_product::MyCircuit;
+
+    fn circuit(corrupt: Option<usize>) -> MyCircuit<Fp> {
+        MyCircuit {
+            a: vec![Fp::from(1), Fp::from(2), Fp::from(3)],
+            b: vec![Fp::from(4), Fp::from(5), Fp::from(6)],
+            corrupt,
+        }
+    }
+
+    #[test]
+    fn test_dot_product() {
+        let prover = MockProver::run(4, &circuit(None), vec![vec![Fp::from(32)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(4, &circuit(None), vec![vec![Fp::from(33)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_corrupted_product() {
+        // the corrupted sum carries through to the output, 33, which the gate on row 1 rejects
+        for output in [32, 33] {
+            let prover = MockProver::run(4, &circuit(Some(1)), vec![vec![Fp::from(output)]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
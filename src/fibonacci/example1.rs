@@ -2,61 +2,245 @@ use std::marker::PhantomData;
 use halo2_proofs::{
     circuit::*,
     plonk::*,
-    poly::Rotation,
+    poly::{commitment::Params, Rotation},
     arithmetic::FieldExt,
+    pasta::{EqAffine, Fp},
 };
+use rand_core::OsRng;
+use crate::StudyChip;
+use crate::fibonacci::fib_term;
+use crate::prover::{make_reader, make_writer};
+use crate::utils::minimum_k;
+
+mod columns;
 
 #[derive(Copy, Clone)]
-struct FibonacciConfig {
+pub(crate) struct FibonacciConfig {
     col_a: Column<Advice>,
     col_b: Column<Advice>,
     col_c: Column<Advice>,
     selector: Selector,
     instance: Column<Instance>,
+    /// The constant `k` the add gate adds on each row, zero where it is not assigned.
+    constant: Column<Fixed>,
+    /// The carry column, a table of `[0, modulus)` that `c` is looked up in, and the modulus
+    /// `c` is reduced by, when the add gate tracks overflow.
+    carry: Option<(Column<Advice>, TableColumn, u64)>,
 }
 
 #[derive(Copy, Clone)]
-struct FibonacciChip<F: FieldExt> {
+pub(crate) struct FibonacciChip<F: FieldExt> {
     config: FibonacciConfig,
     _maker: PhantomData<F>,
 }
 
-impl<F: FieldExt> FibonacciChip<F> {
-    fn construct(config: FibonacciConfig) -> Self {
+impl<F: FieldExt> StudyChip<F> for FibonacciChip<F> {
+    type Config = FibonacciConfig;
+    type Output = AssignedCell<F, F>;
+
+    fn construct(config: Self::Config) -> Self {
         Self {
             config,
             _maker: PhantomData
         }
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> FibonacciConfig {
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        Self::configure_inner(meta, None)
+    }
+}
+
+impl<F: FieldExt> FibonacciChip<F> {
+    /// Like `configure`, but the add gate computes `c = a + b + k mod modulus` and witnesses
+    /// whether the integer sum overflowed in a boolean `carry`, `a + b + k = c + carry * modulus`.
+    /// `c` is looked up in `[0, modulus)`, so that for `a + b + k < 2 * modulus` the carry cannot
+    /// be hidden by witnessing the unreduced sum. The table is loaded by `load_table`.
+    fn configure_with_carry(meta: &mut ConstraintSystem<F>, modulus: u64) -> FibonacciConfig {
+        Self::configure_inner(meta, Some(modulus))
+    }
+
+    fn configure_inner(meta: &mut ConstraintSystem<F>, modulus: Option<u64>) -> FibonacciConfig {
         let [col_a, col_b, col_c] = [(); 3].map(|_| meta.advice_column());
-        let selector = meta.selector();
+        // a lookup needs a complex selector
+        let selector = if modulus.is_some() { meta.complex_selector() } else { meta.selector() };
         let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        let carry = modulus.map(|modulus| (meta.advice_column(), meta.lookup_table_column(), modulus));
 
         meta.enable_equality(col_a);
         meta.enable_equality(col_b);
         meta.enable_equality(col_c);
         meta.enable_equality(instance);
+        if let Some((col_carry, table, _)) = carry {
+            meta.enable_equality(col_carry);
+
+            // c is in [0, modulus)
+            meta.lookup(|meta| {
+                let s = meta.query_selector(selector);
+                let c = meta.query_advice(col_c, Rotation::cur());
+
+                vec![(s * c, table)]
+            });
+        }
+
+        let config = FibonacciConfig {
+            col_a,
+            col_b,
+            col_c,
+            selector,
+            instance,
+            constant,
+            carry,
+        };
+        Self::create_add_gate(meta, &config);
+        config
+    }
+
+    /// Configures a second chip over the `col_a` and `col_b` (and instance and constant) of `other`, with a `c`
+    /// column of its own. Its selector must differ from `other`'s: a gate applies on every row its
+    /// selector is enabled, so with a shared selector the rows of each chip would also have to
+    /// satisfy the other chip's gate, over a `c` column the other chip never assigned.
+    fn configure_shared(meta: &mut ConstraintSystem<F>, other: &FibonacciConfig) -> FibonacciConfig {
+        let col_c = meta.advice_column();
+        let selector = meta.selector();
+
+        meta.enable_equality(col_c);
+
+        let config = FibonacciConfig {
+            col_a: other.col_a,
+            col_b: other.col_b,
+            col_c,
+            selector,
+            instance: other.instance,
+            constant: other.constant,
+            carry: None,
+        };
+        Self::create_add_gate(meta, &config);
+        config
+    }
+
+    fn create_add_gate(meta: &mut ConstraintSystem<F>, config: &FibonacciConfig) {
+        let FibonacciConfig { col_a, col_b, col_c, selector, constant, carry, .. } = *config;
 
         meta.create_gate("add", |meta| {
             let a = meta.query_advice(col_a, Rotation::cur());
             let b = meta.query_advice(col_b, Rotation::cur());
             let c = meta.query_advice(col_c, Rotation::cur());
+            let k = meta.query_fixed(constant, Rotation::cur());
             let s = meta.query_selector(selector);
-            vec![s * (a + b - c)]
+            match carry {
+                None => vec![s * (a + b + k - c)],
+                Some((col_carry, _, modulus)) => {
+                    let carry = meta.query_advice(col_carry, Rotation::cur());
+                    let modulus = Expression::Constant(F::from(modulus));
+                    vec![
+                        s.clone() * carry.clone() * (Expression::Constant(F::one()) - carry.clone()),
+                        s * (a + b + k - c - carry * modulus),
+                    ]
+                }
+            }
         });
+    }
 
-        FibonacciConfig {
-            col_a,
-            col_b,
-            col_c,
-            selector,
-            instance,
+    /// Loads the table of `[0, modulus)` when the add gate tracks overflow.
+    fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        match self.config.carry {
+            None => Ok(()),
+            Some((_, table, modulus)) => layouter.assign_table(
+                || "carry range table",
+                |mut table_region| {
+                    for i in 0..modulus {
+                        table_region.assign_cell(|| "value", table, i as usize, || Value::known(F::from(i)))?;
+                    }
+
+                    Ok(())
+                }
+            ),
         }
     }
 
-    fn assign_first_row(&self, mut layouter: impl Layouter<F>)
+    /// Assigns `k` and `c` for the add gate on the region's first row, together with the carry if tracked.
+    fn assign_sum(&self, region: &mut Region<'_, F>, a: Value<F>, b: Value<F>, k: F)
+        -> Result<(AssignedCell<F, F>, Option<AssignedCell<F, F>>), Error> {
+        region.assign_fixed(|| "k", self.config.constant, 0, || Value::known(k))?;
+
+        match self.config.carry {
+            None => {
+                let c_cell = region.assign_advice(|| "a + b + k", self.config.col_c, 0, || a + b + Value::known(k))?;
+                Ok((c_cell, None))
+            }
+            Some((col_carry, _, modulus)) => {
+                // the operands are small integers, so the sum is taken over their lower 128 bits
+                let sum = a.zip(b).map(|(a, b)| a.get_lower_128() + b.get_lower_128() + k.get_lower_128());
+                let overflow = sum.map(|sum| sum >= modulus as u128);
+                let c = sum.zip(overflow).map(|(sum, overflow)| {
+                    let c = if overflow { sum - modulus as u128 } else { sum };
+                    F::from_u128(c)
+                });
+
+                let carry = overflow.map(|overflow| if overflow { F::one() } else { F::zero() });
+                self.assign_carried_sum(region, c, carry)
+            }
+        }
+    }
+
+    /// Assigns `c` and `carry` on the region's first row as given.
+    fn assign_carried_sum(&self, region: &mut Region<'_, F>, c: Value<F>, carry: Value<F>)
+        -> Result<(AssignedCell<F, F>, Option<AssignedCell<F, F>>), Error> {
+        let (col_carry, _, _) = self.config.carry.expect("configured with a carry");
+
+        let c_cell = region.assign_advice(|| "a + b + k", self.config.col_c, 0, || c)?;
+        let carry_cell = region.assign_advice(|| "carry", col_carry, 0, || carry)?;
+        Ok((c_cell, Some(carry_cell)))
+    }
+
+    /// Assigns a single add row of witnessed `a` and `b`, returning `c` and the carry if tracked.
+    /// `c_carry` overrides the honest `c` and carry, which needs the carry tracked.
+    fn assign_add(&self, mut layouter: impl Layouter<F>, a: Value<F>, b: Value<F>, c_carry: Option<(F, F)>)
+        -> Result<(AssignedCell<F, F>, Option<AssignedCell<F, F>>), Error> {
+        layouter.assign_region(
+            || "add",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "a", self.config.col_a, 0, || a)?;
+                region.assign_advice(|| "b", self.config.col_b, 0, || b)?;
+
+                match c_carry {
+                    None => self.assign_sum(&mut region, a, b, F::zero()),
+                    Some((c, carry)) => {
+                        region.assign_fixed(|| "k", self.config.constant, 0, || Value::known(F::zero()))?;
+                        self.assign_carried_sum(&mut region, Value::known(c), Value::known(carry))
+                    }
+                }
+            }
+        )
+    }
+
+    /// Like `assign_first_row`, but with the seeds witnessed privately instead of read from the instance.
+    pub(crate) fn assign_private_first_row(&self, mut layouter: impl Layouter<F>, a: Value<F>, b: Value<F>)
+        -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "first row",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                let a_cell = region.assign_advice(|| "f(0)", self.config.col_a, 0, || a)?;
+                let b_cell = region.assign_advice(|| "f(1)", self.config.col_b, 0, || b)?;
+                let (c_cell, _) = self.assign_sum(&mut region, a, b, F::zero())?;
+
+                Ok((a_cell, b_cell, c_cell))
+            },
+        )
+    }
+
+    fn assign_first_row(&self, layouter: impl Layouter<F>)
+        -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        self.assign_first_row_with_constant(layouter, F::zero())
+    }
+
+    /// Like `assign_first_row`, but with `c = a + b + k`.
+    fn assign_first_row_with_constant(&self, mut layouter: impl Layouter<F>, k: F)
         -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
         layouter.assign_region(
             || "first row",
@@ -81,11 +265,11 @@ impl<F: FieldExt> FibonacciChip<F> {
                     0,
                 )?;
 
-                let c_cell = region.assign_advice(
-                    || "a + b",
-                    self.config.col_c,
-                    0,
-                    || a_cell.value().copied() + b_cell.value(),
+                let (c_cell, _) = self.assign_sum(
+                    &mut region,
+                    a_cell.value().copied(),
+                    b_cell.value().copied(),
+                    k,
                 )?;
 
                 Ok((a_cell, b_cell, c_cell))
@@ -93,37 +277,62 @@ impl<F: FieldExt> FibonacciChip<F> {
         )
     }
 
-    fn assign_row(&self, mut layouter: impl Layouter<F>, prev_b: &AssignedCell<F, F>, prev_c: &AssignedCell<F, F>)
+    pub(crate) fn assign_row(&self, layouter: impl Layouter<F>, prev_b: &AssignedCell<F, F>, prev_c: &AssignedCell<F, F>)
         -> Result<AssignedCell<F, F>, Error> {
+        self.assign_row_with_constant(layouter, prev_b, prev_c, F::zero())
+    }
+
+    /// Like `assign_row`, but with `k` in the constant column, so that `c = a + b + k`.
+    pub(crate) fn assign_row_with_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        prev_b: &AssignedCell<F, F>,
+        prev_c: &AssignedCell<F, F>,
+        k: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.assign_row_values(
+            layouter,
+            (prev_b, prev_b.value().copied()),
+            (prev_c, prev_c.value().copied()),
+            k,
+        )
+    }
+
+    /// `assign_row_with_constant` with the copies of `prev_b` and `prev_c` witnessed as the
+    /// given values, which the equality constraints bind to the values of the cells.
+    fn assign_row_values(
+        &self,
+        mut layouter: impl Layouter<F>,
+        (prev_b, a): (&AssignedCell<F, F>, Value<F>),
+        (prev_c, b): (&AssignedCell<F, F>, Value<F>),
+        k: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
         layouter.assign_region(
             || "next row",
             |mut region| {
 
                 self.config.selector.enable(&mut region, 0)?;
 
-                prev_b.copy_advice(|| "a", &mut region, self.config.col_a, 0)?;
-                prev_c.copy_advice(|| "b", &mut region, self.config.col_b, 0)?;
+                let a_cell = region.assign_advice(|| "a", self.config.col_a, 0, || a)?;
+                let b_cell = region.assign_advice(|| "b", self.config.col_b, 0, || b)?;
+                region.constrain_equal(prev_b.cell(), a_cell.cell())?;
+                region.constrain_equal(prev_c.cell(), b_cell.cell())?;
 
-                let c_cell = region.assign_advice(
-                    || "c",
-                    self.config.col_c,
-                    0,
-                    || prev_b.value().copied() + prev_c.value(),
-                )?;
+                let (c_cell, _) = self.assign_sum(&mut region, a, b, k)?;
 
                 Ok(c_cell)
             }
         )
     }
 
-    fn expose_public(&self, mut layouter: impl Layouter<F>, cell: &AssignedCell<F, F>, row: usize)
+    pub(crate) fn expose_public(&self, mut layouter: impl Layouter<F>, cell: &AssignedCell<F, F>, row: usize)
         -> Result<(), Error> {
         layouter.constrain_instance(cell.cell(), self.config.instance, row)
     }
 }
 
 #[derive(Copy, Clone, Default)]
-struct MyCircuit<F: FieldExt>(PhantomData<F>);
+pub(crate) struct MyCircuit<F: FieldExt>(PhantomData<F>);
 
 impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     type Config = FibonacciConfig;
@@ -162,21 +371,426 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     }
 }
 
+/// `MyCircuit` exposing `f(n)` instead of `f(9)`, for `n >= 2`.
+#[derive(Copy, Clone, Default)]
+pub(crate) struct MyLengthCircuit<F: FieldExt> {
+    pub(crate) n: usize,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyLengthCircuit<F> {
+    type Config = FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        *self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FibonacciChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FibonacciChip::construct(config);
+
+        let (_, mut prev_b, mut prev_c) = chip.assign_first_row(
+            layouter.namespace(|| "assign first row")
+        )?;
+
+        for _i in 3..=self.n {
+            let c_cell = chip.assign_row(
+                layouter.namespace(|| "assign next row"),
+                &prev_b,
+                &prev_c,
+            )?;
+            prev_b = prev_c;
+            prev_c = c_cell;
+        }
+
+        chip.expose_public(
+            layouter.namespace(|| "expose public"),
+            &prev_c,
+            2
+        )
+    }
+}
+
+/// Proves `MyCircuit` for the seeds `a` and `b` with a real prover, verifies the proof
+/// against the public inputs `[a, b, f(9)]`, and returns the proof bytes.
+pub(crate) fn prove_and_verify(a: u64, b: u64) -> Result<Vec<u8>, Error> {
+    let circuit = MyCircuit::<Fp>(PhantomData);
+    let public_input = [Fp::from(a), Fp::from(b), fib_term(9, (a, b))];
+
+    let k = minimum_k(&circuit, vec![public_input.to_vec()]);
+    let params: Params<EqAffine> = Params::new(k);
+    let vk = keygen_vk(&params, &circuit)?;
+    let pk = keygen_pk(&params, vk, &circuit)?;
+
+    let mut transcript = make_writer();
+    create_proof(&params, &pk, &[circuit], &[&[&public_input]], OsRng, &mut transcript)?;
+    let proof = transcript.finalize();
+
+    let strategy = SingleVerifier::new(&params);
+    let mut transcript = make_reader(&proof);
+    verify_proof(&params, pk.get_vk(), strategy, &[&[&public_input]], &mut transcript)?;
+
+    Ok(proof)
+}
+
+/// `MyCircuit` with `k` added at every step, `f(n) = f(n - 2) + f(n - 1) + k`, exposing `f(9)`.
+#[derive(Copy, Clone, Default)]
+struct MyShiftedCircuit<F: FieldExt> {
+    k: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyShiftedCircuit<F> {
+    type Config = FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        *self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FibonacciChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FibonacciChip::construct(config);
+
+        let (_, mut prev_b, mut prev_c) = chip.assign_first_row_with_constant(
+            layouter.namespace(|| "assign first row"),
+            self.k
+        )?;
+
+        for _i in 3..10 {
+            let c_cell = chip.assign_row_with_constant(
+                layouter.namespace(|| "assign next row"),
+                &prev_b,
+                &prev_c,
+                self.k
+            )?;
+            prev_b = prev_c;
+            prev_c = c_cell;
+        }
+
+        chip.expose_public(
+            layouter.namespace(|| "expose public"),
+            &prev_c,
+            2
+        )
+    }
+}
+
+/// `MyCircuit`, with the copy at index `corrupt` of the chain (two per row) witnessed off by one.
+#[derive(Copy, Clone, Default)]
+struct MyCopyChainCircuit<F: FieldExt> {
+    corrupt: Option<usize>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCopyChainCircuit<F> {
+    type Config = FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FibonacciChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FibonacciChip::construct(config);
+        let witness = |cell: &AssignedCell<F, F>, i: usize| {
+            let offset = if self.corrupt == Some(i) { F::one() } else { F::zero() };
+            cell.value().map(|v| *v + offset)
+        };
+
+        let (_, mut prev_b, mut prev_c) = chip.assign_first_row(
+            layouter.namespace(|| "assign first row")
+        )?;
+
+        for i in 0..7 {
+            let c_cell = chip.assign_row_values(
+                layouter.namespace(|| "assign next row"),
+                (&prev_b, witness(&prev_b, 2 * i)),
+                (&prev_c, witness(&prev_c, 2 * i + 1)),
+                F::zero(),
+            )?;
+            prev_b = prev_c;
+            prev_c = c_cell;
+        }
+
+        chip.expose_public(
+            layouter.namespace(|| "expose public"),
+            &prev_c,
+            2
+        )
+    }
+}
+
+/// Two sequences laid out by two chips over shared `a` and `b` columns: the first from the
+/// public seeds at instance rows 0 and 1, exposing `f(9)` at row 2, the second from private
+/// `seeds`, exposing its `f(9)` at row 3.
+#[derive(Copy, Clone, Default)]
+struct MySharedCircuit<F: FieldExt> {
+    seeds: [F; 2],
+}
+
+impl<F: FieldExt> Circuit<F> for MySharedCircuit<F> {
+    type Config = (FibonacciConfig, FibonacciConfig);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let config = FibonacciChip::configure(meta);
+        let shared = FibonacciChip::configure_shared(meta, &config);
+        (config, shared)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FibonacciChip::construct(config.0);
+        let shared_chip = FibonacciChip::construct(config.1);
+
+        let (_, mut prev_b, mut prev_c) = chip.assign_first_row(
+            layouter.namespace(|| "assign first row")
+        )?;
+        let (_, mut shared_b, mut shared_c) = shared_chip.assign_private_first_row(
+            layouter.namespace(|| "assign shared first row"),
+            Value::known(self.seeds[0]),
+            Value::known(self.seeds[1]),
+        )?;
+
+        for _i in 3..10 {
+            let c_cell = chip.assign_row(layouter.namespace(|| "assign next row"), &prev_b, &prev_c)?;
+            prev_b = prev_c;
+            prev_c = c_cell;
+
+            let c_cell = shared_chip.assign_row(layouter.namespace(|| "assign shared next row"), &shared_b, &shared_c)?;
+            shared_b = shared_c;
+            shared_c = c_cell;
+        }
+
+        chip.expose_public(layouter.namespace(|| "expose public"), &prev_c, 2)?;
+        shared_chip.expose_public(layouter.namespace(|| "expose shared public"), &shared_c, 3)
+    }
+}
+
+/// The modulus of `MyCarryCircuit`, small enough for a Fibonacci step to overflow it.
+const CARRY_MODULUS: u64 = 16;
+
+/// Adds two private values modulo `CARRY_MODULUS`, exposing `[c, carry]`. `c_carry` overrides
+/// the honest `c` and carry.
+#[derive(Copy, Clone, Default)]
+struct MyCarryCircuit<F> {
+    a: Value<F>,
+    b: Value<F>,
+    c_carry: Option<(F, F)>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCarryCircuit<F> {
+    type Config = FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FibonacciChip::configure_with_carry(meta, CARRY_MODULUS)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FibonacciChip::construct(config);
+        chip.load_table(&mut layouter)?;
+
+        let (c_cell, carry_cell) = chip.assign_add(
+            layouter.namespace(|| "add"),
+            self.a,
+            self.b,
+            self.c_carry,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "expose c"), &c_cell, 0)?;
+        chip.expose_public(
+            layouter.namespace(|| "expose carry"),
+            &carry_cell.expect("configured with a carry"),
+            1
+        )
+    }
+}
+
+/// A single add row witnessing `a = 1, b = 1, c = 3`, which breaks the add gate.
+#[derive(Copy, Clone, Default)]
+struct MyBrokenAddCircuit<F: FieldExt>(PhantomData<F>);
+
+impl<F: FieldExt> Circuit<F> for MyBrokenAddCircuit<F> {
+    type Config = FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FibonacciChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "broken add",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "a", config.col_a, 0, || Value::known(F::one()))?;
+                region.assign_advice(|| "b", config.col_b, 0, || Value::known(F::one()))?;
+                region.assign_advice(|| "c", config.col_c, 0, || Value::known(F::from(3)))?;
+                Ok(())
+            }
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::marker::PhantomData;
     use halo2_proofs::dev::MockProver;
     use halo2_proofs::pasta::Fp;
-    use crate::fibonacci::example1::MyCircuit;
+    use halo2_proofs::circuit::Value;
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+    use crate::fibonacci::example1::{
+        prove_and_verify, MyBrokenAddCircuit, MyCarryCircuit, MyCircuit, MyCopyChainCircuit, MySharedCircuit,
+        MyShiftedCircuit,
+    };
+    use crate::fibonacci::{fib_instances, fib_reference, mock_circuit};
+    use crate::testing::{assert_cells_equal_under_mutation, summarize_failures};
 
     #[test]
     fn test_circuit() {
         let circuit = MyCircuit(PhantomData);
-        let prover = MockProver::run(
+        let prover = mock_circuit(4, &circuit, fib_instances(10, (1, 1))).unwrap();
+
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_constant() {
+        // 1, 1, 3, 5, 9, 15, 25, 41, 67, 109
+        let circuit = MyShiftedCircuit {
+            k: Fp::one(),
+        };
+        let prover = mock_circuit(4, &circuit, vec![Fp::from(1), Fp::from(1), Fp::from(109)]).unwrap();
+        prover.assert_satisfied();
+
+        // f(9) of the plain sequence is not the output
+        let prover = mock_circuit(4, &circuit, fib_instances(10, (1, 1))).unwrap();
+        assert!(prover.verify().is_err());
+
+        // k = 0 is the plain sequence
+        let circuit = MyShiftedCircuit {
+            k: Fp::zero(),
+        };
+        let prover = mock_circuit(4, &circuit, fib_instances(10, (1, 1))).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_prove_and_verify() {
+        let proof = prove_and_verify(1, 1).unwrap();
+        assert!(!proof.is_empty());
+
+        let proof = prove_and_verify(2, 7).unwrap();
+        assert!(!proof.is_empty());
+    }
+
+    #[test]
+    fn test_copy_chain() {
+        assert_cells_equal_under_mutation(
             4,
-            &circuit,
-            vec![vec![Fp::from(1), Fp::from(1), Fp::from(55)]]).unwrap();
+            vec![fib_instances(10, (1, 1))],
+            |corrupt| MyCopyChainCircuit {
+                corrupt,
+                _marker: PhantomData,
+            },
+            14,
+        );
+    }
+
+    #[test]
+    fn test_shared_columns() {
+        // the second chip proves f(9) of 2, 3
+        let circuit = MySharedCircuit {
+            seeds: [Fp::from(2), Fp::from(3)],
+        };
+        let out = fib_reference(10, (2, 3))[9];
+        let mut public_input = fib_instances(10, (1, 1));
+        public_input.push(out);
+        let prover = MockProver::run(5, &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        public_input[3] = out - Fp::one();
+        let prover = MockProver::run(5, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // the second chip adds a single advice column where a separate one would add three
+        let mut meta = ConstraintSystem::<Fp>::default();
+        MySharedCircuit::<Fp>::configure(&mut meta);
+        assert_eq!(meta.num_advice_columns(), 4);
+        assert_eq!(meta.num_instance_columns(), 1);
+
+        let mut meta = ConstraintSystem::<Fp>::default();
+        MyCircuit::<Fp>::configure(&mut meta);
+        MyCircuit::<Fp>::configure(&mut meta);
+        assert_eq!(meta.num_advice_columns(), 6);
+    }
+
+    #[test]
+    fn test_summarize_failures() {
+        let prover = MockProver::run(4, &MyBrokenAddCircuit::<Fp>(PhantomData), vec![vec![]]).unwrap();
+        let summary = summarize_failures(&prover);
+        assert_eq!(summary.get("add"), Some(&1));
+
+        // the honest circuit has nothing to report
+        let circuit = MyCircuit(PhantomData);
+        let prover = mock_circuit(4, &circuit, fib_instances(10, (1, 1))).unwrap();
+        assert!(summarize_failures(&prover).is_empty());
+    }
+
+    #[test]
+    fn test_carry() {
+        // 9 + 10 = 19 = 3 + 1 * 16
+        let circuit = MyCarryCircuit {
+            a: Value::known(Fp::from(9)),
+            b: Value::known(Fp::from(10)),
+            c_carry: None,
+        };
+        let prover = MockProver::run(5, &circuit, vec![vec![Fp::from(3), Fp::one()]]).unwrap();
+        prover.assert_satisfied();
 
+        // the honest witness does not match other outputs
+        let prover = MockProver::run(5, &circuit, vec![vec![Fp::from(19), Fp::zero()]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 9 + 10 = 19 + 0 * 16 satisfies the add gate, but 19 is not reduced
+        let circuit = MyCarryCircuit {
+            c_carry: Some((Fp::from(19), Fp::zero())),
+            ..circuit
+        };
+        let prover = MockProver::run(5, &circuit, vec![vec![Fp::from(19), Fp::zero()]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 3 + 4 does not overflow
+        let circuit = MyCarryCircuit {
+            a: Value::known(Fp::from(3)),
+            b: Value::known(Fp::from(4)),
+            c_carry: None,
+        };
+        let prover = MockProver::run(5, &circuit, vec![vec![Fp::from(7), Fp::zero()]]).unwrap();
         prover.assert_satisfied();
     }
 
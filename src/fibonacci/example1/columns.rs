@@ -0,0 +1,226 @@
+//! The layout of example1 generalized to `COLS` columns. It lives beside example1's
+//! `FibonacciChip` rather than replacing it: that chip assigns one add per region over its
+//! three named columns, and the carried, shared and copy-chain variants build on those
+//! per-row regions, while this one lays the whole table out in a single region whose gate
+//! depends on `COLS`. With `COLS = 3` it proves the same `f(9)` as example1.
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+use crate::StudyChip;
+
+/// The sequence laid out row by row over `COLS` advice columns, so that a wider table
+/// needs fewer rows. `f(0)` and `f(1)` are copied from the instance, and the gate enabled
+/// on a row checks every term it can reach from that row, e.g. with `COLS = 2`:
+///```txt
+///      advice_0  |  advice_1  |  selector  |  instance  |
+///     -----------------------------------------------------
+///        f(0)    |    f(1)    |     1      |    f(0)    |
+///        f(2)    |    f(3)    |     1      |    f(1)    |
+///        f(4)    |    f(5)    |     1      |    out     |
+///        f(6)    |    f(7)    |     1      |            |
+///        f(8)    |    f(9)    |     0      |            |
+///```
+/// When the last row is not full, it is filled with the terms that follow the output.
+#[derive(Copy, Clone)]
+pub(crate) struct FibonacciConfig<const COLS: usize> {
+    advice: [Column<Advice>; COLS],
+    selector: Selector,
+    instance: Column<Instance>,
+}
+
+#[derive(Copy, Clone)]
+pub(crate) struct FibonacciChip<F: FieldExt, const COLS: usize> {
+    config: FibonacciConfig<COLS>,
+    _maker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const COLS: usize> FibonacciChip<F, COLS> {
+    /// Fails to compile for any `COLS` other than 1, 2 or 3 once `configure` is instantiated.
+    const SUPPORTED_COLS: () = assert!(COLS >= 1 && COLS <= 3, "the sequence is laid out over 1, 2 or 3 columns");
+}
+
+impl<F: FieldExt, const COLS: usize> StudyChip<F> for FibonacciChip<F, COLS> {
+    type Config = FibonacciConfig<COLS>;
+    type Output = AssignedCell<F, F>;
+
+    fn construct(config: Self::Config) -> Self {
+        Self {
+            config,
+            _maker: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let () = Self::SUPPORTED_COLS;
+
+        let advice = [(); COLS].map(|_| meta.advice_column());
+        let selector = meta.selector();
+        let instance = meta.instance_column();
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        meta.create_gate("add", |meta| {
+            let s = meta.query_selector(selector);
+            let mut term = |column: usize, rotation: i32| meta.query_advice(advice[column], Rotation(rotation));
+
+            let steps = match COLS {
+                1 => vec![(term(0, 0), term(0, 1), term(0, 2))],
+                2 => vec![
+                    (term(0, 0), term(1, 0), term(0, 1)),
+                    (term(1, 0), term(0, 1), term(1, 1)),
+                ],
+                3 => vec![
+                    (term(0, 0), term(1, 0), term(2, 0)),
+                    (term(1, 0), term(2, 0), term(0, 1)),
+                    (term(2, 0), term(0, 1), term(1, 1)),
+                    (term(0, 1), term(1, 1), term(2, 1)),
+                ],
+                _ => unreachable!("SUPPORTED_COLS rejects {} columns", COLS),
+            };
+
+            steps.into_iter().map(|(a, b, c)| s.clone() * (a + b - c)).collect::<Vec<_>>()
+        });
+
+        FibonacciConfig {
+            advice,
+            selector,
+            instance,
+        }
+    }
+}
+
+impl<F: FieldExt, const COLS: usize> FibonacciChip<F, COLS> {
+    /// The number of rows `nterms` terms take up.
+    pub(crate) fn rows(nterms: usize) -> usize {
+        (nterms + COLS - 1) / COLS
+    }
+
+    /// The number of rows, counted from the first, the gate is enabled on: the gate
+    /// reaches two rows ahead in a single column, and one row ahead otherwise. A table
+    /// too short for the gate to fit has none.
+    fn gate_rows(rows: usize) -> usize {
+        if COLS == 1 { rows.saturating_sub(2) } else { rows.saturating_sub(1) }
+    }
+
+    /// Assigns `f(0)` through `f(nterms - 1)`, returning the cell of the last one. `nterms`
+    /// is at least 2, the two seeds; a shorter table is a synthesis error.
+    fn assign(&self, mut layouter: impl Layouter<F>, nterms: usize) -> Result<AssignedCell<F, F>, Error> {
+        if nterms < 2 {
+            return Err(Error::Synthesis);
+        }
+        let rows = Self::rows(nterms);
+
+        layouter.assign_region(
+            || "entire fibonacci table",
+            |mut region| {
+                for row in 0..Self::gate_rows(rows) {
+                    self.config.selector.enable(&mut region, row)?;
+                }
+
+                let mut cells: Vec<AssignedCell<F, F>> = Vec::with_capacity(rows * COLS);
+                for i in 0..rows * COLS {
+                    let (row, column) = (i / COLS, self.config.advice[i % COLS]);
+                    let cell = if i < 2 {
+                        region.assign_advice_from_instance(|| format!("f({})", i), self.config.instance, i, column, row)?
+                    } else {
+                        region.assign_advice(
+                            || format!("f({})", i),
+                            column,
+                            row,
+                            || cells[i - 2].value().copied() + cells[i - 1].value(),
+                        )?
+                    };
+                    cells.push(cell);
+                }
+
+                Ok(cells.swap_remove(nterms - 1))
+            }
+        )
+    }
+
+    fn expose_public(&self, mut layouter: impl Layouter<F>, cell: &AssignedCell<F, F>, row: usize)
+        -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+struct MyCircuit<F: FieldExt, const COLS: usize>(PhantomData<F>);
+
+impl<F: FieldExt, const COLS: usize> Circuit<F> for MyCircuit<F, COLS> {
+    type Config = FibonacciConfig<COLS>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FibonacciChip::<F, COLS>::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FibonacciChip::<F, COLS>::construct(config);
+        let out_cell = chip.assign(layouter.namespace(|| "entire table"), 10)?;
+
+        chip.expose_public(layouter.namespace(|| "expose public"), &out_cell, 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::marker::PhantomData;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+    use crate::fibonacci::example1::columns::{FibonacciChip, MyCircuit};
+    use crate::fibonacci::fib_instances;
+
+    /// Proves `f(9)` over `COLS` columns, returning the advice columns and rows it takes up.
+    fn prove<const COLS: usize>() -> (usize, usize) {
+        let circuit = MyCircuit::<Fp, COLS>(PhantomData);
+        let prover = MockProver::run(4, &circuit, vec![fib_instances(10, (1, 1))]).unwrap();
+        prover.assert_satisfied();
+
+        let mut instances = fib_instances(10, (1, 1));
+        instances[2] += Fp::one();
+        let prover = MockProver::run(4, &circuit, vec![instances]).unwrap();
+        assert!(prover.verify().is_err());
+
+        let mut meta = ConstraintSystem::<Fp>::default();
+        MyCircuit::<Fp, COLS>::configure(&mut meta);
+        (meta.num_advice_columns(), FibonacciChip::<Fp, COLS>::rows(10))
+    }
+
+    #[test]
+    fn test_one_column() {
+        assert_eq!(prove::<1>(), (1, 10));
+    }
+
+    #[test]
+    fn test_two_columns() {
+        assert_eq!(prove::<2>(), (2, 5));
+    }
+
+    #[test]
+    fn test_short_tables() {
+        // no gate fits, rather than the row count underflowing
+        assert_eq!(FibonacciChip::<Fp, 1>::gate_rows(1), 0);
+        assert_eq!(FibonacciChip::<Fp, 2>::gate_rows(0), 0);
+        assert_eq!(FibonacciChip::<Fp, 1>::gate_rows(2), 0);
+        assert_eq!(FibonacciChip::<Fp, 3>::gate_rows(1), 0);
+    }
+
+    #[test]
+    fn test_three_columns() {
+        // f(10) and f(11) fill the last row
+        assert_eq!(prove::<3>(), (3, 4));
+    }
+}
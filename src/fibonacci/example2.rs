@@ -5,9 +5,10 @@ use halo2_proofs::{
     poly::Rotation,
     arithmetic::FieldExt,
 };
+use crate::StudyChip;
 
 #[derive(Copy, Clone)]
-struct FibonacciConfig {
+pub(crate) struct FibonacciConfig {
     col_a: Column<Advice>,
     col_b: Column<Advice>,
     selector: Selector,
@@ -15,21 +16,24 @@ struct FibonacciConfig {
 }
 
 #[derive(Copy, Clone)]
-struct FibonacciChip<F: FieldExt> {
+pub(crate) struct FibonacciChip<F: FieldExt> {
     config: FibonacciConfig,
     _marker: PhantomData<F>
 }
 
 
-impl<F: FieldExt> FibonacciChip<F> {
-    fn construct(config: FibonacciConfig) -> Self {
+impl<F: FieldExt> StudyChip<F> for FibonacciChip<F> {
+    type Config = FibonacciConfig;
+    type Output = AssignedCell<F, F>;
+
+    fn construct(config: Self::Config) -> Self {
         Self {
             config,
             _marker: PhantomData,
         }
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> FibonacciConfig {
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
         let [col_a, col_b] = [(); 2].map(|_| meta.advice_column());
         let selector = meta.selector();
         let instance = meta.instance_column();
@@ -57,7 +61,9 @@ impl<F: FieldExt> FibonacciChip<F> {
             instance
         }
     }
+}
 
+impl<F: FieldExt> FibonacciChip<F> {
     fn assign_row(&self, mut layouter: impl Layouter<F>, nrows: usize)
         -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
 
@@ -114,7 +120,7 @@ impl<F: FieldExt> FibonacciChip<F> {
 }
 
 #[derive(Copy, Clone, Default)]
-struct MyCircuit<F: FieldExt>(PhantomData<F>);
+pub(crate) struct MyCircuit<F: FieldExt>(PhantomData<F>);
 
 
 impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
@@ -122,7 +128,7 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        MyCircuit::default()
+        Self::default()
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -141,23 +147,55 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     }
 }
 
+/// `MyCircuit` exposing `f(n)` instead of `f(9)`, for `n >= 2`. Each row holds two terms,
+/// so an even `n` is read from the `a` column of the last row and an odd one from `b`.
+#[derive(Copy, Clone, Default)]
+pub(crate) struct MyLengthCircuit<F: FieldExt> {
+    pub(crate) n: usize,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyLengthCircuit<F> {
+    type Config = FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        *self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FibonacciChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FibonacciChip::construct(config);
+
+        let (a_cell, b_cell) = chip.assign_row(
+            layouter.namespace(|| "entire table"),
+            self.n / 2 + 1
+        )?;
+        let out_cell = if self.n % 2 == 0 { a_cell } else { b_cell };
+
+        chip.expose_public(layouter.namespace(|| "out"), &out_cell, 2)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::marker::PhantomData;
-    use halo2_proofs::dev::MockProver;
     use halo2_proofs::pasta::Fp;
     use crate::fibonacci::example2::MyCircuit;
+    use crate::fibonacci::{fib_instances, mock_circuit};
 
     #[test]
     fn test_circuit() {
-        let circuit = MyCircuit(PhantomData);
-        let public_input = vec![
-            vec![Fp::from(1), Fp::from(1), Fp::from(55)]
-        ];
-        let prover = MockProver::run(4, &circuit, public_input).unwrap();
+        let instances = fib_instances(10, (1, 1));
+        let circuit = MyCircuit::<Fp>(PhantomData);
+        let prover = mock_circuit(4, &circuit, instances).unwrap();
         prover.assert_satisfied();
     }
 
+
     #[test]
     #[cfg(feature = "dev-graph")]
     fn test_plot_circuit() {
@@ -167,7 +205,7 @@ mod tests {
         root.fill(&WHITE).unwrap();
         let root = root.titled("Fib 2 Layout", ("sans-serif", 60)).unwrap();
 
-        let circuit = MyCircuit::<Fp>::default();
+        let circuit = MyCircuit::<Fp>(PhantomData);
         halo2_proofs::dev::CircuitLayout::default()
             .render(4, &circuit, &root)
             .unwrap();
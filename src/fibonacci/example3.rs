@@ -5,35 +5,52 @@ use halo2_proofs::{
     poly::Rotation,
     arithmetic::FieldExt,
 };
+use crate::StudyChip;
 
 #[derive(Copy, Clone)]
-struct FibonacciConfig {
+pub(crate) struct FibonacciConfig {
     advice: Column<Advice>,
     selector: Selector,
-    instance: Column<Instance>,
+    /// `None` when configured by `configure_without_instance`.
+    instance: Option<Column<Instance>>,
 }
 
 #[derive(Copy, Clone)]
-struct FibonacciChip<F: FieldExt> {
+pub(crate) struct FibonacciChip<F: FieldExt> {
     config: FibonacciConfig,
     _maker: PhantomData<F>,
 }
 
-impl<F: FieldExt> FibonacciChip<F> {
-    fn construct(config: FibonacciConfig) -> Self {
+impl<F: FieldExt> StudyChip<F> for FibonacciChip<F> {
+    type Config = FibonacciConfig;
+    type Output = AssignedCell<F, F>;
+
+    fn construct(config: Self::Config) -> Self {
         Self {
             config,
             _maker: PhantomData,
         }
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> FibonacciConfig {
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        Self::configure_columns(meta, Some(instance))
+    }
+}
+
+impl<F: FieldExt> FibonacciChip<F> {
+    /// Like `configure`, but without an instance column, for circuits with no public inputs.
+    fn configure_without_instance(meta: &mut ConstraintSystem<F>) -> FibonacciConfig {
+        Self::configure_columns(meta, None)
+    }
+
+    fn configure_columns(meta: &mut ConstraintSystem<F>, instance: Option<Column<Instance>>) -> FibonacciConfig {
         let advice = meta.advice_column();
         let selector = meta.selector();
-        let instance = meta.instance_column();
 
         meta.enable_equality(advice);
-        meta.enable_equality(instance);
 
         meta.create_gate("add", |meta| {
             let a = meta.query_advice(advice, Rotation::cur());
@@ -52,8 +69,14 @@ impl<F: FieldExt> FibonacciChip<F> {
         }
     }
 
+    /// The instance column, which circuits configured without one cannot read or expose to.
+    fn instance(&self) -> Result<Column<Instance>, Error> {
+        self.config.instance.ok_or(Error::Synthesis)
+    }
+
     fn assign_row(&self, mut layouter: impl Layouter<F>, nrows: usize)
         -> Result<AssignedCell<F, F>, Error> {
+        let instance = self.instance()?;
         layouter.assign_region(
             || "entire fibonacci table",
             |mut region| {
@@ -62,53 +85,99 @@ impl<F: FieldExt> FibonacciChip<F> {
 
                 let mut a_cell = region.assign_advice_from_instance(
                     || "f(0)",
-                    self.config.instance,
+                    instance,
                     0,
                     self.config.advice,
                     0
                 )?;
 
-                let mut b_cell = region.assign_advice_from_instance(
+                let b_cell = region.assign_advice_from_instance(
                     || "f(1)",
-                    self.config.instance,
+                    instance,
                     1,
                     self.config.advice,
                     1
                 )?;
 
-                for row in 2..nrows {
-                    if row < nrows - 2 {
-                        self.config.selector.enable(&mut region, row)?;
-                    }
+                self.assign_rest(&mut region, a_cell, b_cell, nrows)
+            }
+        )
+    }
+
+    /// Like `assign_row`, but with the seeds witnessed privately instead of read from the instance.
+    fn assign_private_row(&self, mut layouter: impl Layouter<F>, (a, b): (Value<F>, Value<F>), nrows: usize)
+        -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "entire fibonacci table",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+                self.config.selector.enable(&mut region, 1)?;
+
+                let a_cell = region.assign_advice(|| "f(0)", self.config.advice, 0, || a)?;
+                let b_cell = region.assign_advice(|| "f(1)", self.config.advice, 1, || b)?;
+
+                self.assign_rest(&mut region, a_cell, b_cell, nrows)
+            }
+        )
+    }
 
-                    let c_cell = region.assign_advice(
-                        || "next row",
-                        self.config.advice,
-                        row,
-                        || a_cell.value().copied() + b_cell.value()
-                    )?;
+    /// Like `assign_row`, but with the seeds loaded from fixed constants through `enable_constant`,
+    /// so that they are part of the circuit rather than its inputs.
+    fn assign_constant_row(&self, mut layouter: impl Layouter<F>, (a, b): (F, F), nrows: usize)
+        -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "entire fibonacci table",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+                self.config.selector.enable(&mut region, 1)?;
 
-                    a_cell = b_cell;
-                    b_cell = c_cell;
-                }
+                let a_cell = region.assign_advice_from_constant(|| "f(0)", self.config.advice, 0, a)?;
+                let b_cell = region.assign_advice_from_constant(|| "f(1)", self.config.advice, 1, b)?;
 
-                Ok(b_cell)
+                self.assign_rest(&mut region, a_cell, b_cell, nrows)
             }
         )
     }
 
+    /// Fills rows `2..nrows` after the seeds, returning the cell of the last term.
+    fn assign_rest(
+        &self,
+        region: &mut Region<'_, F>,
+        mut a_cell: AssignedCell<F, F>,
+        mut b_cell: AssignedCell<F, F>,
+        nrows: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        for row in 2..nrows {
+            if row < nrows - 2 {
+                self.config.selector.enable(region, row)?;
+            }
+
+            let c_cell = region.assign_advice(
+                || "next row",
+                self.config.advice,
+                row,
+                || a_cell.value().copied() + b_cell.value()
+            )?;
+
+            a_cell = b_cell;
+            b_cell = c_cell;
+        }
+
+        Ok(b_cell)
+    }
+
     fn expose_public(&self, mut layouter: impl Layouter<F>, cell: &AssignedCell<F, F>, row: usize)
         -> Result<(), Error> {
         layouter.constrain_instance(
             cell.cell(),
-            self.config.instance,
+            self.instance()?,
             row,
         )
     }
 }
 
 #[derive(Copy, Clone, Default)]
-struct MyCircuit<F: FieldExt>(PhantomData<F>);
+pub(crate) struct MyCircuit<F: FieldExt>(PhantomData<F>);
 
 
 impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
@@ -116,7 +185,7 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        MyCircuit::default()
+        Self::default()
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -138,20 +207,245 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     }
 }
 
+/// `MyCircuit` exposing `f(n)` instead of `f(9)`, for `n >= 3`: the gate of the first two
+/// rows needs four terms.
+#[derive(Copy, Clone, Default)]
+pub(crate) struct MyLengthCircuit<F: FieldExt> {
+    pub(crate) n: usize,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyLengthCircuit<F> {
+    type Config = FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        *self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FibonacciChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FibonacciChip::construct(config);
+        let out_cell = chip.assign_row(
+            layouter.namespace(|| "entire table"),
+            self.n + 1
+        )?;
+
+        chip.expose_public(
+            layouter.namespace(|| "expose public"),
+            &out_cell,
+            2
+        )
+    }
+}
+
+/// `MyCircuit` with the seeds fixed to `f(0) = f(1) = 1` by constants, and `f(9)` checked
+/// against the constant `out`: the circuit has no instance column at all.
+#[derive(Clone, Default)]
+pub(crate) struct MyCircuitConst<F: FieldExt> {
+    pub(crate) out: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuitConst<F> {
+    type Config = FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        FibonacciChip::configure_without_instance(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FibonacciChip::construct(config);
+        let out_cell = chip.assign_constant_row(
+            layouter.namespace(|| "entire table"),
+            (F::one(), F::one()),
+            10
+        )?;
+
+        layouter.assign_region(
+            || "expected output",
+            |mut region| region.constrain_constant(out_cell.cell(), self.out)
+        )
+    }
+}
+
+/// `MyCircuit` laid out by the `V1` floor planner instead of `SimpleFloorPlanner`, to compare layouts.
+#[derive(Clone, Default)]
+struct MyCircuitV1<F: FieldExt>(MyCircuit<F>);
+
+impl<F: FieldExt> Circuit<F> for MyCircuitV1<F> {
+    type Config = FibonacciConfig;
+    type FloorPlanner = floor_planner::V1;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuitV1(self.0.without_witnesses())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MyCircuit::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        self.0.synthesize(config, layouter)
+    }
+}
+
+/// Several independent sequences, each given by its seeds and its number of terms,
+/// proven together; the last term of sequence `i` is exposed at instance row `i`.
+#[derive(Clone, Default)]
+struct MultiFibCircuit<F: FieldExt> {
+    instances: Vec<(F, F, usize)>,
+}
+
+impl<F: FieldExt> Circuit<F> for MultiFibCircuit<F> {
+    type Config = FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MultiFibCircuit {
+            instances: self.instances.iter().map(|(_, _, nrows)| (F::zero(), F::zero(), *nrows)).collect(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FibonacciChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FibonacciChip::construct(config);
+
+        for (i, (a, b, nrows)) in self.instances.iter().enumerate() {
+            let out_cell = chip.assign_private_row(
+                layouter.namespace(|| format!("sequence {}", i)),
+                (Value::known(*a), Value::known(*b)),
+                *nrows
+            )?;
+
+            chip.expose_public(
+                layouter.namespace(|| format!("expose sequence {}", i)),
+                &out_cell,
+                i
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::marker::PhantomData;
     use halo2_proofs::dev::MockProver;
     use halo2_proofs::pasta::Fp;
-    use crate::fibonacci::example3::MyCircuit;
+    use crate::fibonacci::example3::{MultiFibCircuit, MyCircuit, MyCircuitConst, MyCircuitV1};
+    use crate::fibonacci::{fib_instances, fib_reference, mock_circuit};
+    use crate::testing::read_advice;
+    use crate::utils::minimum_k;
 
     #[test]
     fn test_circuit() {
-        let circuit = MyCircuit::default();
-        let public_input = vec![
-            vec![Fp::from(1), Fp::from(1), Fp::from(55)]
+        let instances = fib_instances(10, (1, 1));
+        let circuit = MyCircuit::<Fp>(PhantomData);
+        let prover = mock_circuit(4, &circuit, instances).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_constant_seeds() {
+        // there is no instance column at all
+        let circuit = MyCircuitConst {
+            out: fib_reference(10, (1, 1))[9],
+        };
+        let prover = MockProver::run(4, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        let circuit = MyCircuitConst {
+            out: fib_reference(10, (1, 1))[9] + Fp::one(),
+        };
+        let prover = MockProver::run(4, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_floor_planners() {
+        // both floor planners fit the table in the same 2^4 rows
+        let instances = fib_instances(10, (1, 1));
+        let circuit = MyCircuit::<Fp>(PhantomData);
+        let prover = mock_circuit(4, &circuit, instances.clone()).unwrap();
+        prover.assert_satisfied();
+
+        let prover = mock_circuit(4, &MyCircuitV1(circuit), instances).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_read_advice() {
+        // the table starts at row 0 of the only advice column, so F(5) is at row 5
+        let instances = fib_instances(10, (1, 1));
+        let circuit = MyCircuit::<Fp>(PhantomData);
+        let instances = vec![instances];
+        assert_eq!(read_advice(&circuit, &instances, 0, 5), Some(fib_reference(10, (1, 1))[5]));
+
+        // past the table
+        assert_eq!(read_advice(&circuit, &instances, 0, 12), None);
+    }
+
+
+    #[test]
+    fn test_multiple_sequences() {
+        let circuit = MultiFibCircuit {
+            instances: vec![
+                (Fp::from(1), Fp::from(1), 10),
+                (Fp::from(2), Fp::from(3), 5),
+                (Fp::from(3), Fp::from(4), 6),
+            ],
+        };
+        let mut outputs = vec![
+            fib_reference(10, (1, 1))[9],
+            fib_reference(5, (2, 3))[4],
+            fib_reference(6, (3, 4))[5],
         ];
-        let prover = MockProver::run(4, &circuit, public_input).unwrap();
+        let prover = MockProver::run(5, &circuit, vec![outputs.clone()]).unwrap();
         prover.assert_satisfied();
+
+        // every output is checked, not just the first
+        outputs[2] += Fp::one();
+        let prover = MockProver::run(5, &circuit, vec![outputs]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_minimum_k() {
+        // the 10 rows of the table do not fit in 2^3 rows
+        let instances = fib_instances(10, (1, 1));
+        let circuit = MyCircuit::<Fp>(PhantomData);
+        assert_eq!(minimum_k(&circuit, vec![instances]), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "debug-dump")]
+    fn test_dump_assignments() {
+        let instances = fib_instances(10, (1, 1));
+        let circuit = MyCircuit::<Fp>(PhantomData);
+        let path = std::env::temp_dir().join("fib-3-assignments.csv");
+        crate::dump::dump_assignments(4, &circuit, vec![instances], &path).unwrap();
+
+        // a header and the 2^4 rows
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 1 + 16);
+        assert_eq!(lines[0], "row,advice_0,instance_0");
+        assert!(lines[1].starts_with("0,1,"));
     }
 
     #[test]
@@ -163,7 +457,7 @@ mod tests {
         root.fill(&WHITE).unwrap();
         let root = root.titled("Fib 3 Layout", ("sans-serif", 60)).unwrap();
 
-        let circuit = MyCircuit::<Fp>::default();
+        let circuit = MyCircuit::<Fp>(PhantomData);
         halo2_proofs::dev::CircuitLayout::default()
             .render(4, &circuit, &root)
             .unwrap();
@@ -4,7 +4,8 @@ use halo2_proofs::{
     poly::Rotation,
     arithmetic::FieldExt,
 };
-use crate::is_zero::{IsZeroChip, IsZeroConfig};
+use crate::is_zero::is_equal::{IsEqualChip, IsEqualConfig};
+use crate::range_check::less_than::{LessThanChip, LessThanConfig};
 
 /// here is the function
 /// ```python
@@ -15,13 +16,16 @@ use crate::is_zero::{IsZeroChip, IsZeroConfig};
 /// ```
 
 #[derive(Clone, Debug)]
-struct FunctionConfig<F: FieldExt> {
+pub(crate) struct FunctionConfig<F: FieldExt> {
     col_a: Column<Advice>,
     col_b: Column<Advice>,
     col_c: Column<Advice>,
     selector: Selector,
-    a_equal_b: IsZeroConfig<F>,
+    a_equal_b: IsEqualConfig<F>,
     output: Column<Advice>,
+    instance: Column<Instance>,
+    /// The sign column and the less-than check it is copied from, when `a < b` is signed.
+    signed: Option<(Column<Advice>, LessThanConfig<F, 4>)>,
 }
 
 #[derive(Clone)]
@@ -37,16 +41,40 @@ impl<F: FieldExt> FunctionChip<F> {
     }
 
     pub fn configure(meta: &mut ConstraintSystem<F>) -> FunctionConfig<F> {
+        Self::configure_inner(meta, false)
+    }
+
+    /// Like `configure`, but for 32-bit `a` and `b` with `a < b` the else branch outputs
+    /// `b - a` instead of wrapping around the field, with the sign `a < b` checked by a
+    /// `LessThanChip`: `output = (1 - 2 * sign) * (a - b)`.
+    pub fn configure_signed(meta: &mut ConstraintSystem<F>) -> FunctionConfig<F> {
+        Self::configure_inner(meta, true)
+    }
+
+    fn configure_inner(meta: &mut ConstraintSystem<F>, signed: bool) -> FunctionConfig<F> {
         let [col_a, col_b, col_c] = [(); 3].map(|_| meta.advice_column());
         let selector = meta.selector();
-        let is_zero_advice_col = meta.advice_column();
+        let is_equal_advice_col = meta.advice_column();
         let output = meta.advice_column();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(output);
+        meta.enable_equality(instance);
 
-        let a_equal_b = IsZeroChip::configure(
+        let signed = signed.then(|| {
+            let sign = meta.advice_column();
+            meta.enable_equality(col_a);
+            meta.enable_equality(col_b);
+            meta.enable_equality(sign);
+            (sign, LessThanChip::configure(meta))
+        });
+
+        let a_equal_b = IsEqualChip::configure(
             meta,
             |meta| meta.query_selector(selector),
-            |meta| meta.query_advice(col_a, Rotation::cur()) - meta.query_advice(col_b, Rotation::cur()),
-            is_zero_advice_col
+            |meta| meta.query_advice(col_a, Rotation::cur()),
+            |meta| meta.query_advice(col_b, Rotation::cur()),
+            is_equal_advice_col
         );
 
         meta.create_gate("f(a, b, c) = if a == b {c} else {a - b}", |meta| {
@@ -55,10 +83,19 @@ impl<F: FieldExt> FunctionChip<F> {
             let b = meta.query_advice(col_b, Rotation::cur());
             let c = meta.query_advice(col_c, Rotation::cur());
             let output = meta.query_advice(output, Rotation::cur());
+            let one = Expression::Constant(F::one());
+
+            let difference = match signed {
+                None => a - b,
+                Some((sign, _)) => {
+                    let sign = meta.query_advice(sign, Rotation::cur());
+                    (one.clone() - Expression::Constant(F::from(2u64)) * sign) * (a - b)
+                }
+            };
 
             vec![
                 s.clone() * (a_equal_b.expr() * (output.clone() - c)),
-                s * (Expression::Constant(F::one()) - a_equal_b.expr()) * (output - (a - b)),
+                s * (one - a_equal_b.expr()) * (output - difference),
             ]
         });
 
@@ -69,39 +106,85 @@ impl<F: FieldExt> FunctionChip<F> {
             selector,
             a_equal_b,
             output,
+            instance,
+            signed,
+        }
+    }
+
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        match &self.config.signed {
+            None => Ok(()),
+            Some((_, less_than)) => LessThanChip::construct(less_than.clone()).load_table(layouter),
         }
     }
 
+    /// Returns the cells of `a == b` and of the output, together with the sign if signed.
     pub fn assign(
         &self,
         mut layouter: impl Layouter<F>,
         a: F,
         b: F,
         c: F,
-    ) -> Result<(), Error> {
-        let is_zero_chip = IsZeroChip::construct(self.config.a_equal_b.clone());
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, Option<AssignedCell<F, F>>), Error> {
+        let is_equal_chip = IsEqualChip::construct(self.config.a_equal_b.clone());
+        // the operands are 32-bit integers when signed, so they compare by their lower 128 bits
+        let a_lt_b = a.get_lower_128() < b.get_lower_128();
 
-        layouter.assign_region(
+        let (a_cell, b_cell, a_equal_b, output, sign) = layouter.assign_region(
             || "f(a, b, c) = if a=b {c} else {a-b}",
             |mut region| {
                 self.config.selector.enable(&mut region, 0)?;
-                region.assign_advice(|| "a", self.config.col_a, 0, || Value::known(a))?;
-                region.assign_advice(|| "b", self.config.col_b, 0, || Value::known(b))?;
+                let a_cell = region.assign_advice(|| "a", self.config.col_a, 0, || Value::known(a))?;
+                let b_cell = region.assign_advice(|| "b", self.config.col_b, 0, || Value::known(b))?;
                 region.assign_advice(|| "c", self.config.col_c, 0, || Value::known(c))?;
 
-                is_zero_chip.assign(&mut region, 0, Value::known(a-b))?;
+                let a_equal_b = is_equal_chip.assign(&mut region, 0, Value::known(a), Value::known(b))?;
 
-                let output = if a==b {c} else {a-b};
-                region.assign_advice(||"output", self.config.output, 0, || Value::known(output))?;
-                Ok(())
+                let sign = match self.config.signed {
+                    None => None,
+                    Some((sign, _)) => {
+                        let value = if a_lt_b { F::one() } else { F::zero() };
+                        Some(region.assign_advice(|| "sign", sign, 0, || Value::known(value))?)
+                    }
+                };
+
+                let output = if a == b {
+                    c
+                } else if sign.is_some() && a_lt_b {
+                    b - a
+                } else {
+                    a - b
+                };
+                let output = region.assign_advice(||"output", self.config.output, 0, || Value::known(output))?;
+                Ok((a_cell, b_cell, a_equal_b, output, sign))
             }
-        )
+        )?;
+
+        if let (Some(sign), Some((_, less_than))) = (&sign, &self.config.signed) {
+            let lt = LessThanChip::construct(less_than.clone()).assign_from_cells(
+                layouter.namespace(|| "a < b"),
+                &a_cell,
+                &b_cell,
+            )?;
+            layouter.assign_region(
+                || "sign = a < b",
+                |mut region| region.constrain_equal(sign.cell(), lt.cell())
+            )?;
+        }
+
+        Ok((a_equal_b, output, sign))
+    }
+
+    pub fn expose_public(&self, mut layouter: impl Layouter<F>, cell: &AssignedCell<F, F>, row: usize)
+        -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
     }
 }
 
 
+/// Exposes `a == b` at instance row 0 and the output at row 1.
 #[derive(Default)]
-struct MyCircuit<F> {
+pub(crate) struct MyCircuit<F> {
     a: F,
     b: F,
     c: F
@@ -119,17 +202,52 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
         FunctionChip::configure(meta)
     }
 
-    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FunctionChip::construct(config);
+
+        let (a_equal_b, output, _) = chip.assign(layouter.namespace(|| "f(a, b, c)"), self.a, self.b, self.c)?;
+
+        chip.expose_public(layouter.namespace(|| "expose a == b"), &a_equal_b, 0)?;
+        chip.expose_public(layouter.namespace(|| "expose output"), &output, 1)
+    }
+}
+
+/// `MyCircuit` with the else branch signed, also exposing the sign at instance row 2.
+#[derive(Default)]
+struct MySignedCircuit<F> {
+    a: F,
+    b: F,
+    c: F
+}
+
+impl<F: FieldExt> Circuit<F> for MySignedCircuit<F> {
+    type Config = FunctionConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MySignedCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FunctionChip::configure_signed(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
         let chip = FunctionChip::construct(config);
+        chip.load_table(&mut layouter)?;
+
+        let (a_equal_b, output, sign) = chip.assign(layouter.namespace(|| "f(a, b, c)"), self.a, self.b, self.c)?;
 
-        chip.assign(layouter, self.a, self.b, self.c)
+        chip.expose_public(layouter.namespace(|| "expose a == b"), &a_equal_b, 0)?;
+        chip.expose_public(layouter.namespace(|| "expose output"), &output, 1)?;
+        chip.expose_public(layouter.namespace(|| "expose sign"), &sign.unwrap(), 2)
     }
 }
 
 #[cfg(test)]
 mod tests{
     use halo2_proofs::dev::MockProver;
-    use crate::fibonacci::example4::MyCircuit;
+    use crate::fibonacci::example4::{MyCircuit, MySignedCircuit};
     use halo2_proofs::pasta::Fp;
 
     #[test]
@@ -140,7 +258,85 @@ mod tests{
             c: Fp::from(15),
         };
 
-        let prover = MockProver::run(4, &circuit, vec![]).unwrap();
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::one(), Fp::from(15)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_expose_a_equal_b() {
+        let circuit = MyCircuit {
+            a: Fp::from(12),
+            b: Fp::from(10),
+            c: Fp::from(15),
+        };
+
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::zero(), Fp::from(2)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::one(), Fp::from(2)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_expose_output() {
+        // a == b, so the output is c
+        let circuit = MyCircuit {
+            a: Fp::from(7),
+            b: Fp::from(7),
+            c: Fp::from(3),
+        };
+
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::one(), Fp::from(3)]]).unwrap();
+        prover.assert_satisfied();
+
+        // a - b = 0 is not the output when a == b
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::one(), Fp::zero()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_unsigned_a_less_than_b() {
+        // 5 - 12 wraps around the field
+        let circuit = MyCircuit {
+            a: Fp::from(5),
+            b: Fp::from(12),
+            c: Fp::from(15),
+        };
+
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::zero(), -Fp::from(7)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::zero(), Fp::from(7)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_signed_a_less_than_b() {
+        // 5 - 12 is 7 with the sign set
+        let circuit = MySignedCircuit {
+            a: Fp::from(5),
+            b: Fp::from(12),
+            c: Fp::from(15),
+        };
+
+        let prover = MockProver::run(9, &circuit, vec![vec![Fp::zero(), Fp::from(7), Fp::one()]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(9, &circuit, vec![vec![Fp::zero(), -Fp::from(7), Fp::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // the sign is checked against a < b
+        let prover = MockProver::run(9, &circuit, vec![vec![Fp::zero(), Fp::from(7), Fp::zero()]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 12 - 5 is 7 with the sign clear
+        let circuit = MySignedCircuit {
+            a: Fp::from(12),
+            b: Fp::from(5),
+            c: Fp::from(15),
+        };
+
+        let prover = MockProver::run(9, &circuit, vec![vec![Fp::zero(), Fp::from(7), Fp::zero()]]).unwrap();
         prover.assert_satisfied();
     }
 
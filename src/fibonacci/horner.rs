@@ -0,0 +1,177 @@
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+/// Evaluates `p(x) = c_0 + c_1 * x + .. + c_n * x^n` by Horner's rule, from the leading
+/// coefficient down: the first row starts the evaluation at `c_n`, and every following row
+/// multiplies the row above by `x` and adds its coefficient. `x` is repeated on every row,
+/// and each row checks it against the row above.
+///```txt
+///        coeff    |  x  |  acc                  |  q_first  |  q_rest  |  instance
+///       ------------------------------------------------------------------------------
+///         c_n     |  x  |  c_n                  |     1     |    0     |    p(x)
+///         c_{n-1} |  x  |  acc_prev * x + c_n-1 |     0     |    1     |
+///         ..      |  .. |   ..                  |     0     |    1     |
+///         c_0     |  x  |  p(x)                 |     0     |    1     |
+///```
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HornerConfig {
+    coeff: Column<Advice>,
+    x: Column<Advice>,
+    acc: Column<Advice>,
+    q_first: Selector,
+    q_rest: Selector,
+    instance: Column<Instance>,
+}
+
+pub(crate) struct HornerChip<F: FieldExt> {
+    config: HornerConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> HornerChip<F> {
+    pub(crate) fn construct(config: HornerConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> HornerConfig {
+        let coeff = meta.advice_column();
+        let x = meta.advice_column();
+        let acc = meta.advice_column();
+        let q_first = meta.selector();
+        let q_rest = meta.selector();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(acc);
+        meta.enable_equality(instance);
+
+        meta.create_gate("horner", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let q_rest = meta.query_selector(q_rest);
+            let coeff = meta.query_advice(coeff, Rotation::cur());
+            let x_prev = meta.query_advice(x, Rotation::prev());
+            let x = meta.query_advice(x, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+
+            vec![
+                q_first * (acc.clone() - coeff.clone()),
+                q_rest.clone() * (x.clone() - x_prev),
+                q_rest * (acc - (acc_prev * x + coeff)),
+            ]
+        });
+
+        HornerConfig {
+            coeff,
+            x,
+            acc,
+            q_first,
+            q_rest,
+            instance,
+        }
+    }
+
+    /// Evaluates the polynomial with coefficients `coeffs`, lowest degree first, at `x`,
+    /// returning the cell of the result.
+    pub(crate) fn assign(&self, mut layouter: impl Layouter<F>, coeffs: &[Value<F>], x: Value<F>)
+        -> Result<AssignedCell<F, F>, Error> {
+        assert!(!coeffs.is_empty());
+
+        layouter.assign_region(
+            || "horner",
+            |mut region| {
+                let mut acc = Value::known(F::zero());
+                let mut cell = None;
+                for (row, coeff) in coeffs.iter().rev().enumerate() {
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_rest.enable(&mut region, row)?;
+                    }
+
+                    region.assign_advice(|| "coeff", self.config.coeff, row, || *coeff)?;
+                    region.assign_advice(|| "x", self.config.x, row, || x)?;
+
+                    acc = acc * x + *coeff;
+                    cell = Some(region.assign_advice(|| "acc", self.config.acc, row, || acc)?);
+                }
+
+                Ok(cell.unwrap())
+            }
+        )
+    }
+
+    pub(crate) fn expose_public(&self, mut layouter: impl Layouter<F>, cell: &AssignedCell<F, F>, row: usize)
+        -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    coeffs: Vec<F>,
+    x: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = HornerConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            coeffs: vec![F::zero(); self.coeffs.len()],
+            x: F::zero(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        HornerChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = HornerChip::construct(config);
+
+        let coeffs: Vec<_> = self.coeffs.iter().map(|c| Value::known(*c)).collect();
+        let eval = chip.assign(layouter.namespace(|| "horner"), &coeffs, Value::known(self.x))?;
+
+        chip.expose_public(layouter.namespace(|| "expose evaluation"), &eval, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::fibonacci::horner::MyCircuit;
+
+    #[test]
+    fn test_horner() {
+        // 1 + 2 * 2 + 3 * 2^2 = 17
+        let circuit = MyCircuit {
+            coeffs: vec![Fp::from(1), Fp::from(2), Fp::from(3)],
+            x: Fp::from(2),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(17)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(18)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_constant_polynomial() {
+        let circuit = MyCircuit {
+            coeffs: vec![Fp::from(7)],
+            x: Fp::from(5),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(7)]]).unwrap();
+        prover.assert_satisfied();
+    }
+}
@@ -0,0 +1,221 @@
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+/// Computes `base^EXP` by left-to-right square-and-multiply, one step per row. The chain
+/// starts from `base` for the leading bit of `EXP`, then every further bit squares the
+/// accumulator and, if the bit is set, multiplies it by `base`. With `EXP = 5 = 0b101`:
+///```txt
+///        base  |  acc   |  q_first  |  q_square  |  q_mul  |  instance
+///       -----------------------------------------------------------------
+///         b    |  b     |     1     |     1      |    0    |    out
+///         b    |  b^2   |     0     |     1      |    0    |
+///         b    |  b^4   |     0     |     0      |    1    |
+///         b    |  b^5   |     0     |     0      |    0    |
+///```
+/// `EXP = 0` has no chain, and its single row holds `acc = 1` under `q_one`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PowConfig {
+    base: Column<Advice>,
+    acc: Column<Advice>,
+    q_first: Selector,
+    q_one: Selector,
+    q_square: Selector,
+    q_mul: Selector,
+    instance: Column<Instance>,
+}
+
+/// A step of the square-and-multiply chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Square,
+    Multiply,
+}
+
+pub(crate) struct PowChip<F: FieldExt, const EXP: u64> {
+    config: PowConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const EXP: u64> PowChip<F, EXP> {
+    pub(crate) fn construct(config: PowConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> PowConfig {
+        let [base, acc] = [(); 2].map(|_| meta.advice_column());
+        let q_first = meta.selector();
+        let q_one = meta.selector();
+        let q_square = meta.selector();
+        let q_mul = meta.selector();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(base);
+        meta.enable_equality(acc);
+        meta.enable_equality(instance);
+
+        meta.create_gate("square and multiply", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let q_one = meta.query_selector(q_one);
+            let q_square = meta.query_selector(q_square);
+            let q_mul = meta.query_selector(q_mul);
+            let base = meta.query_advice(base, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_next = meta.query_advice(acc, Rotation::next());
+
+            vec![
+                q_first * (acc.clone() - base.clone()),
+                q_one * (acc.clone() - Expression::Constant(F::one())),
+                q_square * (acc_next.clone() - acc.clone() * acc.clone()),
+                q_mul * (acc_next - acc * base),
+            ]
+        });
+
+        PowConfig {
+            base,
+            acc,
+            q_first,
+            q_one,
+            q_square,
+            q_mul,
+            instance,
+        }
+    }
+
+    /// The steps after the leading bit of `EXP`.
+    fn chain() -> Vec<Step> {
+        if EXP == 0 {
+            return vec![];
+        }
+
+        let bits = 64 - EXP.leading_zeros();
+        (0..bits - 1)
+            .rev()
+            .flat_map(|i| {
+                if EXP >> i & 1 == 1 {
+                    vec![Step::Square, Step::Multiply]
+                } else {
+                    vec![Step::Square]
+                }
+            })
+            .collect()
+    }
+
+    /// The rows `assign` lays out: the start of the chain, then one per step.
+    pub(crate) fn num_rows() -> usize {
+        Self::chain().len() + 1
+    }
+
+    /// Returns the cell of `base^EXP`.
+    pub(crate) fn assign(&self, mut layouter: impl Layouter<F>, base: F) -> Result<AssignedCell<F, F>, Error> {
+        let chain = Self::chain();
+
+        layouter.assign_region(
+            || "pow",
+            |mut region| {
+                let base_cell = region.assign_advice(|| "base", self.config.base, 0, || Value::known(base))?;
+
+                let mut acc = if EXP == 0 {
+                    self.config.q_one.enable(&mut region, 0)?;
+                    F::one()
+                } else {
+                    self.config.q_first.enable(&mut region, 0)?;
+                    base
+                };
+                let mut acc_cell = region.assign_advice(|| "acc 0", self.config.acc, 0, || Value::known(acc))?;
+
+                for (row, step) in chain.iter().enumerate() {
+                    match step {
+                        Step::Square => {
+                            self.config.q_square.enable(&mut region, row)?;
+                            acc = acc.square();
+                        }
+                        Step::Multiply => {
+                            self.config.q_mul.enable(&mut region, row)?;
+                            acc *= base;
+                        }
+                    }
+
+                    base_cell.copy_advice(|| "base", &mut region, self.config.base, row + 1)?;
+                    acc_cell = region.assign_advice(|| format!("acc {}", row + 1), self.config.acc, row + 1, || Value::known(acc))?;
+                }
+
+                Ok(acc_cell)
+            }
+        )
+    }
+
+    pub(crate) fn expose_public(&self, mut layouter: impl Layouter<F>, cell: &AssignedCell<F, F>, row: usize)
+        -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<F, const EXP: u64> {
+    base: F,
+}
+
+impl<F: FieldExt, const EXP: u64> Circuit<F> for MyCircuit<F, EXP> {
+    type Config = PowConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        PowChip::<F, EXP>::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = PowChip::<F, EXP>::construct(config);
+        let out = chip.assign(layouter.namespace(|| "pow"), self.base)?;
+        chip.expose_public(layouter.namespace(|| "out"), &out, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::fibonacci::pow::{MyCircuit, PowChip};
+
+    #[test]
+    fn test_pow_5() {
+        let circuit = MyCircuit::<_, 5> {
+            base: Fp::from(3),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(243)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(81)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_pow_0() {
+        let circuit = MyCircuit::<_, 0> {
+            base: Fp::from(3),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::one()]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_num_rows() {
+        // the leading bit, then a square per further bit and a multiply per further set bit
+        assert_eq!(PowChip::<Fp, 0>::num_rows(), 1);
+        assert_eq!(PowChip::<Fp, 1>::num_rows(), 1);
+        assert_eq!(PowChip::<Fp, 5>::num_rows(), 4);
+        assert_eq!(PowChip::<Fp, 8>::num_rows(), 4);
+        assert_eq!(PowChip::<Fp, 15>::num_rows(), 7);
+    }
+}
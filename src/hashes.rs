@@ -0,0 +1,2 @@
+mod fibonacci;
+mod poseidon;
@@ -0,0 +1,133 @@
+/// Composes the Fibonacci chip of `fibonacci::example1` with the Poseidon chip in one circuit:
+/// `F(n)` is computed from private seeds and only its Poseidon hash is made public.
+/// The output cell of the Fibonacci chip is copied straight into the Poseidon state.
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength, P128Pow5T3},
+    Hash, Pow5Chip, Pow5Config,
+};
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    pasta::*,
+};
+use crate::StudyChip;
+use crate::fibonacci::example1::{FibonacciChip, FibonacciConfig};
+
+/// the width of the poseidon state, and how many of its elements absorb the message
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+
+#[derive(Clone)]
+struct FibonacciHashConfig {
+    fibonacci_config: FibonacciConfig,
+    input: Column<Advice>,
+    poseidon_config: Pow5Config<pallas::Base, WIDTH, RATE>,
+}
+
+/// Proves knowledge of seeds whose `n`-th Fibonacci term hashes to the public digest.
+#[derive(Default, Copy, Clone)]
+struct MyCircuit {
+    seeds: [pallas::Base; 2],
+    n: usize,
+}
+
+impl Circuit<pallas::Base> for MyCircuit {
+    type Config = FibonacciHashConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            n: self.n,
+            ..Default::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        let fibonacci_config = FibonacciChip::configure(meta);
+
+        let state = [(); WIDTH].map(|_| meta.advice_column());
+        let partial_sbox = meta.advice_column();
+
+        // fixed columns for the round constants
+        let rc_a = [(); WIDTH].map(|_| meta.fixed_column());
+        let rc_b = [(); WIDTH].map(|_| meta.fixed_column());
+        meta.enable_constant(rc_b[0]);
+
+        let poseidon_config = Pow5Chip::configure::<P128Pow5T3>(
+            meta,
+            state,
+            partial_sbox,
+            rc_a,
+            rc_b,
+        );
+
+        FibonacciHashConfig {
+            fibonacci_config,
+            input: state[0],
+            poseidon_config,
+        }
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        assert!(self.n >= 2);
+        let fibonacci_chip = FibonacciChip::construct(config.fibonacci_config);
+
+        let (_, mut prev_b, mut prev_c) = fibonacci_chip.assign_private_first_row(
+            layouter.namespace(|| "assign first row"),
+            Value::known(self.seeds[0]),
+            Value::known(self.seeds[1]),
+        )?;
+        for _ in 3..=self.n {
+            let c_cell = fibonacci_chip.assign_row(
+                layouter.namespace(|| "assign next row"),
+                &prev_b,
+                &prev_c,
+            )?;
+            prev_b = prev_c;
+            prev_c = c_cell;
+        }
+
+        let message = layouter.assign_region(
+            || "load message",
+            |mut region| prev_c.copy_advice(|| "F(n)", &mut region, config.input, 0)
+        )?;
+
+        let hasher = Hash::<_, _, P128Pow5T3, ConstantLength<1>, WIDTH, RATE>::init(
+            Pow5Chip::construct(config.poseidon_config.clone()),
+            layouter.namespace(|| "init")
+        )?;
+        let digest = hasher.hash(layouter.namespace(|| "hash"), [message])?;
+
+        fibonacci_chip.expose_public(layouter.namespace(|| "expose digest"), &digest, 0)
+    }
+}
+
+/// Computes `F(n)` and its digest off-circuit, for checking the circuit against.
+fn fibonacci_digest(seeds: [pallas::Base; 2], n: usize) -> pallas::Base {
+    let (f_n, _) = (0..n).fold((seeds[0], seeds[1]), |(a, b), _| (b, a + b));
+    poseidon::Hash::<_, P128Pow5T3, ConstantLength<1>, WIDTH, RATE>::init().hash([f_n])
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::pallas;
+    use crate::hashes::fibonacci::{fibonacci_digest, MyCircuit};
+
+    #[test]
+    fn test_circuit() {
+        let seeds = [pallas::Base::one(), pallas::Base::one()];
+        let circuit = MyCircuit {
+            seeds,
+            n: 9,
+        };
+        let digest = fibonacci_digest(seeds, 9);
+
+        let prover = MockProver::run(7, &circuit, vec![vec![digest]]).unwrap();
+        prover.assert_satisfied();
+
+        // the digest of a neighbouring term is rejected
+        let prover = MockProver::run(7, &circuit, vec![vec![fibonacci_digest(seeds, 10)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
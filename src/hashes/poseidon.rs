@@ -0,0 +1,108 @@
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength, P128Pow5T3},
+    Hash, Pow5Chip, Pow5Config,
+};
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    pasta::*,
+};
+
+/// the width of the poseidon state, and how many of its elements absorb the message
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+
+#[derive(Debug, Clone)]
+struct PoseidonConfig {
+    input: [Column<Advice>; RATE],
+    instance: Column<Instance>,
+    poseidon_config: Pow5Config<pallas::Base, WIDTH, RATE>,
+}
+
+#[derive(Default, Copy, Clone)]
+struct MyCircuit {
+    message: [pallas::Base; 2],
+}
+
+impl Circuit<pallas::Base> for MyCircuit {
+    type Config = PoseidonConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        let state = [(); WIDTH].map(|_| meta.advice_column());
+        let partial_sbox = meta.advice_column();
+
+        // fixed columns for the round constants
+        let rc_a = [(); WIDTH].map(|_| meta.fixed_column());
+        let rc_b = [(); WIDTH].map(|_| meta.fixed_column());
+        meta.enable_constant(rc_b[0]);
+
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let poseidon_config = Pow5Chip::configure::<P128Pow5T3>(
+            meta,
+            state,
+            partial_sbox,
+            rc_a,
+            rc_b,
+        );
+
+        PoseidonConfig {
+            input: state[..RATE].try_into().unwrap(),
+            instance,
+            poseidon_config,
+        }
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        let chip = Pow5Chip::construct(config.poseidon_config.clone());
+
+        let message = layouter.assign_region(
+            || "load message",
+            |mut region| {
+                let left = region.assign_advice(|| "left", config.input[0], 0, || Value::known(self.message[0]))?;
+                let right = region.assign_advice(|| "right", config.input[1], 0, || Value::known(self.message[1]))?;
+                Ok([left, right])
+            }
+        )?;
+
+        let hasher = Hash::<_, _, P128Pow5T3, ConstantLength<2>, WIDTH, RATE>::init(
+            chip,
+            layouter.namespace(|| "init")
+        )?;
+        let digest = hasher.hash(layouter.namespace(|| "hash"), message)?;
+
+        layouter.constrain_instance(digest.cell(), config.instance, 0)
+    }
+}
+
+/// Computes the digest off-circuit, for checking the circuit against.
+fn hash(message: [pallas::Base; 2]) -> pallas::Base {
+    poseidon::Hash::<_, P128Pow5T3, ConstantLength<2>, WIDTH, RATE>::init().hash(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::pallas;
+    use crate::hashes::poseidon::{hash, MyCircuit};
+
+    #[test]
+    fn test_circuit() {
+        let message = [pallas::Base::from(1), pallas::Base::from(2)];
+        let circuit = MyCircuit {
+            message
+        };
+
+        let prover = MockProver::run(6, &circuit, vec![vec![hash(message)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(6, &circuit, vec![vec![hash(message) + pallas::Base::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -1,3 +1,12 @@
+pub mod batch;
+pub mod constant;
+pub mod inverse;
+pub mod is_equal;
+pub mod mux4;
+pub mod not_equal;
+pub mod select;
+pub mod xor_bit;
+
 use halo2_proofs::{
     circuit::*,
     plonk::*,
@@ -8,6 +17,7 @@ use halo2_proofs::{
 #[derive(Debug, Clone)]
 pub struct IsZeroConfig<F> {
     pub value_inv: Column<Advice>,
+    pub is_zero: Column<Advice>,
     pub is_zero_expr: Expression<F>,
 }
 
@@ -36,6 +46,9 @@ impl<F: FieldExt> IsZeroChip<F> {
         value_inv: Column<Advice>,
     ) -> IsZeroConfig<F> {
         let mut is_zero_expr = Expression::Constant(F::zero());
+        // holds the result of `is_zero_expr`, so that other regions can copy it
+        let is_zero = meta.advice_column();
+        meta.enable_equality(is_zero);
 
         meta.create_gate("is_zero", |meta| {
             //
@@ -50,13 +63,19 @@ impl<F: FieldExt> IsZeroChip<F> {
             let value = value(meta);
             let q_enable = q_enable(meta);
             let value_inv = meta.query_advice(value_inv, Rotation::cur());
+            let is_zero = meta.query_advice(is_zero, Rotation::cur());
 
             is_zero_expr = Expression::Constant(F::one()) - value.clone() * value_inv;
-            vec![q_enable * value * is_zero_expr.clone()]
+            vec![
+                q_enable.clone() * value * is_zero_expr.clone(),
+                q_enable.clone() * is_zero.clone() * (Expression::Constant(F::one()) - is_zero.clone()),
+                q_enable * (is_zero - is_zero_expr.clone()),
+            ]
         });
 
         IsZeroConfig {
             value_inv,
+            is_zero,
             is_zero_expr
         }
     }
@@ -66,9 +85,11 @@ impl<F: FieldExt> IsZeroChip<F> {
         region: &mut Region<'_, F>,
         offset: usize,
         value: Value<F>,
-    ) -> Result<(), Error> {
+    ) -> Result<AssignedCell<F, F>, Error> {
         let value_inv = value.map(|value| value.invert().unwrap_or(F::zero()));
         region.assign_advice(|| "value inv", self.config.value_inv, offset, || value_inv)?;
-        Ok(())
+
+        let is_zero = value.map(|value| if value == F::zero() { F::one() } else { F::zero() });
+        region.assign_advice(|| "is zero", self.config.is_zero, offset, || is_zero)
     }
 }
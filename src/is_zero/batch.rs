@@ -0,0 +1,170 @@
+/// Checks whether every value of a slice is zero, in a single region.
+/// All values share one column, one per row, so the gate degree does not grow with the slice:
+/// each row carries the is_zero flag of its value multiplied into a running product.
+///```txt
+///        value  |  value_inv  |  all_zero                      |  q_first  |  q_rest
+///       ---------------------------------------------------------------------------------
+///         v_0   |   1/v_0     |  is_zero(v_0)                  |     1     |    0
+///         v_1   |   1/v_1     |  all_zero_0 * is_zero(v_1)     |     0     |    1
+///         v_2   |   1/v_2     |  all_zero_1 * is_zero(v_2)     |     0     |    1
+///```
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+use crate::is_zero::{IsZeroChip, IsZeroConfig};
+
+#[derive(Debug, Clone)]
+pub struct BatchIsZeroConfig<F> {
+    pub value: Column<Advice>,
+    pub all_zero: Column<Advice>,
+    pub q_first: Selector,
+    pub q_rest: Selector,
+    pub is_zero: IsZeroConfig<F>,
+}
+
+pub struct BatchIsZeroChip<F: FieldExt> {
+    config: BatchIsZeroConfig<F>
+}
+
+impl<F: FieldExt> BatchIsZeroChip<F> {
+    pub fn construct(config: BatchIsZeroConfig<F>) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> BatchIsZeroConfig<F> {
+        let [value, value_inv, all_zero] = [(); 3].map(|_| meta.advice_column());
+        let q_first = meta.selector();
+        let q_rest = meta.selector();
+
+        meta.enable_equality(all_zero);
+
+        let is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_first) + meta.query_selector(q_rest),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            value_inv
+        );
+
+        meta.create_gate("all zero", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let q_rest = meta.query_selector(q_rest);
+            let prev = meta.query_advice(all_zero, Rotation::prev());
+            let cur = meta.query_advice(all_zero, Rotation::cur());
+
+            vec![
+                q_first * (cur.clone() - is_zero.expr()),
+                q_rest * (cur - prev * is_zero.expr()),
+            ]
+        });
+
+        BatchIsZeroConfig {
+            value,
+            all_zero,
+            q_first,
+            q_rest,
+            is_zero,
+        }
+    }
+
+    /// Returns a cell that is 1 iff every one of `values` is zero.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(!values.is_empty());
+        let is_zero_chip = IsZeroChip::construct(self.config.is_zero.clone());
+
+        layouter.assign_region(
+            || "batch is zero",
+            |mut region| {
+                let mut all_zero = Value::known(F::one());
+                let mut cell = None;
+                for (row, value) in values.iter().enumerate() {
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_rest.enable(&mut region, row)?;
+                    }
+
+                    region.assign_advice(|| "value", self.config.value, row, || *value)?;
+                    is_zero_chip.assign(&mut region, row, *value)?;
+
+                    all_zero = all_zero.zip(*value).map(|(all_zero, value)| {
+                        if value == F::zero() { all_zero } else { F::zero() }
+                    });
+                    cell = Some(region.assign_advice(|| "all zero", self.config.all_zero, row, || all_zero)?);
+                }
+
+                Ok(cell.unwrap())
+            }
+        )
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    values: Vec<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (BatchIsZeroConfig<F>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            values: vec![F::zero(); self.values.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (BatchIsZeroChip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = BatchIsZeroChip::construct(config.0);
+
+        let values: Vec<_> = self.values.iter().map(|v| Value::known(*v)).collect();
+        let all_zero = chip.assign(layouter.namespace(|| "batch is zero"), &values)?;
+
+        layouter.constrain_instance(all_zero.cell(), config.1, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::is_zero::batch::MyCircuit;
+
+    #[test]
+    fn test_all_zero() {
+        let circuit = MyCircuit {
+            values: vec![Fp::zero(); 8],
+        };
+        let prover = MockProver::run(5, &circuit, vec![vec![Fp::one()]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_one_nonzero() {
+        let mut values = vec![Fp::zero(); 8];
+        values[5] = Fp::from(3);
+        let circuit = MyCircuit {
+            values,
+        };
+        let prover = MockProver::run(5, &circuit, vec![vec![Fp::zero()]]).unwrap();
+        prover.assert_satisfied();
+
+        // claiming the batch is all zero must fail
+        let prover = MockProver::run(5, &circuit, vec![vec![Fp::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
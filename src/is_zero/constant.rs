@@ -0,0 +1,147 @@
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    arithmetic::FieldExt,
+};
+use crate::StudyChip;
+use crate::fibonacci::example1::{FibonacciChip, FibonacciConfig};
+
+/// Loads constants into an advice cell through `enable_constant`: the floor planner places
+/// the constant in the fixed column and copies it into the advice cell, so a cell can be
+/// pinned to a value chosen at keygen rather than a public input.
+///```txt
+///        advice  |  constants
+///       ----------------------
+///          c     |     c
+///```
+#[derive(Debug, Clone)]
+pub struct ConstantConfig {
+    pub advice: Column<Advice>,
+    pub constants: Column<Fixed>,
+}
+
+pub struct ConstantChip<F: FieldExt> {
+    config: ConstantConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> ConstantChip<F> {
+    pub fn construct(config: ConstantConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> ConstantConfig {
+        let advice = meta.advice_column();
+        let constants = meta.fixed_column();
+
+        meta.enable_equality(advice);
+        meta.enable_constant(constants);
+
+        ConstantConfig {
+            advice,
+            constants,
+        }
+    }
+
+    /// Returns an advice cell constrained to hold `value`.
+    pub fn assign_constant(&self, mut layouter: impl Layouter<F>, value: F) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load constant",
+            |mut region| region.assign_advice_from_constant(|| "constant", self.config.advice, 0, value)
+        )
+    }
+
+    /// Constrains `cell` to hold `value`.
+    pub fn constrain_equal_to_constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        value: F,
+    ) -> Result<(), Error> {
+        let constant = self.assign_constant(layouter.namespace(|| "load constant"), value)?;
+        layouter.assign_region(
+            || "equal to constant",
+            |mut region| region.constrain_equal(cell.cell(), constant.cell())
+        )
+    }
+}
+
+/// Computes the 9th Fibonacci term from private seeds and pins it to `expected`.
+#[derive(Default)]
+struct MyCircuit<F> {
+    seeds: [F; 2],
+    expected: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (FibonacciConfig, ConstantConfig);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            expected: self.expected,
+            ..Default::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        (FibonacciChip::configure(meta), ConstantChip::configure(meta))
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let fibonacci_chip = FibonacciChip::construct(config.0);
+        let constant_chip = ConstantChip::construct(config.1);
+
+        let (_, mut prev_b, mut prev_c) = fibonacci_chip.assign_private_first_row(
+            layouter.namespace(|| "assign first row"),
+            Value::known(self.seeds[0]),
+            Value::known(self.seeds[1]),
+        )?;
+        for _ in 3..10 {
+            let c_cell = fibonacci_chip.assign_row(
+                layouter.namespace(|| "assign next row"),
+                &prev_b,
+                &prev_c,
+            )?;
+            prev_b = prev_c;
+            prev_c = c_cell;
+        }
+
+        constant_chip.constrain_equal_to_constant(
+            layouter.namespace(|| "f(9) is constant"),
+            &prev_c,
+            self.expected
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::is_zero::constant::MyCircuit;
+
+    #[test]
+    fn test_equal_to_constant() {
+        let circuit = MyCircuit {
+            seeds: [Fp::one(), Fp::one()],
+            expected: Fp::from(55),
+        };
+        let prover = MockProver::run(5, &circuit, vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_wrong_constant() {
+        let circuit = MyCircuit {
+            seeds: [Fp::one(), Fp::one()],
+            expected: Fp::from(56),
+        };
+        let prover = MockProver::run(5, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+/// The inverse hint of `IsZeroChip` as a result of its own: the prover witnesses `inv` and the
+/// gate checks `value * inv = 1`, so `inv` is the inverse of `value`. Zero has no inverse, so
+/// a row where `value == 0` cannot be satisfied at all.
+#[derive(Debug, Clone)]
+pub struct InverseConfig {
+    pub inv: Column<Advice>,
+}
+
+pub struct InverseChip<F: FieldExt> {
+    config: InverseConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> InverseChip<F> {
+    pub fn construct(config: InverseConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        value: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        inv: Column<Advice>,
+    ) -> InverseConfig {
+        meta.enable_equality(inv);
+
+        meta.create_gate("inverse", |meta| {
+            let q_enable = q_enable(meta);
+            let value = value(meta);
+            let inv = meta.query_advice(inv, Rotation::cur());
+
+            vec![q_enable * (value * inv - Expression::Constant(F::one()))]
+        });
+
+        InverseConfig {
+            inv,
+        }
+    }
+
+    /// Returns the cell of `1 / value`. A zero `value` is witnessed with `inv = 0`, which
+    /// the gate rejects.
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let inv = value.map(|value| value.invert().unwrap_or(F::zero()));
+        region.assign_advice(|| "inv", self.config.inv, offset, || inv)
+    }
+}
+
+/// Inverts `value`, exposing the inverse at instance row 0.
+#[derive(Default)]
+struct MyCircuit<F> {
+    value: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (Column<Advice>, Selector, InverseConfig, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        let inv = meta.advice_column();
+        let selector = meta.selector();
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let inverse = InverseChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            inv
+        );
+
+        (value, selector, inverse, instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let (value, selector, inverse, instance) = config;
+        let chip = InverseChip::construct(inverse);
+
+        let inv = layouter.assign_region(
+            || "1 / value",
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "value", value, 0, || Value::known(self.value))?;
+
+                chip.assign(&mut region, 0, Value::known(self.value))
+            }
+        )?;
+
+        layouter.constrain_instance(inv.cell(), instance, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::pasta::group::ff::Field;
+    use crate::is_zero::inverse::MyCircuit;
+
+    #[test]
+    fn test_inverse() {
+        let inv = Fp::from(7).invert().unwrap();
+        assert_eq!(Fp::from(7) * inv, Fp::one());
+
+        let circuit = MyCircuit {
+            value: Fp::from(7),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![inv]]).unwrap();
+        prover.assert_satisfied();
+
+        // 7 is not its own inverse
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(7)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_zero() {
+        let circuit = MyCircuit {
+            value: Fp::zero(),
+        };
+        for inv in [Fp::zero(), Fp::one()] {
+            let prover = MockProver::run(4, &circuit, vec![vec![inv]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
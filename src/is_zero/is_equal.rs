@@ -0,0 +1,62 @@
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    arithmetic::FieldExt,
+};
+use crate::is_zero::{IsZeroChip, IsZeroConfig};
+
+/// `lhs == rhs` is checked as `lhs - rhs == 0`, so the inverse hint is `1 / (lhs - rhs)`.
+#[derive(Debug, Clone)]
+pub struct IsEqualConfig<F> {
+    pub is_zero: IsZeroConfig<F>,
+}
+
+impl<F: FieldExt> IsEqualConfig<F> {
+    /// 1 if `lhs == rhs`, otherwise 0.
+    pub fn expr(&self) -> Expression<F> {
+        self.is_zero.expr()
+    }
+}
+
+pub struct IsEqualChip<F: FieldExt> {
+    config: IsEqualConfig<F>
+}
+
+impl<F: FieldExt> IsEqualChip<F> {
+    pub fn construct(config: IsEqualConfig<F>) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        lhs: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        rhs: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        value_inv: Column<Advice>,
+    ) -> IsEqualConfig<F> {
+        let is_zero = IsZeroChip::configure(
+            meta,
+            q_enable,
+            |meta| lhs(meta) - rhs(meta),
+            value_inv
+        );
+
+        IsEqualConfig {
+            is_zero
+        }
+    }
+
+    /// Returns the cell holding 1 if `lhs == rhs`, otherwise 0.
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: Value<F>,
+        rhs: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let is_zero_chip = IsZeroChip::construct(self.config.is_zero.clone());
+        is_zero_chip.assign(region, offset, lhs - rhs)
+    }
+}
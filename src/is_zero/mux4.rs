@@ -0,0 +1,198 @@
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+use crate::is_zero::is_equal::{IsEqualChip, IsEqualConfig};
+
+/// `out = inputs[s_0 + 2 * s_1]`, generalizing `SelectChip` from one selector bit to two.
+/// The bits are packed into `sel = s_0 + 2 * s_1`, and one `IsEqualChip` per input checks
+/// `sel == i`, so that `out = sum_i (sel == i) * input_i`.
+///```txt
+///        s_0  |  s_1  |  input_0  |  ..  |  input_3  |  out                        |  q_mux
+///       ------------------------------------------------------------------------------------
+///         b0  |  b1   |    x_0    |  ..  |    x_3    |  sum_i (sel == i) * x_i     |    1
+///```
+#[derive(Debug, Clone)]
+pub struct Mux4Config<F> {
+    pub s_0: Column<Advice>,
+    pub s_1: Column<Advice>,
+    pub inputs: [Column<Advice>; 4],
+    pub out: Column<Advice>,
+    pub q_mux: Selector,
+    pub is_equal: [IsEqualConfig<F>; 4],
+}
+
+pub struct Mux4Chip<F: FieldExt> {
+    config: Mux4Config<F>,
+}
+
+impl<F: FieldExt> Mux4Chip<F> {
+    pub fn construct(config: Mux4Config<F>) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Mux4Config<F> {
+        let [s_0, s_1, out] = [(); 3].map(|_| meta.advice_column());
+        let inputs = [(); 4].map(|_| meta.advice_column());
+        let q_mux = meta.selector();
+
+        for column in inputs.iter().chain([&s_0, &s_1, &out]) {
+            meta.enable_equality(*column);
+        }
+
+        let sel = move |meta: &mut VirtualCells<'_, F>| {
+            meta.query_advice(s_0, Rotation::cur())
+                + meta.query_advice(s_1, Rotation::cur()) * Expression::Constant(F::from(2))
+        };
+        let is_equal = [0u64, 1, 2, 3].map(|i| {
+            let value_inv = meta.advice_column();
+            IsEqualChip::configure(
+                meta,
+                |meta| meta.query_selector(q_mux),
+                sel,
+                |_| Expression::Constant(F::from(i)),
+                value_inv
+            )
+        });
+
+        meta.create_gate("mux4", |meta| {
+            let s = meta.query_selector(q_mux);
+            let s_0 = meta.query_advice(s_0, Rotation::cur());
+            let s_1 = meta.query_advice(s_1, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let one = Expression::Constant(F::one());
+
+            let selected = inputs.iter().zip(is_equal.iter()).fold(
+                Expression::Constant(F::zero()),
+                |acc, (input, is_equal)| acc + is_equal.expr() * meta.query_advice(*input, Rotation::cur())
+            );
+
+            Constraints::with_selector(s, [
+                ("s_0 is boolean", s_0.clone() * (one.clone() - s_0)),
+                ("s_1 is boolean", s_1.clone() * (one - s_1)),
+                ("out = sum_i (sel == i) * input_i", out - selected),
+            ])
+        });
+
+        Mux4Config {
+            s_0,
+            s_1,
+            inputs,
+            out,
+            q_mux,
+            is_equal,
+        }
+    }
+
+    /// Returns the cell holding `inputs[s_0 + 2 * s_1]`.
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        (s_0, s_1): (Value<F>, Value<F>),
+        inputs: [Value<F>; 4],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.config.q_mux.enable(region, offset)?;
+
+        region.assign_advice(|| "s_0", self.config.s_0, offset, || s_0)?;
+        region.assign_advice(|| "s_1", self.config.s_1, offset, || s_1)?;
+        for (i, (column, input)) in self.config.inputs.iter().zip(inputs).enumerate() {
+            region.assign_advice(|| format!("input {}", i), *column, offset, || input)?;
+        }
+
+        let sel = s_0 + s_1.map(|s_1| s_1.double());
+        let mut out = Value::known(F::zero());
+        for (i, (config, input)) in self.config.is_equal.iter().zip(inputs).enumerate() {
+            let is_equal_chip = IsEqualChip::construct(config.clone());
+            let is_equal = is_equal_chip.assign(region, offset, sel, Value::known(F::from(i as u64)))?;
+            out = out + is_equal.value().copied() * input;
+        }
+
+        region.assign_advice(|| "out", self.config.out, offset, || out)
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    s_0: F,
+    s_1: F,
+    inputs: [F; 4],
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (Mux4Config<F>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (Mux4Chip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = Mux4Chip::construct(config.0);
+
+        let out = layouter.assign_region(
+            || "mux4",
+            |mut region| {
+                chip.assign(
+                    &mut region,
+                    0,
+                    (Value::known(self.s_0), Value::known(self.s_1)),
+                    self.inputs.map(Value::known),
+                )
+            }
+        )?;
+
+        layouter.constrain_instance(out.cell(), config.1, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::is_zero::mux4::MyCircuit;
+
+    fn inputs() -> [Fp; 4] {
+        [Fp::from(10), Fp::from(11), Fp::from(12), Fp::from(13)]
+    }
+
+    #[test]
+    fn test_mux4() {
+        for i in 0..4u64 {
+            let circuit = MyCircuit {
+                s_0: Fp::from(i & 1),
+                s_1: Fp::from(i >> 1),
+                inputs: inputs(),
+            };
+            let prover = MockProver::run(4, &circuit, vec![vec![inputs()[i as usize]]]).unwrap();
+            prover.assert_satisfied();
+
+            // any other input is rejected
+            let other = inputs()[(i as usize + 1) % 4];
+            let prover = MockProver::run(4, &circuit, vec![vec![other]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_non_boolean_selector() {
+        // s_0 = 2 packs to the selector of input 2, but is not a bit
+        let circuit = MyCircuit {
+            s_0: Fp::from(2),
+            s_1: Fp::zero(),
+            inputs: inputs(),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(12)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
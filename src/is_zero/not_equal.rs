@@ -0,0 +1,160 @@
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+/// `lhs != rhs` exactly when `lhs - rhs` has an inverse, so the prover witnesses it as
+/// `inv` and the gate checks `(lhs - rhs) * inv = 1`. Unlike `IsEqualChip`, which computes
+/// a boolean either way, a row where `lhs == rhs` cannot be satisfied at all.
+/// The product is kept in `out`, a cell holding 1 that other regions can copy.
+#[derive(Debug, Clone)]
+pub struct IsNotEqualConfig {
+    pub inv: Column<Advice>,
+    pub out: Column<Advice>,
+}
+
+pub struct IsNotEqualChip<F: FieldExt> {
+    config: IsNotEqualConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> IsNotEqualChip<F> {
+    pub fn construct(config: IsNotEqualConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        lhs: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        rhs: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        inv: Column<Advice>,
+    ) -> IsNotEqualConfig {
+        let out = meta.advice_column();
+        meta.enable_equality(out);
+
+        meta.create_gate("is_not_equal", |meta| {
+            let q_enable = q_enable(meta);
+            let diff = lhs(meta) - rhs(meta);
+            let inv = meta.query_advice(inv, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+
+            vec![
+                q_enable.clone() * (diff * inv - out.clone()),
+                q_enable * (out - Expression::Constant(F::one())),
+            ]
+        });
+
+        IsNotEqualConfig {
+            inv,
+            out,
+        }
+    }
+
+    /// Returns the cell of `(lhs - rhs) * inv`, which is 1 in any satisfied circuit.
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: Value<F>,
+        rhs: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let diff = lhs - rhs;
+        let inv = diff.map(|diff| diff.invert().unwrap_or(F::zero()));
+        region.assign_advice(|| "inv", self.config.inv, offset, || inv)?;
+
+        region.assign_advice(|| "out", self.config.out, offset, || diff * inv)
+    }
+}
+
+/// Proves `a != b`, exposing the output of the chip at instance row 0.
+#[derive(Default)]
+struct MyCircuit<F> {
+    a: F,
+    b: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (Column<Advice>, Column<Advice>, Selector, IsNotEqualConfig, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let inv = meta.advice_column();
+        let selector = meta.selector();
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let not_equal = IsNotEqualChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(a, Rotation::cur()),
+            |meta| meta.query_advice(b, Rotation::cur()),
+            inv
+        );
+
+        (a, b, selector, not_equal, instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let (a, b, selector, not_equal, instance) = config;
+        let chip = IsNotEqualChip::construct(not_equal);
+
+        let out = layouter.assign_region(
+            || "a != b",
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "a", a, 0, || Value::known(self.a))?;
+                region.assign_advice(|| "b", b, 0, || Value::known(self.b))?;
+
+                chip.assign(&mut region, 0, Value::known(self.a), Value::known(self.b))
+            }
+        )?;
+
+        layouter.constrain_instance(out.cell(), instance, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::is_zero::not_equal::MyCircuit;
+
+    #[test]
+    fn test_not_equal() {
+        let circuit = MyCircuit {
+            a: Fp::from(5),
+            b: Fp::from(3),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::one()]]).unwrap();
+        prover.assert_satisfied();
+
+        // the output is always 1
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::zero()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_equal() {
+        let circuit = MyCircuit {
+            a: Fp::from(5),
+            b: Fp::from(5),
+        };
+        for out in [Fp::one(), Fp::zero()] {
+            let prover = MockProver::run(4, &circuit, vec![vec![out]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+/// `out = cond ? a : b`, the general form of the if/else in example4.
+///```txt
+///        cond  |  a  |  b  |  out                       |  q_select
+///       ------------------------------------------------------------
+///         c    |  a  |  b  |  c * a + (1 - c) * b       |     1
+///```
+#[derive(Debug, Clone)]
+pub struct SelectConfig {
+    pub cond: Column<Advice>,
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub out: Column<Advice>,
+    pub q_select: Selector,
+}
+
+pub struct SelectChip<F: FieldExt> {
+    config: SelectConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> SelectChip<F> {
+    pub fn construct(config: SelectConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> SelectConfig {
+        let [cond, a, b, out] = [(); 4].map(|_| meta.advice_column());
+        let q_select = meta.selector();
+
+        for column in [cond, a, b, out] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("select", |meta| {
+            let s = meta.query_selector(q_select);
+            let cond = meta.query_advice(cond, Rotation::cur());
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let one = Expression::Constant(F::one());
+
+            Constraints::with_selector(s, [
+                ("cond is boolean", cond.clone() * (one.clone() - cond.clone())),
+                ("out = cond * a + (1 - cond) * b", out - (cond.clone() * a + (one - cond) * b)),
+            ])
+        });
+
+        SelectConfig {
+            cond,
+            a,
+            b,
+            out,
+            q_select,
+        }
+    }
+
+    /// Returns the cell holding `a` if `cond` is 1, or `b` if it is 0.
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        cond: Value<F>,
+        a: Value<F>,
+        b: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.config.q_select.enable(region, offset)?;
+
+        region.assign_advice(|| "cond", self.config.cond, offset, || cond)?;
+        region.assign_advice(|| "a", self.config.a, offset, || a)?;
+        region.assign_advice(|| "b", self.config.b, offset, || b)?;
+
+        let out = cond.zip(a).zip(b).map(|((cond, a), b)| cond * a + (F::one() - cond) * b);
+        region.assign_advice(|| "out", self.config.out, offset, || out)
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    cond: F,
+    a: F,
+    b: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (SelectConfig, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (SelectChip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = SelectChip::construct(config.0);
+
+        let out = layouter.assign_region(
+            || "select",
+            |mut region| {
+                chip.assign(
+                    &mut region,
+                    0,
+                    Value::known(self.cond),
+                    Value::known(self.a),
+                    Value::known(self.b),
+                )
+            }
+        )?;
+
+        layouter.constrain_instance(out.cell(), config.1, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::is_zero::select::MyCircuit;
+
+    #[test]
+    fn test_select_a() {
+        let circuit = MyCircuit {
+            cond: Fp::one(),
+            a: Fp::from(3),
+            b: Fp::from(5),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(3)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_select_b() {
+        let circuit = MyCircuit {
+            cond: Fp::zero(),
+            a: Fp::from(3),
+            b: Fp::from(5),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(5)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_non_boolean_cond() {
+        // out = 2 * 3 - 5 satisfies the selection but not the boolean constraint
+        let circuit = MyCircuit {
+            cond: Fp::from(2),
+            a: Fp::from(3),
+            b: Fp::from(5),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,148 @@
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+/// Constrains `c = a xor b` for single bits. Over booleans, `a + b` double counts the
+/// case where both bits are set, so `a xor b = a + b - 2 * a * b`; the expression only
+/// means xor if `a`, `b` and `c` are all constrained to be boolean as well.
+///```txt
+///        a  |  b  |  c  |  selector
+///       ----------------------------
+///        a  |  b  |  c  |     1
+///```
+#[derive(Debug, Clone)]
+pub struct XorBitConfig {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub c: Column<Advice>,
+    pub selector: Selector,
+}
+
+pub struct XorBitChip<F: FieldExt> {
+    config: XorBitConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> XorBitChip<F> {
+    pub fn construct(config: XorBitConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> XorBitConfig {
+        let [a, b, c] = [(); 3].map(|_| meta.advice_column());
+        let selector = meta.selector();
+
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(c);
+
+        meta.create_gate("xor bit", |meta| {
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let c = meta.query_advice(c, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            let two = Expression::Constant(F::from(2u64));
+
+            Constraints::with_selector(s, [
+                ("a is boolean", a.clone() * (one.clone() - a.clone())),
+                ("b is boolean", b.clone() * (one.clone() - b.clone())),
+                ("c is boolean", c.clone() * (one - c.clone())),
+                ("c = a + b - 2ab", a.clone() + b.clone() - two * a * b - c),
+            ])
+        });
+
+        XorBitConfig {
+            a,
+            b,
+            c,
+            selector,
+        }
+    }
+
+    /// Returns the cell of `a xor b`.
+    pub fn assign(&self, mut layouter: impl Layouter<F>, a: Value<F>, b: Value<F>) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "xor bit",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "a", self.config.a, 0, || a)?;
+                region.assign_advice(|| "b", self.config.b, 0, || b)?;
+
+                let c = a.zip(b).map(|(a, b)| a + b - a * b * F::from(2u64));
+                region.assign_advice(|| "c", self.config.c, 0, || c)
+            }
+        )
+    }
+}
+
+/// Exposes `a xor b` at instance row 0.
+#[derive(Default)]
+struct MyCircuit<F> {
+    a: F,
+    b: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (XorBitConfig, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        (XorBitChip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, (config, instance): Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = XorBitChip::construct(config);
+        let c = chip.assign(layouter.namespace(|| "a xor b"), Value::known(self.a), Value::known(self.b))?;
+
+        layouter.constrain_instance(c.cell(), instance, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::is_zero::xor_bit::MyCircuit;
+
+    #[test]
+    fn test_truth_table() {
+        for (a, b, c) in [(0u64, 0u64, 0u64), (0, 1, 1), (1, 0, 1), (1, 1, 0)] {
+            let circuit = MyCircuit {
+                a: Fp::from(a),
+                b: Fp::from(b),
+            };
+            let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(c)]]).unwrap();
+            prover.assert_satisfied();
+
+            let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(1 - c)]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_non_boolean() {
+        // 2 + 0 - 2 * 2 * 0 = 2 satisfies the xor expression, but 2 is not a bit
+        let circuit = MyCircuit {
+            a: Fp::from(2),
+            b: Fp::zero(),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(2)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -1,4 +1,56 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    plonk::ConstraintSystem,
+};
+
+#[cfg(feature = "bench")]
+pub mod bench;
+#[cfg(feature = "debug-dump")]
+mod dump;
 mod fibonacci;
+mod hashes;
 mod is_zero;
 mod range_check;
 mod merkle_tree;
+mod prover;
+#[cfg(test)]
+mod testing;
+mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod zkmips;
+
+/// The interface shared by the example chips: a chip is configured once per circuit,
+/// then constructed from its config in `synthesize`. How a chip assigns its witness
+/// differs between examples, so `assign` stays on the chips; `Output` names what it returns.
+pub(crate) trait StudyChip<F: FieldExt>: Sized {
+    type Config;
+    type Output;
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config;
+
+    fn construct(config: Self::Config) -> Self;
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::ConstraintSystem;
+    use crate::StudyChip;
+    use crate::fibonacci::example1::FibonacciChip;
+    use crate::range_check::example1::RangeCheckChip;
+
+    /// Configures and constructs any chip, returning the advice columns it allocated.
+    fn advice_columns<C: StudyChip<Fp>>() -> usize {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let config = C::configure(&mut meta);
+        C::construct(config);
+        meta.num_advice_columns()
+    }
+
+    #[test]
+    fn test_study_chip() {
+        assert_eq!(advice_columns::<FibonacciChip<Fp>>(), 3);
+        assert_eq!(advice_columns::<RangeCheckChip<Fp, 8>>(), 1);
+    }
+}
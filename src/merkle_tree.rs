@@ -1 +1,13 @@
+mod bits;
+mod builder;
+mod cond_negate;
+mod cond_swap;
+mod membership;
+mod pedersen;
+mod point_eq;
+mod poseidon;
+mod preimage;
+mod update;
+mod var_base_mul;
+mod wide;
 mod sinsemilla;
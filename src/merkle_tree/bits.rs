@@ -0,0 +1,161 @@
+/// Packs little-endian bits into a field element inside the circuit, so that the packing
+/// done by `fold` in the Sinsemilla example is proven rather than trusted.
+/// The bits are laid out from the most significant one down, doubling a running sum:
+///```txt
+///        bit        |  acc                        |  q_first  |  q_rest
+///       ---------------------------------------------------------------
+///        b_{n-1}    |  b_{n-1}                    |     1     |    0
+///        b_{n-2}    |  2 * acc_prev + b_{n-2}     |     0     |    1
+///         ..        |   ..                        |     0     |    1
+///        b_0        |  sum(b_i * 2^i)             |     0     |    1
+///```
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BitsToFieldConfig {
+    bit: Column<Advice>,
+    acc: Column<Advice>,
+    q_first: Selector,
+    q_rest: Selector,
+}
+
+pub(crate) struct BitsToFieldChip<F: FieldExt> {
+    config: BitsToFieldConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> BitsToFieldChip<F> {
+    pub(crate) fn construct(config: BitsToFieldConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> BitsToFieldConfig {
+        let bit = meta.advice_column();
+        let acc = meta.advice_column();
+        let q_first = meta.selector();
+        let q_rest = meta.selector();
+
+        meta.enable_equality(acc);
+
+        meta.create_gate("bits to field", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let q_rest = meta.query_selector(q_rest);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let two = Expression::Constant(F::from(2u64));
+
+            vec![
+                (q_first.clone() + q_rest.clone()) * bit.clone() * (Expression::Constant(F::one()) - bit.clone()),
+                q_first * (acc.clone() - bit.clone()),
+                q_rest * (acc - (acc_prev * two + bit)),
+            ]
+        });
+
+        BitsToFieldConfig {
+            bit,
+            acc,
+            q_first,
+            q_rest,
+        }
+    }
+
+    /// Returns the cell holding `sum(bits[i] * 2^i)`.
+    pub(crate) fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bits: &[Value<F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(!bits.is_empty());
+
+        layouter.assign_region(
+            || "bits to field",
+            |mut region| {
+                let mut acc = Value::known(F::zero());
+                let mut cell = None;
+                for (row, bit) in bits.iter().rev().enumerate() {
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_rest.enable(&mut region, row)?;
+                    }
+
+                    region.assign_advice(|| "bit", self.config.bit, row, || *bit)?;
+                    acc = acc.zip(*bit).map(|(acc, bit)| acc.double() + bit);
+                    cell = Some(region.assign_advice(|| "acc", self.config.acc, row, || acc)?);
+                }
+
+                Ok(cell.unwrap())
+            }
+        )
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    bits: Vec<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (BitsToFieldConfig, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            bits: vec![F::zero(); self.bits.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (BitsToFieldChip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = BitsToFieldChip::construct(config.0);
+
+        let bits: Vec<_> = self.bits.iter().map(|bit| Value::known(*bit)).collect();
+        let value = chip.assign(layouter.namespace(|| "pack bits"), &bits)?;
+
+        layouter.constrain_instance(value.cell(), config.1, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::merkle_tree::bits::MyCircuit;
+
+    #[test]
+    fn test_pack_bits() {
+        // the message of the Sinsemilla example, 1 + 2 + 2^9
+        let bits = [1u64, 1, 0, 0, 0, 0, 0, 0, 0, 1].map(Fp::from);
+        let circuit = MyCircuit {
+            bits: bits.to_vec(),
+        };
+        let prover = MockProver::run(5, &circuit, vec![vec![Fp::from(515)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_non_boolean_bit() {
+        // 0, 2, 0 packs to 4 just like the bits 0, 0, 1, but 2 is not a bit
+        let bits = [0u64, 2, 0].map(Fp::from);
+        let circuit = MyCircuit {
+            bits: bits.to_vec(),
+        };
+        let prover = MockProver::run(5, &circuit, vec![vec![Fp::from(4)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
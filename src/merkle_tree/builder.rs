@@ -0,0 +1,131 @@
+/// Builds a Merkle tree off-circuit and produces the witnesses of the Merkle path circuits.
+use std::fmt::Debug;
+
+/// The two-to-one hash combining sibling nodes into their parent, so that the same builder
+/// makes trees over any hash.
+pub(crate) trait NodeHasher<F> {
+    fn hash(&self, left: F, right: F) -> F;
+
+    /// The hash at `layer`, counted from the leaves, for hashes separated by layer.
+    fn hash_at_layer(&self, _layer: usize, left: F, right: F) -> F {
+        self.hash(left, right)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MerkleTree<F> {
+    /// `levels[0]` holds the leaves and the last level holds the root alone.
+    levels: Vec<Vec<F>>,
+}
+
+impl<F: Copy + Debug> MerkleTree<F> {
+    /// The number of leaves must be a power of two.
+    pub(crate) fn new(leaves: Vec<F>, hasher: &dyn NodeHasher<F>) -> Self {
+        assert!(leaves.len().is_power_of_two(), "the number of leaves must be a power of two");
+
+        let mut levels = vec![leaves];
+        while levels.last().unwrap().len() > 1 {
+            let layer = levels.len() - 1;
+            let level = levels.last().unwrap()
+                .chunks(2)
+                .map(|pair| hasher.hash_at_layer(layer, pair[0], pair[1]))
+                .collect();
+            levels.push(level);
+        }
+
+        MerkleTree {
+            levels
+        }
+    }
+
+    pub(crate) fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    pub(crate) fn root(&self) -> F {
+        self.levels.last().unwrap()[0]
+    }
+
+    /// The siblings from the leaf upwards, whether the node at each layer is the right child,
+    /// and the root.
+    pub(crate) fn authentication_path(&self, leaf_index: usize) -> (Vec<F>, Vec<bool>, F) {
+        assert!(leaf_index < self.levels[0].len(), "leaf index out of range");
+
+        let (siblings, positions): (Vec<F>, Vec<bool>) = self.levels[..self.depth()]
+            .iter()
+            .enumerate()
+            .map(|(l, level)| {
+                let index = leaf_index >> l;
+                (level[index ^ 1], index & 1 == 1)
+            })
+            .unzip();
+
+        (siblings, positions, self.root())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::pasta::pallas;
+    use crate::merkle_tree::builder::{MerkleTree, NodeHasher};
+    use crate::merkle_tree::poseidon::{compute_root, PoseidonHash};
+    use crate::merkle_tree::sinsemilla::path::{merkle_root, SinsemillaHash};
+    use crate::merkle_tree::sinsemilla::DOMAIN;
+
+    /// Adds the children, so that the root is the sum of the leaves.
+    struct AddHash;
+
+    impl NodeHasher<pallas::Base> for AddHash {
+        fn hash(&self, left: pallas::Base, right: pallas::Base) -> pallas::Base {
+            left + right
+        }
+    }
+
+    #[test]
+    fn test_four_leaves() {
+        let leaves: Vec<_> = (0..4).map(|i| pallas::Base::from(i as u64)).collect();
+        let tree = MerkleTree::new(leaves.clone(), &PoseidonHash);
+        assert_eq!(tree.depth(), 2);
+
+        let root = PoseidonHash.hash(
+            PoseidonHash.hash(leaves[0], leaves[1]),
+            PoseidonHash.hash(leaves[2], leaves[3]),
+        );
+        assert_eq!(tree.root(), root);
+
+        for (i, leaf) in leaves.iter().enumerate() {
+            let (siblings, positions, path_root) = tree.authentication_path(i);
+            assert_eq!(path_root, root);
+            assert_eq!(positions, vec![i & 1 == 1, i & 2 == 2]);
+            assert_eq!(siblings[0], leaves[i ^ 1]);
+
+            let siblings: [pallas::Base; 2] = siblings.try_into().unwrap();
+            let positions: [bool; 2] = positions.try_into().unwrap();
+            assert_eq!(compute_root(*leaf, siblings, positions), root);
+        }
+    }
+
+    #[test]
+    fn test_dummy_hasher() {
+        let leaves: Vec<_> = (0..8).map(|i| pallas::Base::from(i as u64)).collect();
+        let tree = MerkleTree::new(leaves.clone(), &AddHash);
+        assert_eq!(tree.root(), pallas::Base::from(28));
+
+        // the same leaves under another hash
+        assert_ne!(MerkleTree::new(leaves, &PoseidonHash).root(), tree.root());
+    }
+
+    #[test]
+    fn test_sinsemilla_hasher() {
+        let leaves: Vec<_> = (0..4).map(|i| pallas::Base::from(i as u64)).collect();
+        let tree = MerkleTree::new(leaves.clone(), &SinsemillaHash::new(DOMAIN.clone()));
+
+        // the layers are hashed like the path circuit hashes them
+        let (siblings, positions, root) = tree.authentication_path(2);
+        let siblings: [pallas::Base; 2] = siblings.try_into().unwrap();
+        let positions: [bool; 2] = positions.try_into().unwrap();
+        assert_eq!(merkle_root(&DOMAIN, leaves[2], siblings, positions), root);
+
+        assert_ne!(MerkleTree::new(leaves, &PoseidonHash).root(), root);
+    }
+}
@@ -0,0 +1,215 @@
+/// This chip negates a point iff a boolean `neg` is 1: the x-coordinate is kept and
+/// `y_out = y * (1 - 2 * neg)`. The output is witnessed as a `NonIdentityPoint` of the ECC chip,
+/// which checks it is on the curve, and its coordinates are bound to `x` and `y_out`.
+///```txt
+///        x   |  y  |  neg  |  y_out              |  q_negate
+///       ------------------------------------------------------
+///        x   |  y  |   n   |  y * (1 - 2 * n)    |     1
+///```
+use halo2_gadgets::{
+    ecc::{
+        chip::*,
+        NonIdentityPoint,
+    },
+    sinsemilla::chip::{SinsemillaChip, SinsemillaConfig},
+};
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    circuit::*,
+    plonk::*,
+    pasta::*,
+    poly::Rotation,
+};
+use crate::merkle_tree::sinsemilla::{
+    configure_chips, TestCommitDomain, TestFixedBases, TestHashDomain,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct CondNegateConfig {
+    x: Column<Advice>,
+    y: Column<Advice>,
+    neg: Column<Advice>,
+    y_out: Column<Advice>,
+    q_negate: Selector,
+}
+
+pub(crate) struct CondNegateChip {
+    config: CondNegateConfig,
+    ecc_chip: EccChip<TestFixedBases>,
+}
+
+impl CondNegateChip {
+    pub(crate) fn construct(config: CondNegateConfig, ecc_chip: EccChip<TestFixedBases>) -> Self {
+        Self {
+            config,
+            ecc_chip,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> CondNegateConfig {
+        let [x, y, neg, y_out] = [(); 4].map(|_| meta.advice_column());
+        let q_negate = meta.selector();
+
+        for column in [x, y, y_out] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("conditional negation", |meta| {
+            let s = meta.query_selector(q_negate);
+            let y = meta.query_advice(y, Rotation::cur());
+            let neg = meta.query_advice(neg, Rotation::cur());
+            let y_out = meta.query_advice(y_out, Rotation::cur());
+            let one = Expression::Constant(pallas::Base::one());
+            let two = Expression::Constant(pallas::Base::from(2));
+
+            Constraints::with_selector(s, [
+                ("neg is boolean", neg.clone() * (one.clone() - neg.clone())),
+                ("y_out = y * (1 - 2 * neg)", y_out - y * (one - two * neg)),
+            ])
+        });
+
+        CondNegateConfig {
+            x,
+            y,
+            neg,
+            y_out,
+            q_negate,
+        }
+    }
+
+    /// Returns `-point` if `neg` is true, otherwise `point`.
+    pub(crate) fn assign(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        point: &NonIdentityPoint<pallas::Affine, EccChip<TestFixedBases>>,
+        neg: Value<bool>,
+    ) -> Result<NonIdentityPoint<pallas::Affine, EccChip<TestFixedBases>>, Error> {
+        let (x, y_out) = layouter.assign_region(
+            || "conditional negation",
+            |mut region| {
+                self.config.q_negate.enable(&mut region, 0)?;
+
+                let x = point.inner().x().copy_advice(|| "x", &mut region, self.config.x, 0)?;
+                let y = point.inner().y().copy_advice(|| "y", &mut region, self.config.y, 0)?;
+                let neg = neg.map(|neg| if neg { pallas::Base::one() } else { pallas::Base::zero() });
+                region.assign_advice(|| "neg", self.config.neg, 0, || neg)?;
+
+                let y_out = y.value().copied() * (Value::known(pallas::Base::one()) - neg.map(|neg| neg.double()));
+                let y_out = region.assign_advice(|| "y_out", self.config.y_out, 0, || y_out)?;
+
+                Ok((x, y_out))
+            }
+        )?;
+
+        let out = NonIdentityPoint::new(
+            self.ecc_chip.clone(),
+            layouter.namespace(|| "output point"),
+            x.value().zip(y_out.value()).map(|(x, y)| pallas::Affine::from_xy(*x, *y).unwrap()),
+        )?;
+
+        layouter.assign_region(
+            || "output = (x, y_out)",
+            |mut region| {
+                region.constrain_equal(out.inner().x().cell(), x.cell())?;
+                region.constrain_equal(out.inner().y().cell(), y_out.cell())
+            }
+        )?;
+
+        Ok(out)
+    }
+}
+
+#[derive(Copy, Clone)]
+struct MyCircuit {
+    point: pallas::Affine,
+    neg: bool,
+}
+
+impl Circuit<pallas::Base> for MyCircuit {
+    type Config = (
+        EccConfig<TestFixedBases>,
+        SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases>,
+        CondNegateConfig,
+        Column<Instance>,
+    );
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        *self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        let (ecc_config, sinsemilla_config) = configure_chips(meta);
+        let cond_negate_config = CondNegateChip::configure(meta);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        (ecc_config, sinsemilla_config, cond_negate_config, instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        // load the lookup table the ecc range checks use
+        SinsemillaChip::load(config.1, &mut layouter)?;
+
+        let ecc_chip = EccChip::construct(config.0);
+        let chip = CondNegateChip::construct(config.2, ecc_chip.clone());
+
+        let point = NonIdentityPoint::new(ecc_chip, layouter.namespace(|| "point"), Value::known(self.point))?;
+        let out = chip.assign(layouter.namespace(|| "cond negate"), &point, Value::known(self.neg))?;
+
+        layouter.constrain_instance(out.inner().x().cell(), config.3, 0)?;
+        layouter.constrain_instance(out.inner().y().cell(), config.3, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::arithmetic::CurveAffine;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::group::{Curve, Group};
+    use halo2_proofs::pasta::pallas;
+    use crate::merkle_tree::cond_negate::MyCircuit;
+
+    fn coordinates(point: pallas::Affine) -> Vec<pallas::Base> {
+        let coordinates = point.coordinates().unwrap();
+        vec![*coordinates.x(), *coordinates.y()]
+    }
+
+    fn point() -> pallas::Affine {
+        (pallas::Point::generator() * pallas::Scalar::from(1234)).to_affine()
+    }
+
+    #[test]
+    fn test_keep() {
+        let k = 11;
+        let circuit = MyCircuit {
+            point: point(),
+            neg: false,
+        };
+        let prover = MockProver::run(k, &circuit, vec![coordinates(point())]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit, vec![coordinates(-point())]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_negate() {
+        let k = 11;
+        let circuit = MyCircuit {
+            point: point(),
+            neg: true,
+        };
+
+        // the y-coordinate is negated, the x-coordinate kept
+        let negated = coordinates(-point());
+        assert_eq!(negated[0], coordinates(point())[0]);
+        assert_eq!(negated[1], -coordinates(point())[1]);
+
+        let prover = MockProver::run(k, &circuit, vec![negated]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit, vec![coordinates(point())]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
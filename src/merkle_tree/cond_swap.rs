@@ -0,0 +1,198 @@
+/// Orders a pair by a boolean, as in halo2_gadgets' `CondSwapChip`:
+/// `(left, right) = swap ? (b, a) : (a, b)`.
+///```txt
+///        a   |  b  |  swap  |  left                  |  right                 |  q_swap
+///       --------------------------------------------------------------------------------
+///        a   |  b  |   s    |  a + s * (b - a)       |  b + s * (a - b)       |    1
+///```
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CondSwapConfig {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    swap: Column<Advice>,
+    left: Column<Advice>,
+    right: Column<Advice>,
+    q_swap: Selector,
+}
+
+pub(crate) struct CondSwapChip<F: FieldExt> {
+    config: CondSwapConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> CondSwapChip<F> {
+    pub(crate) fn construct(config: CondSwapConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> CondSwapConfig {
+        let [a, b, swap, left, right] = [(); 5].map(|_| meta.advice_column());
+        let q_swap = meta.selector();
+
+        for column in [a, b, left, right] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("swap", |meta| {
+            let s = meta.query_selector(q_swap);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let swap = meta.query_advice(swap, Rotation::cur());
+            let left = meta.query_advice(left, Rotation::cur());
+            let right = meta.query_advice(right, Rotation::cur());
+            let one = Expression::Constant(F::one());
+
+            Constraints::with_selector(s, [
+                ("swap is boolean", swap.clone() * (one - swap.clone())),
+                ("left = swap ? b : a", left - a.clone() - swap.clone() * (b.clone() - a.clone())),
+                ("right = swap ? a : b", right - b.clone() - swap * (a - b)),
+            ])
+        });
+
+        CondSwapConfig {
+            a,
+            b,
+            swap,
+            left,
+            right,
+            q_swap,
+        }
+    }
+
+    /// Witnesses a value in the `a` column, for starting a chain of swaps.
+    pub(crate) fn witness(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "witness",
+            |mut region| {
+                region.assign_advice(|| "value", self.config.a, 0, || value)
+            }
+        )
+    }
+
+    /// Copies `a`, witnesses `b`, and returns `(b, a)` if `swap` is true, otherwise `(a, b)`.
+    pub(crate) fn swap(
+        &self,
+        layouter: impl Layouter<F>,
+        pair: (AssignedCell<F, F>, Value<F>),
+        swap: Value<bool>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        self.assign_swap(layouter, pair, swap.map(|swap| if swap { F::one() } else { F::zero() }))
+    }
+
+    /// Takes the boolean as a field element, so that the circuit below can witness a non-boolean one.
+    fn assign_swap(
+        &self,
+        mut layouter: impl Layouter<F>,
+        pair: (AssignedCell<F, F>, Value<F>),
+        swap: Value<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "swap",
+            |mut region| {
+                self.config.q_swap.enable(&mut region, 0)?;
+
+                let a = pair.0.copy_advice(|| "a", &mut region, self.config.a, 0)?;
+                let b = region.assign_advice(|| "b", self.config.b, 0, || pair.1)?;
+                region.assign_advice(|| "swap", self.config.swap, 0, || swap)?;
+
+                let (a, b) = (a.value().copied(), b.value().copied());
+                let left = region.assign_advice(|| "left", self.config.left, 0, || a + swap * (b - a))?;
+                let right = region.assign_advice(|| "right", self.config.right, 0, || b + swap * (a - b))?;
+
+                Ok((left, right))
+            }
+        )
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    a: F,
+    b: F,
+    swap: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (CondSwapConfig, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (CondSwapChip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = CondSwapChip::construct(config.0);
+
+        let a = chip.witness(layouter.namespace(|| "a"), Value::known(self.a))?;
+        let (left, right) = chip.assign_swap(
+            layouter.namespace(|| "swap"),
+            (a, Value::known(self.b)),
+            Value::known(self.swap),
+        )?;
+
+        layouter.constrain_instance(left.cell(), config.1, 0)?;
+        layouter.constrain_instance(right.cell(), config.1, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::merkle_tree::cond_swap::MyCircuit;
+
+    #[test]
+    fn test_no_swap() {
+        let circuit = MyCircuit {
+            a: Fp::from(3),
+            b: Fp::from(5),
+            swap: Fp::zero(),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(3), Fp::from(5)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_swap() {
+        let circuit = MyCircuit {
+            a: Fp::from(3),
+            b: Fp::from(5),
+            swap: Fp::one(),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(5), Fp::from(3)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_non_boolean_swap() {
+        // with swap = 2: left = 3 + 2 * 2, right = 5 - 2 * 2
+        let circuit = MyCircuit {
+            a: Fp::from(3),
+            b: Fp::from(5),
+            swap: Fp::from(2),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(7), Fp::from(1)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
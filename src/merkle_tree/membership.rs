@@ -0,0 +1,194 @@
+/// Proves membership in a committed set without revealing the member: "I know a secret `x`
+/// whose commitment is a leaf of the Merkle tree with public root `R`". The commitment is the
+/// x-coordinate of the blinded Sinsemilla commitment to `x`, as in `preimage`, and the tree is
+/// hashed like `MerklePathChip` hashes it, starting from a copy of the commitment cell. `x`, its
+/// blinding and its path are private witnesses; only the root is exposed, at instance row 0.
+use halo2_gadgets::{
+    ecc::chip::*,
+    sinsemilla::chip::SinsemillaChip,
+};
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    pasta::*,
+};
+use crate::merkle_tree::preimage::{commit, MSG_BITS};
+use crate::merkle_tree::sinsemilla::path::{MerklePathChip, MerklePathConfig};
+use crate::merkle_tree::sinsemilla::DOMAIN;
+
+/// `forged_leaf` replaces the commitment in the message of the first layer, as a prover who
+/// does not know a member's secret would.
+#[derive(Copy, Clone)]
+pub(crate) struct SetMembershipCircuit<const DEPTH: usize> {
+    x: [bool; MSG_BITS],
+    r: pallas::Scalar,
+    siblings: [pallas::Base; DEPTH],
+    position: [bool; DEPTH],
+    forged_leaf: Option<pallas::Base>,
+}
+
+impl<const DEPTH: usize> Default for SetMembershipCircuit<DEPTH> {
+    fn default() -> Self {
+        SetMembershipCircuit {
+            x: [false; MSG_BITS],
+            r: pallas::Scalar::zero(),
+            siblings: [pallas::Base::zero(); DEPTH],
+            position: [false; DEPTH],
+            forged_leaf: None,
+        }
+    }
+}
+
+impl<const DEPTH: usize> Circuit<pallas::Base> for SetMembershipCircuit<DEPTH> {
+    type Config = MerklePathConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        SetMembershipCircuit::default()
+    }
+
+    /// The commitment runs on the chips of the path, over its columns and its table.
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        MerklePathChip::<DEPTH>::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        let ecc_chip = EccChip::construct(config.ecc().clone());
+        let sinsemilla_chip = SinsemillaChip::construct(config.sinsemilla().clone());
+
+        let path_chip = MerklePathChip::<DEPTH>::construct(config);
+        // one generator table serves the commitment and the path
+        path_chip.load_table(&mut layouter)?;
+
+        let point = commit(
+            sinsemilla_chip,
+            ecc_chip,
+            layouter.namespace(|| "commit to x"),
+            &self.x,
+            Value::known(self.r),
+        )?;
+        let leaf = point.inner().x();
+
+        let root = match self.forged_leaf {
+            None => path_chip.assign_from_cell(
+                layouter.namespace(|| "merkle path"),
+                &DOMAIN,
+                &leaf,
+                self.siblings,
+                self.position,
+            )?,
+            Some(forged) => path_chip.assign_forged(
+                layouter.namespace(|| "merkle path"),
+                &DOMAIN,
+                &leaf,
+                self.siblings,
+                self.position,
+                (0, forged),
+            )?,
+        };
+
+        path_chip.expose_public(layouter.namespace(|| "expose root"), &root, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::pallas;
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+    use crate::merkle_tree::builder::MerkleTree;
+    use crate::merkle_tree::membership::SetMembershipCircuit;
+    use crate::merkle_tree::preimage::{commitment, MSG_BITS};
+    use crate::merkle_tree::sinsemilla::path::{MerklePathChip, SinsemillaHash};
+    use crate::merkle_tree::sinsemilla::DOMAIN;
+
+    /// The secret whose bits are the binary digits of `n`.
+    fn secret(n: u32) -> [bool; MSG_BITS] {
+        let mut x = [false; MSG_BITS];
+        for (i, bit) in x.iter_mut().enumerate() {
+            *bit = (n >> i) & 1 == 1;
+        }
+        x
+    }
+
+    /// The blinding the member with secret `n` commits with.
+    fn blinding(n: u32) -> pallas::Scalar {
+        pallas::Scalar::from(0x5eed_0000 + n as u64)
+    }
+
+    /// The tree committing to the set of secrets `members`.
+    fn committed_set(members: &[u32]) -> MerkleTree<pallas::Base> {
+        let leaves = members.iter().map(|n| commitment(&secret(*n), blinding(*n))).collect();
+        MerkleTree::new(leaves, &SinsemillaHash::new(DOMAIN.clone()))
+    }
+
+    fn circuit(x: u32, tree: &MerkleTree<pallas::Base>, index: usize) -> SetMembershipCircuit<2> {
+        let (siblings, position, _) = tree.authentication_path(index);
+        SetMembershipCircuit {
+            x: secret(x),
+            r: blinding(x),
+            siblings: siblings.try_into().unwrap(),
+            position: position.try_into().unwrap(),
+            forged_leaf: None,
+        }
+    }
+
+    #[test]
+    fn test_member() {
+        let tree = committed_set(&[11, 22, 33, 44]);
+        let k = MerklePathChip::<2>::k();
+
+        for (index, x) in [11, 22, 33, 44].into_iter().enumerate() {
+            let prover = MockProver::run(k, &circuit(x, &tree, index), vec![vec![tree.root()]]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_shared_columns() {
+        // the commitment adds no columns to those of the path
+        let mut meta = ConstraintSystem::<pallas::Base>::default();
+        SetMembershipCircuit::<2>::configure(&mut meta);
+        assert_eq!(meta.num_advice_columns(), 10);
+    }
+
+    #[test]
+    fn test_non_member() {
+        let tree = committed_set(&[11, 22, 33, 44]);
+        let k = MerklePathChip::<2>::k();
+
+        // 55 is not in the set, whichever path it claims
+        for index in 0..4 {
+            let prover = MockProver::run(k, &circuit(55, &tree, index), vec![vec![tree.root()]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+
+        // a member under the root of another set
+        let other = committed_set(&[11, 22, 33, 45]);
+        let prover = MockProver::run(k, &circuit(22, &tree, 1), vec![vec![other.root()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_forged_leaf() {
+        let tree = committed_set(&[11, 22, 33, 44]);
+        let k = MerklePathChip::<2>::k();
+
+        // hashing a member's leaf with its honest path, while committing to a secret that is
+        // not in the set, is rejected
+        let circuit = SetMembershipCircuit {
+            forged_leaf: Some(commitment(&secret(22), blinding(22))),
+            ..circuit(55, &tree, 1)
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![tree.root()]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // while forging the honest leaf changes nothing
+        let circuit = SetMembershipCircuit {
+            forged_leaf: Some(commitment(&secret(22), blinding(22))),
+            ..circuit(22, &tree, 1)
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![tree.root()]]).unwrap();
+        prover.assert_satisfied();
+    }
+}
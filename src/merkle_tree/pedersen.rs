@@ -0,0 +1,182 @@
+/// This chip commits to a value `v` with blinding `r` as `v*G + r*H`, where `G` is the pallas
+/// generator and `H` an independent base with no known discrete log relative to `G`. Both
+/// products are fixed-base scalar multiplications over the fixed bases of the sinsemilla tests,
+/// so opening a commitment is recomputing it from `(v, r)` and constraining the two points equal.
+use halo2_gadgets::{
+    ecc::{
+        chip::*,
+        FixedPoint, Point, ScalarFixed,
+    },
+    sinsemilla::chip::{SinsemillaChip, SinsemillaConfig},
+};
+use halo2_proofs::{
+    arithmetic::CurveExt,
+    circuit::*,
+    plonk::*,
+    pasta::*,
+};
+use halo2_proofs::pasta::group::{prime::PrimeCurveAffine, Curve, Group};
+use lazy_static::lazy_static;
+use crate::merkle_tree::sinsemilla::{
+    configure_chips, FullWidth, TestCommitDomain, TestFixedBases, TestHashDomain,
+};
+
+lazy_static! {
+    // the blinding base, hashed to the curve so that its discrete log is unknown
+    static ref BLINDING_BASE: pallas::Affine =
+        pallas::Point::hash_to_curve("halo2-study:pedersen")(b"H").to_affine();
+    static ref BLINDING_ZS_AND_US: Vec<(u64, [pallas::Base; H])> =
+        find_zs_and_us(*BLINDING_BASE, NUM_WINDOWS).unwrap();
+}
+
+/// The commitment `v*G + r*H` computed off-circuit.
+pub(crate) fn pedersen_commit(v: pallas::Scalar, r: pallas::Scalar) -> pallas::Affine {
+    (pallas::Point::generator() * v + *BLINDING_BASE * r).to_affine()
+}
+
+pub(crate) struct PedersenCommitChip {
+    ecc_chip: EccChip<TestFixedBases>,
+    value_base: FullWidth,
+    blinding_base: FullWidth,
+}
+
+impl PedersenCommitChip {
+    pub(crate) fn construct(ecc_chip: EccChip<TestFixedBases>) -> Self {
+        Self {
+            ecc_chip,
+            value_base: FullWidth::from_pallas_generator(),
+            blinding_base: FullWidth::from_parts(*BLINDING_BASE, BLINDING_ZS_AND_US.clone()),
+        }
+    }
+
+    /// Commits to `v` with blinding `r`, returning the commitment point.
+    pub(crate) fn commit(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        v: Value<pallas::Scalar>,
+        r: Value<pallas::Scalar>,
+    ) -> Result<Point<pallas::Affine, EccChip<TestFixedBases>>, Error> {
+        let v = ScalarFixed::new(self.ecc_chip.clone(), layouter.namespace(|| "v"), v)?;
+        let r = ScalarFixed::new(self.ecc_chip.clone(), layouter.namespace(|| "r"), r)?;
+
+        let value_base = FixedPoint::from_inner(self.ecc_chip.clone(), self.value_base.clone());
+        let (v_g, _) = value_base.mul(layouter.namespace(|| "v * G"), v)?;
+
+        let blinding_base = FixedPoint::from_inner(self.ecc_chip.clone(), self.blinding_base.clone());
+        let (r_h, _) = blinding_base.mul(layouter.namespace(|| "r * H"), r)?;
+
+        v_g.add(layouter.namespace(|| "v * G + r * H"), &r_h)
+    }
+
+    /// Constrains `commitment` to open to `(v, r)`.
+    pub(crate) fn open(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        commitment: &Point<pallas::Affine, EccChip<TestFixedBases>>,
+        v: Value<pallas::Scalar>,
+        r: Value<pallas::Scalar>,
+    ) -> Result<(), Error> {
+        let recomputed = self.commit(layouter.namespace(|| "recommit"), v, r)?;
+        recomputed.constrain_equal(layouter.namespace(|| "commitment opens to (v, r)"), commitment)
+    }
+}
+
+/// Witnesses `commitment`, exposes its coordinates at instance rows 0 and 1, and proves it
+/// opens to `(v, r)`.
+#[derive(Copy, Clone)]
+struct MyCircuit {
+    commitment: pallas::Affine,
+    v: pallas::Scalar,
+    r: pallas::Scalar,
+}
+
+impl Circuit<pallas::Base> for MyCircuit {
+    type Config = (
+        EccConfig<TestFixedBases>,
+        SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases>,
+        Column<Instance>,
+    );
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            commitment: pallas::Affine::identity(),
+            v: pallas::Scalar::zero(),
+            r: pallas::Scalar::zero(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        let (ecc, sinsemilla) = configure_chips(meta);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (ecc, sinsemilla, instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        // load the lookup table the ecc range checks use
+        SinsemillaChip::load(config.1, &mut layouter)?;
+
+        let ecc_chip = EccChip::construct(config.0);
+        let chip = PedersenCommitChip::construct(ecc_chip.clone());
+
+        let commitment = Point::new(
+            ecc_chip,
+            layouter.namespace(|| "commitment"),
+            Value::known(self.commitment)
+        )?;
+
+        layouter.constrain_instance(commitment.inner().x().cell(), config.2, 0)?;
+        layouter.constrain_instance(commitment.inner().y().cell(), config.2, 1)?;
+
+        chip.open(
+            layouter.namespace(|| "open"),
+            &commitment,
+            Value::known(self.v),
+            Value::known(self.r)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::arithmetic::CurveAffine;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::pallas;
+    use crate::merkle_tree::pedersen::{pedersen_commit, MyCircuit};
+
+    /// The coordinates of `commitment`, the instance of `MyCircuit`.
+    fn instances(commitment: pallas::Affine) -> Vec<pallas::Base> {
+        let coordinates = commitment.coordinates().unwrap();
+        vec![*coordinates.x(), *coordinates.y()]
+    }
+
+    #[test]
+    fn test_commit() {
+        let k = 11;
+        let v = pallas::Scalar::from(42u64);
+        let r = pallas::Scalar::from(123_456_789u64);
+
+        let circuit = MyCircuit {
+            commitment: pedersen_commit(v, r),
+            v,
+            r,
+        };
+        let prover = MockProver::run(k, &circuit, vec![instances(pedersen_commit(v, r))]).unwrap();
+        prover.assert_satisfied();
+
+        // the proof is about the public commitment, not another one
+        let other = pedersen_commit(v, r + pallas::Scalar::one());
+        let prover = MockProver::run(k, &circuit, vec![instances(other)]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // the commitment does not open to another value
+        let circuit = MyCircuit {
+            commitment: pedersen_commit(v, r),
+            v: pallas::Scalar::from(43u64),
+            r,
+        };
+        let prover = MockProver::run(k, &circuit, vec![instances(pedersen_commit(v, r))]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
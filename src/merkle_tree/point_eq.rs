@@ -0,0 +1,207 @@
+/// Compares two elliptic curve points and returns whether they are equal as a boolean cell,
+/// for circuits that branch on the comparison instead of failing like `constrain_equal`.
+/// The coordinates are copied in and compared with one `IsEqualChip` each:
+///```txt
+///        lhs_x  |  lhs_y  |  rhs_x  |  rhs_y  |  x_inv  |  y_inv  |  out            |  q_eq
+///       -------------------------------------------------------------------------------------
+///         x_1   |   y_1   |   x_2   |   y_2   |   ..    |   ..    |  x_eq * y_eq    |   1
+///```
+use halo2_gadgets::ecc::{
+    chip::*,
+    NonIdentityPoint,
+};
+use halo2_gadgets::sinsemilla::chip::{SinsemillaChip, SinsemillaConfig};
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    pasta::*,
+    poly::Rotation,
+};
+use halo2_proofs::pasta::group::{Curve, Group};
+use crate::is_zero::is_equal::{IsEqualChip, IsEqualConfig};
+use crate::merkle_tree::sinsemilla::{configure_chips, TestCommitDomain, TestFixedBases, TestHashDomain};
+
+#[derive(Debug, Clone)]
+pub(crate) struct PointEqConfig {
+    lhs_x: Column<Advice>,
+    lhs_y: Column<Advice>,
+    rhs_x: Column<Advice>,
+    rhs_y: Column<Advice>,
+    out: Column<Advice>,
+    q_eq: Selector,
+    x_eq: IsEqualConfig<pallas::Base>,
+    y_eq: IsEqualConfig<pallas::Base>,
+}
+
+pub(crate) struct PointEqChip {
+    config: PointEqConfig,
+}
+
+impl PointEqChip {
+    pub(crate) fn construct(config: PointEqConfig) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> PointEqConfig {
+        let [lhs_x, lhs_y, rhs_x, rhs_y, x_inv, y_inv, out] = [(); 7].map(|_| meta.advice_column());
+        let q_eq = meta.selector();
+
+        for column in [lhs_x, lhs_y, rhs_x, rhs_y, out] {
+            meta.enable_equality(column);
+        }
+
+        let x_eq = IsEqualChip::configure(
+            meta,
+            |meta| meta.query_selector(q_eq),
+            |meta| meta.query_advice(lhs_x, Rotation::cur()),
+            |meta| meta.query_advice(rhs_x, Rotation::cur()),
+            x_inv,
+        );
+        let y_eq = IsEqualChip::configure(
+            meta,
+            |meta| meta.query_selector(q_eq),
+            |meta| meta.query_advice(lhs_y, Rotation::cur()),
+            |meta| meta.query_advice(rhs_y, Rotation::cur()),
+            y_inv,
+        );
+
+        meta.create_gate("points are equal", |meta| {
+            let s = meta.query_selector(q_eq);
+            let out = meta.query_advice(out, Rotation::cur());
+
+            Constraints::with_selector(s, [("out = x_eq * y_eq", out - x_eq.expr() * y_eq.expr())])
+        });
+
+        PointEqConfig {
+            lhs_x,
+            lhs_y,
+            rhs_x,
+            rhs_y,
+            out,
+            q_eq,
+            x_eq,
+            y_eq,
+        }
+    }
+
+    /// Returns the cell holding 1 if `lhs == rhs`, otherwise 0.
+    pub(crate) fn assign(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        lhs: &NonIdentityPoint<pallas::Affine, EccChip<TestFixedBases>>,
+        rhs: &NonIdentityPoint<pallas::Affine, EccChip<TestFixedBases>>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        let x_eq_chip = IsEqualChip::construct(self.config.x_eq.clone());
+        let y_eq_chip = IsEqualChip::construct(self.config.y_eq.clone());
+
+        layouter.assign_region(
+            || "point equality",
+            |mut region| {
+                self.config.q_eq.enable(&mut region, 0)?;
+
+                let lhs_x = lhs.inner().x().copy_advice(|| "lhs x", &mut region, self.config.lhs_x, 0)?;
+                let lhs_y = lhs.inner().y().copy_advice(|| "lhs y", &mut region, self.config.lhs_y, 0)?;
+                let rhs_x = rhs.inner().x().copy_advice(|| "rhs x", &mut region, self.config.rhs_x, 0)?;
+                let rhs_y = rhs.inner().y().copy_advice(|| "rhs y", &mut region, self.config.rhs_y, 0)?;
+
+                let x_eq = x_eq_chip.assign(&mut region, 0, lhs_x.value().copied(), rhs_x.value().copied())?;
+                let y_eq = y_eq_chip.assign(&mut region, 0, lhs_y.value().copied(), rhs_y.value().copied())?;
+
+                let out = x_eq.value().copied() * y_eq.value();
+                region.assign_advice(|| "out", self.config.out, 0, || out)
+            }
+        )
+    }
+}
+
+/// Witnesses two points and exposes whether they are equal.
+#[derive(Copy, Clone)]
+struct MyCircuit {
+    lhs: pallas::Affine,
+    rhs: pallas::Affine,
+}
+
+impl Circuit<pallas::Base> for MyCircuit {
+    type Config = (
+        EccConfig<TestFixedBases>,
+        SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases>,
+        PointEqConfig,
+        Column<Instance>,
+    );
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        let generator = pallas::Point::generator().to_affine();
+        MyCircuit {
+            lhs: generator,
+            rhs: generator,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        let (ecc_config, sinsemilla_config) = configure_chips(meta);
+        let point_eq_config = PointEqChip::configure(meta);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        (ecc_config, sinsemilla_config, point_eq_config, instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        // load the lookup table the ecc range checks use
+        SinsemillaChip::load(config.1, &mut layouter)?;
+
+        let ecc_chip = EccChip::construct(config.0);
+        let lhs = NonIdentityPoint::new(ecc_chip.clone(), layouter.namespace(|| "lhs"), Value::known(self.lhs))?;
+        let rhs = NonIdentityPoint::new(ecc_chip, layouter.namespace(|| "rhs"), Value::known(self.rhs))?;
+
+        let chip = PointEqChip::construct(config.2);
+        let out = chip.assign(layouter.namespace(|| "lhs == rhs"), &lhs, &rhs)?;
+
+        layouter.constrain_instance(out.cell(), config.3, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::{pallas, Fp};
+    use halo2_proofs::pasta::group::{Curve, Group};
+    use crate::merkle_tree::point_eq::MyCircuit;
+
+    #[test]
+    fn test_equal_points() {
+        let point = pallas::Point::generator().double().to_affine();
+        let circuit = MyCircuit {
+            lhs: point,
+            rhs: point,
+        };
+        let prover = MockProver::run(11, &circuit, vec![vec![Fp::one()]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_different_points() {
+        // same x, different y
+        let point = pallas::Point::generator();
+        let circuit = MyCircuit {
+            lhs: point.to_affine(),
+            rhs: (-point).to_affine(),
+        };
+        let prover = MockProver::run(11, &circuit, vec![vec![Fp::zero()]]).unwrap();
+        prover.assert_satisfied();
+
+        let circuit = MyCircuit {
+            lhs: point.to_affine(),
+            rhs: point.double().to_affine(),
+        };
+        let prover = MockProver::run(11, &circuit, vec![vec![Fp::zero()]]).unwrap();
+        prover.assert_satisfied();
+
+        // claiming they are equal
+        let prover = MockProver::run(11, &circuit, vec![vec![Fp::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,206 @@
+/// Verifies a Merkle authentication path where every node is `Poseidon(left, right)`.
+/// Each layer orders the current node and its sibling by the position bit with `CondSwapChip`,
+/// then hashes them.
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength, P128Pow5T3},
+    Hash, Pow5Chip, Pow5Config,
+};
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    pasta::*,
+};
+use crate::merkle_tree::builder::NodeHasher;
+use crate::merkle_tree::cond_swap::{CondSwapChip, CondSwapConfig};
+
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+
+#[derive(Debug, Clone)]
+pub(crate) struct PoseidonMerklePathConfig {
+    cond_swap_config: CondSwapConfig,
+    instance: Column<Instance>,
+    poseidon_config: Pow5Config<pallas::Base, WIDTH, RATE>,
+}
+
+pub(crate) struct PoseidonMerklePathChip<const DEPTH: usize> {
+    config: PoseidonMerklePathConfig,
+}
+
+impl<const DEPTH: usize> PoseidonMerklePathChip<DEPTH> {
+    pub(crate) fn construct(config: PoseidonMerklePathConfig) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> PoseidonMerklePathConfig {
+        let cond_swap_config = CondSwapChip::configure(meta);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let state = [(); WIDTH].map(|_| meta.advice_column());
+        let partial_sbox = meta.advice_column();
+
+        // fixed columns for the round constants
+        let rc_a = [(); WIDTH].map(|_| meta.fixed_column());
+        let rc_b = [(); WIDTH].map(|_| meta.fixed_column());
+        meta.enable_constant(rc_b[0]);
+
+        let poseidon_config = Pow5Chip::configure::<P128Pow5T3>(
+            meta,
+            state,
+            partial_sbox,
+            rc_a,
+            rc_b,
+        );
+
+        PoseidonMerklePathConfig {
+            cond_swap_config,
+            instance,
+            poseidon_config,
+        }
+    }
+
+    /// Hashes `leaf` up through `siblings`, where `position[i]` is true when the node at
+    /// layer `i` is the right child, and returns the cell holding the root.
+    pub(crate) fn assign(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        leaf: Value<pallas::Base>,
+        siblings: [Value<pallas::Base>; DEPTH],
+        position: [Value<bool>; DEPTH],
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        let cond_swap_chip = CondSwapChip::construct(self.config.cond_swap_config);
+        let mut node = cond_swap_chip.witness(layouter.namespace(|| "leaf"), leaf)?;
+
+        for l in 0..DEPTH {
+            let (left, right) = cond_swap_chip.swap(
+                layouter.namespace(|| format!("swap layer {}", l)),
+                (node, siblings[l]),
+                position[l],
+            )?;
+
+            let hasher = Hash::<_, _, P128Pow5T3, ConstantLength<2>, WIDTH, RATE>::init(
+                Pow5Chip::construct(self.config.poseidon_config.clone()),
+                layouter.namespace(|| format!("init layer {}", l))
+            )?;
+            node = hasher.hash(layouter.namespace(|| format!("hash layer {}", l)), [left, right])?;
+        }
+
+        Ok(node)
+    }
+
+    pub(crate) fn expose_public(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        cell: &AssignedCell<pallas::Base, pallas::Base>,
+        row: usize
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// The node hash of the tree, for building trees with `MerkleTree`.
+pub(crate) struct PoseidonHash;
+
+impl NodeHasher<pallas::Base> for PoseidonHash {
+    fn hash(&self, left: pallas::Base, right: pallas::Base) -> pallas::Base {
+        poseidon::Hash::<_, P128Pow5T3, ConstantLength<2>, WIDTH, RATE>::init().hash([left, right])
+    }
+}
+
+/// Computes the root off-circuit, for checking the circuit against.
+pub(crate) fn compute_root<const DEPTH: usize>(
+    leaf: pallas::Base,
+    siblings: [pallas::Base; DEPTH],
+    position: [bool; DEPTH],
+) -> pallas::Base {
+    (0..DEPTH).fold(leaf, |node, l| {
+        let message = if position[l] {
+            [siblings[l], node]
+        } else {
+            [node, siblings[l]]
+        };
+        let [left, right] = message;
+        PoseidonHash.hash(left, right)
+    })
+}
+
+#[derive(Clone, Copy)]
+struct MyCircuit<const DEPTH: usize> {
+    leaf: Value<pallas::Base>,
+    siblings: [Value<pallas::Base>; DEPTH],
+    position: [Value<bool>; DEPTH],
+}
+
+impl<const DEPTH: usize> Default for MyCircuit<DEPTH> {
+    fn default() -> Self {
+        MyCircuit {
+            leaf: Value::unknown(),
+            siblings: [Value::unknown(); DEPTH],
+            position: [Value::unknown(); DEPTH],
+        }
+    }
+}
+
+impl<const DEPTH: usize> Circuit<pallas::Base> for MyCircuit<DEPTH> {
+    type Config = PoseidonMerklePathConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        PoseidonMerklePathChip::<DEPTH>::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        let chip = PoseidonMerklePathChip::<DEPTH>::construct(config);
+
+        let root = chip.assign(
+            layouter.namespace(|| "merkle path"),
+            self.leaf,
+            self.siblings,
+            self.position,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "expose root"), &root, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::Value;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::pallas;
+    use crate::merkle_tree::poseidon::{compute_root, MyCircuit};
+
+    #[test]
+    fn test_depth_8() {
+        let leaf = pallas::Base::from(7);
+        let mut siblings = [pallas::Base::zero(); 8];
+        for (i, sibling) in siblings.iter_mut().enumerate() {
+            *sibling = pallas::Base::from(100 + i as u64);
+        }
+        let position = [true, false, false, true, true, false, true, false];
+        let root = compute_root(leaf, siblings, position);
+
+        let circuit = MyCircuit {
+            leaf: Value::known(leaf),
+            siblings: siblings.map(Value::known),
+            position: position.map(Value::known),
+        };
+        let prover = MockProver::run(10, &circuit, vec![vec![root]]).unwrap();
+        prover.assert_satisfied();
+
+        // a leaf that is not in the tree does not reach the root
+        let circuit = MyCircuit {
+            leaf: Value::known(leaf + pallas::Base::one()),
+            ..circuit
+        };
+        let prover = MockProver::run(10, &circuit, vec![vec![root]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,174 @@
+/// Proves knowledge of a secret message committed to by a public commitment, without revealing
+/// the message. The commitment is the Sinsemilla commitment `hash(msg) + r*R` of the message
+/// blinded by a random scalar `r`, so that even a message of a few bits cannot be recovered by
+/// hashing every candidate. `msg` and `r` are private witnesses; only the x-coordinate of the
+/// commitment is exposed, at instance row 0, and the verifier checks it against the commitment.
+use halo2_gadgets::{
+    ecc::{chip::*, Point, ScalarFixed},
+    sinsemilla::{
+        chip::{SinsemillaChip, SinsemillaConfig},
+        CommitDomain,
+    },
+};
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    circuit::*,
+    plonk::*,
+    pasta::*,
+};
+use halo2_proofs::pasta::group::Curve;
+use lazy_static::lazy_static;
+use crate::merkle_tree::sinsemilla::{
+    configure_chips, Domain, SinsemillaHasher, TestCommitDomain, TestFixedBases, TestHashDomain, TestSinsemillaChip,
+};
+
+/// The domain the commitments are computed in.
+const PERSONALIZATION: &str = "halo2-study:preimage";
+lazy_static! {
+    pub(crate) static ref DOMAIN: Domain = Domain::new(PERSONALIZATION);
+}
+
+/// The bits of a secret message, a whole number of 10-bit words.
+pub(crate) const MSG_BITS: usize = 20;
+
+/// The public commitment to `msg` blinded by `r`, the x-coordinate of the commitment point.
+pub(crate) fn commitment(msg: &[bool], r: pallas::Scalar) -> pallas::Base {
+    let point = DOMAIN
+        .primitive_commit()
+        .commit(msg.iter().copied(), &r)
+        .unwrap()
+        .to_affine();
+    *point.coordinates().unwrap().x()
+}
+
+/// Commits to `msg` blinded by `r` in the commit domain of `DOMAIN`, returning the commitment
+/// point whose x-coordinate `commitment` computes.
+pub(crate) fn commit(
+    sinsemilla_chip: TestSinsemillaChip,
+    ecc_chip: EccChip<TestFixedBases>,
+    mut layouter: impl Layouter<pallas::Base>,
+    msg: &[bool],
+    r: Value<pallas::Scalar>,
+) -> Result<Point<pallas::Affine, EccChip<TestFixedBases>>, Error> {
+    let hasher = SinsemillaHasher::new(sinsemilla_chip.clone(), ecc_chip.clone(), &DOMAIN);
+    let message = hasher.message(layouter.namespace(|| "secret message"), msg)?;
+
+    let r = ScalarFixed::new(ecc_chip.clone(), layouter.namespace(|| "r"), r)?;
+    let commit_domain = CommitDomain::new(sinsemilla_chip, ecc_chip, DOMAIN.commit_domain());
+    let (point, _) = commit_domain.commit(layouter.namespace(|| "commit"), message, r)?;
+
+    Ok(point)
+}
+
+#[derive(Copy, Clone)]
+struct MyCircuit {
+    msg: [bool; MSG_BITS],
+    r: pallas::Scalar,
+}
+
+impl Circuit<pallas::Base> for MyCircuit {
+    type Config = (
+        EccConfig<TestFixedBases>,
+        SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases>,
+        Column<Instance>,
+    );
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            msg: [false; MSG_BITS],
+            r: pallas::Scalar::zero(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        let (ecc_config, sinsemilla_config) = configure_chips(meta);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        (ecc_config, sinsemilla_config, instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        let ecc_chip = EccChip::construct(config.0);
+
+        // load the lookup table
+        SinsemillaChip::load(config.1.clone(), &mut layouter)?;
+
+        let point = commit(
+            SinsemillaChip::construct(config.1),
+            ecc_chip,
+            layouter.namespace(|| "commit to msg"),
+            &self.msg,
+            Value::known(self.r),
+        )?;
+
+        layouter.constrain_instance(point.inner().x().cell(), config.2, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::pallas;
+    use crate::merkle_tree::preimage::{commitment, MyCircuit, MSG_BITS};
+
+    fn secret() -> [bool; MSG_BITS] {
+        let mut msg = [false; MSG_BITS];
+        for i in [0, 3, 4, 9, 11, 17] {
+            msg[i] = true;
+        }
+        msg
+    }
+
+    fn blinding() -> pallas::Scalar {
+        pallas::Scalar::from(0x5eed_b11d_u64)
+    }
+
+    #[test]
+    fn test_valid_preimage() {
+        let k = 11;
+        let circuit = MyCircuit {
+            msg: secret(),
+            r: blinding(),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![commitment(&secret(), blinding())]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_wrong_commitment() {
+        let k = 11;
+        let circuit = MyCircuit {
+            msg: secret(),
+            r: blinding(),
+        };
+
+        // the commitment to another message
+        let mut other = secret();
+        other[1] = true;
+        let prover = MockProver::run(k, &circuit, vec![vec![commitment(&other, blinding())]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        let prover = MockProver::run(k, &circuit, vec![vec![pallas::Base::zero()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_blinding() {
+        let k = 11;
+
+        // the same message under another blinding commits to another value, so hashing
+        // candidate messages does not reveal it
+        let r = blinding() + pallas::Scalar::one();
+        assert_ne!(commitment(&secret(), r), commitment(&secret(), blinding()));
+
+        // and the commitment does not open with another blinding
+        let circuit = MyCircuit {
+            msg: secret(),
+            r,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![commitment(&secret(), blinding())]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
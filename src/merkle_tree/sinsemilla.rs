@@ -1,3 +1,6 @@
+pub(crate) mod pack;
+pub(crate) mod path;
+
 use halo2_gadgets::{
     ecc::{
         chip::*,
@@ -18,17 +21,20 @@ use halo2_proofs::{
     plonk::*,
     pasta::*,
     pasta::group::ff::PrimeField,
+    poly::Rotation,
 };
 use halo2_proofs::pasta::group::{Curve, Group};
 
+use pack::pack_bits_for_sinsemilla;
+
 // to initialize the global variables, these variables compose some struct.
 use lazy_static::lazy_static;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
-struct TestFixedBases;
+pub(crate) struct TestFixedBases;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
-struct FullWidth(pallas::Affine, &'static [(u64, [pallas::Base; H])]);
+pub(crate) struct FullWidth(pallas::Affine, Vec<(u64, [pallas::Base; H])>);
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 struct BaseField;
@@ -36,8 +42,8 @@ struct BaseField;
 #[derive(Debug, Eq, PartialEq, Clone)]
 struct Short;
 
-// to create the commit domain of sinsemilla
-const PERSONALIZATION: &str = "MerkleCRH";
+// the default personalization of the sinsemilla commit domain
+pub(crate) const PERSONALIZATION: &str = "MerkleCRH";
 lazy_static! {
     // the generator point of elliptic curve
     static ref BASE: pallas::Affine = pallas::Point::generator().to_affine();
@@ -46,29 +52,69 @@ lazy_static! {
         find_zs_and_us(*BASE, NUM_WINDOWS).unwrap();
     static ref ZS_AND_US_SHORT: Vec<(u64, [pallas::Base; H])> =
         find_zs_and_us(*BASE, NUM_WINDOWS_SHORT).unwrap();
-
-    // create the commit domain
-    static ref COMMIT_DOMAIN: sinsemilla::CommitDomain = sinsemilla::CommitDomain::new(PERSONALIZATION);
-    static ref Q: pallas::Affine = COMMIT_DOMAIN.Q().to_affine();
-    static ref R: pallas::Affine = COMMIT_DOMAIN.R().to_affine();
-    static ref R_ZS_AND_US: Vec<(u64, [pallas::Base; H])> =
-        find_zs_and_us(*R, NUM_WINDOWS).unwrap();
+    // the domains of the default personalization, built once rather than in every synthesize
+    pub(crate) static ref DOMAIN: Domain = Domain::new(PERSONALIZATION);
 }
 
 impl FullWidth {
-    #[allow(dead_code)]
     pub(crate) fn from_pallas_generator() -> Self {
-        FullWidth(*BASE, &ZS_AND_US)
+        FullWidth(*BASE, ZS_AND_US.clone())
     }
 
     pub(crate) fn from_parts(
         base: pallas::Affine,
-        zs_and_us: &'static [(u64, [pallas::Base; H])],
+        zs_and_us: Vec<(u64, [pallas::Base; H])>,
     ) -> Self {
         FullWidth(base, zs_and_us)
     }
 }
 
+/// The sinsemilla domains derived from a personalization string,
+/// so that a circuit can use its own domain separator.
+#[derive(Debug, Clone)]
+pub(crate) struct Domain {
+    personalization: String,
+    hash_domain: TestHashDomain,
+    commit_domain: TestCommitDomain,
+}
+
+impl Domain {
+    pub(crate) fn new(personalization: &str) -> Self {
+        // create the commit domain
+        let commit_domain = sinsemilla::CommitDomain::new(personalization);
+        let q = commit_domain.Q().to_affine();
+        let r = commit_domain.R().to_affine();
+        let r_zs_and_us = find_zs_and_us(r, NUM_WINDOWS).unwrap();
+
+        Domain {
+            personalization: personalization.to_string(),
+            hash_domain: TestHashDomain(q),
+            commit_domain: TestCommitDomain(
+                FullWidth::from_parts(r, r_zs_and_us),
+                TestHashDomain(q),
+            ),
+        }
+    }
+
+    pub(crate) fn hash_domain(&self) -> &TestHashDomain {
+        &self.hash_domain
+    }
+
+    pub(crate) fn commit_domain(&self) -> &TestCommitDomain {
+        &self.commit_domain
+    }
+
+    /// The off-circuit hash domain matching `hash_domain`.
+    pub(crate) fn primitive(&self) -> sinsemilla::HashDomain {
+        sinsemilla::HashDomain::new(&format!("{}-M", self.personalization))
+    }
+
+    /// The off-circuit commit domain matching `commit_domain`.
+    pub(crate) fn primitive_commit(&self) -> sinsemilla::CommitDomain {
+        sinsemilla::CommitDomain::new(&self.personalization)
+    }
+}
+
 impl FixedPoint<pallas::Affine> for FullWidth {
     type FixedScalarKind = FullScalar;
 
@@ -166,30 +212,171 @@ impl FixedPoints<pallas::Affine> for TestFixedBases {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-pub(crate) struct TestHashDomain;
+pub(crate) struct TestHashDomain(pallas::Affine);
 impl HashDomains<pallas::Affine> for TestHashDomain {
     #[allow(non_snake_case)]
     fn Q(&self) -> pallas::Affine {
-        *Q
+        self.0
     }
 }
 
 // This test does not make use of the CommitDomain.
 #[derive(Debug, Clone, Eq, PartialEq)]
-pub(crate) struct TestCommitDomain;
+pub(crate) struct TestCommitDomain(FullWidth, TestHashDomain);
 impl CommitDomains<pallas::Affine, TestFixedBases, TestHashDomain> for TestCommitDomain {
     fn r(&self) -> FullWidth {
-        FullWidth::from_parts(*R, &R_ZS_AND_US)
+        self.0.clone()
     }
 
     fn hash_domain(&self) -> TestHashDomain {
-        TestHashDomain
+        self.1.clone()
     }
 }
 
-#[derive(Default, Copy, Clone)]
+pub(crate) type TestSinsemillaChip = SinsemillaChip<TestHashDomain, TestCommitDomain, TestFixedBases>;
+
+/// The most words in one message piece; 25 words are 250 bits, within the capacity of the base field.
+const MAX_PIECE_WORDS: usize = 25;
+
+/// The bits of `bytes`, each byte little-endian.
+fn bytes_to_bits(bytes: &[u8]) -> Vec<bool> {
+    bytes.iter().flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1)).collect()
+}
+
+/// A message to hash with a `SinsemillaHasher`.
+pub(crate) type TestMessage = Message<pallas::Affine, TestSinsemillaChip, { sinsemilla::K }, { sinsemilla::C }>;
+
+/// A sinsemilla chip together with the hash domain of a `Domain`, built once so that
+/// every message a circuit hashes goes through the same handler.
+pub(crate) struct SinsemillaHasher {
+    chip: TestSinsemillaChip,
+    hash_domain: HashDomain<pallas::Affine, TestSinsemillaChip, EccChip<TestFixedBases>, { sinsemilla::K }, { sinsemilla::C }>,
+}
+
+impl SinsemillaHasher {
+    pub(crate) fn new(chip: TestSinsemillaChip, ecc_chip: EccChip<TestFixedBases>, domain: &Domain) -> Self {
+        let hash_domain = HashDomain::new(chip.clone(), ecc_chip, domain.hash_domain());
+        SinsemillaHasher {
+            chip,
+            hash_domain,
+        }
+    }
+
+    /// Witnesses `bits`, a whole number of 10-bit words, as a message, split into pieces of
+    /// at most `MAX_PIECE_WORDS` words so that every piece fits in a field element.
+    pub(crate) fn message(&self, mut layouter: impl Layouter<pallas::Base>, bits: &[bool]) -> Result<TestMessage, Error> {
+        assert_eq!(bits.len() % sinsemilla::K, 0);
+
+        let pieces = bits
+            .chunks(MAX_PIECE_WORDS * sinsemilla::K)
+            .enumerate()
+            .map(|(i, piece)| {
+                MessagePiece::from_field_elem(
+                    self.chip.clone(),
+                    layouter.namespace(|| format!("piece {}", i)),
+                    Value::known(pack_bits_for_sinsemilla(piece)),
+                    piece.len() / sinsemilla::K
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Message::from_pieces(self.chip.clone(), pieces))
+    }
+
+    /// Hashes `bytes` to a point, each byte as its 8 little-endian bits. The bits are padded
+    /// with zeros to a whole number of words, as the off-circuit hash pads them.
+    pub(crate) fn hash_bytes(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        bytes: &[u8],
+    ) -> Result<NonIdentityPoint<pallas::Affine, EccChip<TestFixedBases>>, Error> {
+        let mut bits = bytes_to_bits(bytes);
+        let padding = (sinsemilla::K - bits.len() % sinsemilla::K) % sinsemilla::K;
+        bits.extend(std::iter::repeat(false).take(padding));
+
+        let message = self.message(layouter.namespace(|| "message"), &bits)?;
+        let mut points = self.hash_many(layouter.namespace(|| "hash bytes"), vec![message])?;
+        Ok(points.remove(0))
+    }
+
+    /// Hashes every one of `messages` to a point, in order.
+    pub(crate) fn hash_many(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        messages: Vec<TestMessage>,
+    ) -> Result<Vec<NonIdentityPoint<pallas::Affine, EccChip<TestFixedBases>>>, Error> {
+        messages
+            .into_iter()
+            .enumerate()
+            .map(|(i, message)| {
+                let (point, _) = self.hash_domain.hash_to_point(
+                    layouter.namespace(|| format!("hash to point {}", i)),
+                    message
+                )?;
+                Ok(point)
+            })
+            .collect()
+    }
+}
+
+/// Configures the ecc and sinsemilla chips the hashing circuits share.
+pub(crate) fn configure_chips(meta: &mut ConstraintSystem<pallas::Base>) -> (
+    EccConfig<TestFixedBases>,
+    SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases>,
+) {
+    let advices = [(); 10].map(|_| meta.advice_column());
+    configure_with_columns(meta, advices)
+}
+
+/// Like `configure_chips`, but on `advices` from the caller, so that a larger circuit can share
+/// its column pool with the chips. The sinsemilla chip uses the first five, and the range
+/// check the last.
+pub(crate) fn configure_with_columns(meta: &mut ConstraintSystem<pallas::Base>, advices: [Column<Advice>; 10]) -> (
+    EccConfig<TestFixedBases>,
+    SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases>,
+) {
+    // Shared fixed column for loading constants
+    let constants = meta.fixed_column();
+    meta.enable_constant(constants);
+
+    let lagrange_coeffs = [(); 8].map(|_| meta.fixed_column());
+    let table_idx = meta.lookup_table_column();
+    let range_check = LookupRangeCheckConfig::configure(
+        meta,
+        advices[9],
+        table_idx
+    );
+
+    let ecc_config = EccChip::<TestFixedBases>::configure(
+        meta,
+        advices,
+        lagrange_coeffs,
+        range_check
+    );
+
+    // fixed columns for the sinsemilla generator lookup table
+    let lookup = (
+        table_idx,
+        meta.lookup_table_column(),
+        meta.lookup_table_column(),
+    );
+
+    let sinsemilla_config = SinsemillaChip::configure(
+        meta,
+        advices[..5].try_into().unwrap(),
+        advices[2],
+        lagrange_coeffs[0],
+        lookup,
+        range_check,
+    );
+
+    (ecc_config, sinsemilla_config)
+}
+
+#[derive(Copy, Clone)]
 struct MyCircuit {
     data: [bool; 10],
+    domain: &'static Domain,
 }
 
 impl Circuit<pallas::Base> for MyCircuit {
@@ -200,48 +387,14 @@ impl Circuit<pallas::Base> for MyCircuit {
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        MyCircuit::default()
+        MyCircuit {
+            data: [false; 10],
+            domain: self.domain,
+        }
     }
 
     fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
-        let advices = [(); 10].map(|_| meta.advice_column());
-
-        // Shared fixed column for loading constants
-        let constants = meta.fixed_column();
-        meta.enable_constant(constants);
-
-        let lagrange_coeffs = [(); 8].map(|_| meta.fixed_column());
-        let table_idx = meta.lookup_table_column();
-        let range_check = LookupRangeCheckConfig::configure(
-            meta,
-            advices[9],
-            table_idx
-        );
-
-        let ecc_config = EccChip::<TestFixedBases>::configure(
-            meta,
-            advices,
-            lagrange_coeffs,
-            range_check
-        );
-
-        // fixed columns for the sinsemilla generator lookup table
-        let lookup = (
-            table_idx,
-            meta.lookup_table_column(),
-            meta.lookup_table_column(),
-        );
-
-        let sinsemilla_config = SinsemillaChip::configure(
-            meta,
-            advices[..5].try_into().unwrap(),
-            advices[2],
-            lagrange_coeffs[0],
-            lookup,
-            range_check,
-        );
-
-        (ecc_config, sinsemilla_config)
+        configure_chips(meta)
     }
 
     fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
@@ -251,31 +404,12 @@ impl Circuit<pallas::Base> for MyCircuit {
         SinsemillaChip::load(config.1.clone(), &mut layouter)?;
 
         let sinsemilla_chip = SinsemillaChip::construct(config.1);
+        let hasher = SinsemillaHasher::new(sinsemilla_chip, ecc_chip.clone(), self.domain);
 
-        let hash_handler = HashDomain::new(
-            sinsemilla_chip.clone(),
-            ecc_chip.clone(),
-            &TestHashDomain
-        );
-
-        let field_ele = self.data.into_iter().rev().fold(pallas::Base::zero(), |acc, bit| {
-            if bit {
-                acc.double() + pallas::Base::one()
-            } else {
-                acc.double()
-            }
-        });
-
-        let message_piece = MessagePiece::from_field_elem(
-            sinsemilla_chip.clone(),
-            layouter.namespace(|| "message"),
-            Value::known(field_ele),
-            1
-        )?;
-
+        let message = hasher.message(layouter.namespace(|| "message"), &self.data)?;
 
         let expected_point= {
-            let hash_handler = sinsemilla::HashDomain::new(&format!("{}-M", PERSONALIZATION));
+            let hash_handler = self.domain.primitive();
             let expected_point = hash_handler.hash_to_point(self.data.into_iter()).unwrap();
 
             NonIdentityPoint::new(
@@ -285,29 +419,306 @@ impl Circuit<pallas::Base> for MyCircuit {
             )?
         };
 
-        let (result, _) = hash_handler.hash_to_point(
-            layouter.namespace(|| "hash to point"),
-            Message::from_pieces(sinsemilla_chip.clone(), vec![message_piece.clone()])
-        )?;
-
+        let result = hasher.hash_many(layouter.namespace(|| "hash to point"), vec![message])?;
 
-        result.constrain_equal(
+        result[0].constrain_equal(
             layouter.namespace(|| "result == expected_point"),
             &expected_point
         )
     }
 }
 
+/// Hashes several 10-bit messages with a single `SinsemillaHasher`, checking each
+/// against the off-circuit hash.
+#[derive(Clone)]
+struct MyManyCircuit {
+    messages: Vec<[bool; 10]>,
+}
+
+impl Circuit<pallas::Base> for MyManyCircuit {
+    type Config = (
+        EccConfig<TestFixedBases>,
+        SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases>,
+    );
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyManyCircuit {
+            messages: vec![[false; 10]; self.messages.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        configure_chips(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        let ecc_chip = EccChip::construct(config.0);
+
+        // load the lookup table
+        SinsemillaChip::load(config.1.clone(), &mut layouter)?;
+
+        let sinsemilla_chip = SinsemillaChip::construct(config.1);
+        let hasher = SinsemillaHasher::new(sinsemilla_chip, ecc_chip.clone(), &DOMAIN);
+
+        let messages = self.messages
+            .iter()
+            .enumerate()
+            .map(|(i, data)| hasher.message(layouter.namespace(|| format!("message {}", i)), data))
+            .collect::<Result<Vec<_>, _>>()?;
+        let results = hasher.hash_many(layouter.namespace(|| "hash many"), messages)?;
+
+        for (i, (data, result)) in self.messages.iter().zip(results.iter()).enumerate() {
+            let expected_point = NonIdentityPoint::new(
+                ecc_chip.clone(),
+                layouter.namespace(|| format!("expected point {}", i)),
+                Value::known(DOMAIN.primitive().hash_to_point(data.iter().copied()).unwrap().to_affine())
+            )?;
+
+            result.constrain_equal(
+                layouter.namespace(|| format!("result {} == expected_point", i)),
+                &expected_point
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A gate of its own, `b = 2 * a`, on two of the advice columns the chips use.
+#[derive(Debug, Clone)]
+struct DoubleConfig {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    q_double: Selector,
+}
+
+/// Hashes `data` like `MyCircuit` and checks `b = 2 * a` on the same advice columns.
+#[derive(Copy, Clone)]
+struct MySharedCircuit {
+    data: [bool; 10],
+    a: pallas::Base,
+    b: pallas::Base,
+}
+
+impl Circuit<pallas::Base> for MySharedCircuit {
+    type Config = (
+        EccConfig<TestFixedBases>,
+        SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases>,
+        DoubleConfig,
+    );
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MySharedCircuit {
+            data: [false; 10],
+            a: pallas::Base::zero(),
+            b: pallas::Base::zero(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        let advices = [(); 10].map(|_| meta.advice_column());
+        let (ecc_config, sinsemilla_config) = configure_with_columns(meta, advices);
+
+        let double_config = DoubleConfig {
+            a: advices[0],
+            b: advices[1],
+            q_double: meta.selector(),
+        };
+        meta.create_gate("double", |meta| {
+            let s = meta.query_selector(double_config.q_double);
+            let a = meta.query_advice(double_config.a, Rotation::cur());
+            let b = meta.query_advice(double_config.b, Rotation::cur());
+
+            Constraints::with_selector(s, [("b = 2 * a", b - a * Expression::Constant(pallas::Base::from(2u64)))])
+        });
+
+        (ecc_config, sinsemilla_config, double_config)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        let ecc_chip = EccChip::construct(config.0);
+
+        // load the lookup table
+        SinsemillaChip::load(config.1.clone(), &mut layouter)?;
+
+        let sinsemilla_chip = SinsemillaChip::construct(config.1);
+        let hasher = SinsemillaHasher::new(sinsemilla_chip, ecc_chip.clone(), &DOMAIN);
+
+        let message = hasher.message(layouter.namespace(|| "message"), &self.data)?;
+        let result = hasher.hash_many(layouter.namespace(|| "hash to point"), vec![message])?;
+
+        let expected_point = NonIdentityPoint::new(
+            ecc_chip,
+            layouter.namespace(|| "expected point"),
+            Value::known(DOMAIN.primitive().hash_to_point(self.data.into_iter()).unwrap().to_affine())
+        )?;
+        result[0].constrain_equal(layouter.namespace(|| "result == expected_point"), &expected_point)?;
+
+        let double = config.2;
+        layouter.assign_region(
+            || "double",
+            |mut region| {
+                double.q_double.enable(&mut region, 0)?;
+                region.assign_advice(|| "a", double.a, 0, || Value::known(self.a))?;
+                region.assign_advice(|| "b", double.b, 0, || Value::known(self.b))?;
+                Ok(())
+            }
+        )
+    }
+}
+
+/// Hashes `bytes` with `SinsemillaHasher::hash_bytes`, checking it against the off-circuit hash.
+#[derive(Clone)]
+struct MyBytesCircuit {
+    bytes: Vec<u8>,
+}
+
+impl Circuit<pallas::Base> for MyBytesCircuit {
+    type Config = (
+        EccConfig<TestFixedBases>,
+        SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases>,
+    );
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyBytesCircuit {
+            bytes: vec![0; self.bytes.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        configure_chips(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        let ecc_chip = EccChip::construct(config.0);
+
+        // load the lookup table
+        SinsemillaChip::load(config.1.clone(), &mut layouter)?;
+
+        let sinsemilla_chip = SinsemillaChip::construct(config.1);
+        let hasher = SinsemillaHasher::new(sinsemilla_chip, ecc_chip.clone(), &DOMAIN);
+
+        let result = hasher.hash_bytes(layouter.namespace(|| "hash bytes"), &self.bytes)?;
+
+        let expected_point = NonIdentityPoint::new(
+            ecc_chip,
+            layouter.namespace(|| "expected point"),
+            Value::known(DOMAIN.primitive().hash_to_point(bytes_to_bits(&self.bytes).into_iter()).unwrap().to_affine())
+        )?;
+
+        result.constrain_equal(layouter.namespace(|| "result == expected_point"), &expected_point)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use halo2_proofs::dev::MockProver;
-    use crate::merkle_tree::sinsemilla::MyCircuit;
+    use halo2_proofs::pasta::group::Curve;
+    use halo2_proofs::pasta::pallas;
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+    use lazy_static::lazy_static;
+    use crate::merkle_tree::sinsemilla::{Domain, MyBytesCircuit, MyCircuit, MyManyCircuit, MySharedCircuit, DOMAIN};
+
+    lazy_static! {
+        static ref APP_DOMAIN: Domain = Domain::new("MyAppCRH");
+    }
 
     #[test]
     fn test_circuit() {
         let k = 11;
         let circuit = MyCircuit {
-            data: [true, true, false, false, false, false, false, false, false, true]
+            data: [true, true, false, false, false, false, false, false, false, true],
+            domain: &DOMAIN,
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_personalization() {
+        let k = 11;
+        let data = [true, true, false, false, false, false, false, false, false, true];
+
+        let point = |domain: &Domain| {
+            domain
+                .primitive()
+                .hash_to_point(data.into_iter())
+                .unwrap()
+                .to_affine()
+        };
+        assert_ne!(point(&DOMAIN), point(&APP_DOMAIN));
+
+        for domain in [&*DOMAIN, &*APP_DOMAIN] {
+            let circuit = MyCircuit {
+                data,
+                domain,
+            };
+            let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_hash_many() {
+        let k = 11;
+        let circuit = MyManyCircuit {
+            messages: vec![
+                [true, true, false, false, false, false, false, false, false, true],
+                [false, true, false, true, false, true, false, true, false, true],
+                [true; 10],
+            ],
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_shared_columns() {
+        let k = 11;
+        let data = [true, true, false, false, false, false, false, false, false, true];
+
+        // the extra gate adds no advice column
+        let mut meta = ConstraintSystem::<pallas::Base>::default();
+        MySharedCircuit::configure(&mut meta);
+        assert_eq!(meta.num_advice_columns(), 10);
+
+        let circuit = MySharedCircuit {
+            data,
+            a: pallas::Base::from(21),
+            b: pallas::Base::from(42),
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        let circuit = MySharedCircuit {
+            data,
+            a: pallas::Base::from(21),
+            b: pallas::Base::from(43),
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_hash_bytes() {
+        // 256 bits, padded to 26 words in pieces of 25 and 1 words
+        let k = 11;
+        let circuit = MyBytesCircuit {
+            bytes: (0..32).collect(),
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_hash_bytes_partial_word() {
+        // 24 bits, padded to 3 words
+        let k = 11;
+        let circuit = MyBytesCircuit {
+            bytes: vec![0xde, 0xad, 0xbe],
         };
         let prover = MockProver::run(k, &circuit, vec![]).unwrap();
         prover.assert_satisfied();
@@ -324,7 +735,8 @@ mod tests {
         let root = root.titled("SinsemillaHash", ("sans-serif", 60)).unwrap();
 
         let circuit = MyCircuit {
-            data: [true, true, false, false, false, false, false, false, false, false]
+            data: [true, true, false, false, false, false, false, false, false, false],
+            domain: &DOMAIN,
         };
         halo2_proofs::dev::CircuitLayout::default()
             .render(11, &circuit, &root)
@@ -0,0 +1,263 @@
+/// Packs message bits into the field element of a Sinsemilla message piece.
+///
+/// Sinsemilla consumes a message in order, 10 bits at a time, and a piece holding the words
+/// `w_0, w_1, ..` is the field element `w_0 + w_1 * 2^10 + ..`, each word little-endian.
+/// So the bit at index `i` of the message has weight `2^i`: the first bit is the least
+/// significant, and folding the bits into the element runs from the last bit to the first.
+/// `PackBitsChip` constrains the same packing in-circuit over a running sum, most significant
+/// bit first, so that `acc` on the last row is the packed value:
+///```txt
+///        bit      |  acc                          |  q_first  |  q_step
+///       -----------------------------------------------------------------
+///       b_{n-1}   |  b_{n-1}                      |     1     |    0
+///       b_{n-2}   |  2 * b_{n-1} + b_{n-2}        |     0     |    1
+///         ..      |    ..                         |     0     |    1
+///       b_0       |  sum b_i * 2^i                |     0     |    1
+///```
+use halo2_gadgets::{
+    ecc::{chip::*, NonIdentityPoint},
+    sinsemilla::{
+        chip::{SinsemillaChip, SinsemillaConfig},
+        primitives as sinsemilla,
+        Message, MessagePiece,
+    },
+};
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    pasta::*,
+    poly::Rotation,
+};
+use halo2_proofs::pasta::group::Curve;
+use super::{configure_chips, SinsemillaHasher, TestCommitDomain, TestFixedBases, TestHashDomain, DOMAIN};
+
+/// The field element of a message piece of `bits`, in the order Sinsemilla hashes them:
+/// `bits[i]` has weight `2^i`.
+pub(crate) fn pack_bits_for_sinsemilla(bits: &[bool]) -> pallas::Base {
+    bits.iter().rev().fold(pallas::Base::zero(), |acc, bit| {
+        if *bit {
+            acc.double() + pallas::Base::one()
+        } else {
+            acc.double()
+        }
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PackBitsConfig {
+    bit: Column<Advice>,
+    acc: Column<Advice>,
+    q_first: Selector,
+    q_step: Selector,
+}
+
+pub(crate) struct PackBitsChip {
+    config: PackBitsConfig,
+}
+
+impl PackBitsChip {
+    pub(crate) fn construct(config: PackBitsConfig) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<pallas::Base>, bit: Column<Advice>, acc: Column<Advice>) -> PackBitsConfig {
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+
+        meta.enable_equality(acc);
+
+        let bool_check = |bit: Expression<pallas::Base>| bit.clone() * (Expression::Constant(pallas::Base::one()) - bit);
+
+        meta.create_gate("pack first bit", |meta| {
+            let s = meta.query_selector(q_first);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+
+            Constraints::with_selector(s, [
+                ("bit is boolean", bool_check(bit.clone())),
+                ("acc = bit", acc - bit),
+            ])
+        });
+
+        meta.create_gate("pack next bit", |meta| {
+            let s = meta.query_selector(q_step);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let prev_acc = meta.query_advice(acc, Rotation::prev());
+
+            Constraints::with_selector(s, [
+                ("bit is boolean", bool_check(bit.clone())),
+                ("acc = 2 * prev_acc + bit", acc - prev_acc * Expression::Constant(pallas::Base::from(2)) - bit),
+            ])
+        });
+
+        PackBitsConfig {
+            bit,
+            acc,
+            q_first,
+            q_step,
+        }
+    }
+
+    /// Witnesses `bits` and returns the cell of their packing, `bits[i]` with weight `2^i`.
+    pub(crate) fn assign(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        bits: &[Value<bool>],
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        assert!(!bits.is_empty(), "there is at least one bit to pack");
+
+        layouter.assign_region(
+            || "pack bits",
+            |mut region| {
+                let mut acc = Value::known(pallas::Base::zero());
+                let mut acc_cell = None;
+                for (row, bit) in bits.iter().rev().enumerate() {
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_step.enable(&mut region, row)?;
+                    }
+
+                    let bit = bit.map(|bit| if bit { pallas::Base::one() } else { pallas::Base::zero() });
+                    region.assign_advice(|| "bit", self.config.bit, row, || bit)?;
+
+                    acc = acc.map(|acc| acc.double()) + bit;
+                    acc_cell = Some(region.assign_advice(|| "acc", self.config.acc, row, || acc)?);
+                }
+
+                Ok(acc_cell.unwrap())
+            }
+        )
+    }
+}
+
+/// Packs `bits` in-circuit, hashes them as a single message piece bound to the packing, and
+/// checks the hash against the off-circuit hash of `bits`. `piece_override` witnesses the
+/// piece as another value than the packing, to check the binding.
+#[derive(Clone, Default)]
+struct MyCircuit {
+    bits: Vec<bool>,
+    piece_override: Option<pallas::Base>,
+}
+
+impl Circuit<pallas::Base> for MyCircuit {
+    type Config = (
+        EccConfig<TestFixedBases>,
+        SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases>,
+        PackBitsConfig,
+    );
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            bits: vec![false; self.bits.len()],
+            piece_override: None,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        let (ecc_config, sinsemilla_config) = configure_chips(meta);
+        let [bit, acc] = [(); 2].map(|_| meta.advice_column());
+        let pack_config = PackBitsChip::configure(meta, bit, acc);
+
+        (ecc_config, sinsemilla_config, pack_config)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        let ecc_chip = EccChip::construct(config.0);
+
+        // load the lookup table
+        SinsemillaChip::load(config.1.clone(), &mut layouter)?;
+
+        let sinsemilla_chip = SinsemillaChip::construct(config.1);
+        let hasher = SinsemillaHasher::new(sinsemilla_chip.clone(), ecc_chip.clone(), &DOMAIN);
+
+        let bits: Vec<_> = self.bits.iter().copied().map(Value::known).collect();
+        let packed = PackBitsChip::construct(config.2).assign(layouter.namespace(|| "pack bits"), &bits)?;
+
+        let piece = MessagePiece::from_field_elem(
+            sinsemilla_chip.clone(),
+            layouter.namespace(|| "piece"),
+            Value::known(self.piece_override.unwrap_or_else(|| pack_bits_for_sinsemilla(&self.bits))),
+            self.bits.len() / sinsemilla::K,
+        )?;
+        layouter.assign_region(
+            || "piece = packed bits",
+            |mut region| region.constrain_equal(piece.inner().cell_value().cell(), packed.cell())
+        )?;
+
+        let message = Message::from_pieces(sinsemilla_chip, vec![piece]);
+        let result = hasher.hash_many(layouter.namespace(|| "hash to point"), vec![message])?;
+
+        let expected_point = NonIdentityPoint::new(
+            ecc_chip,
+            layouter.namespace(|| "expected point"),
+            Value::known(DOMAIN.primitive().hash_to_point(self.bits.iter().copied()).unwrap().to_affine())
+        )?;
+
+        result[0].constrain_equal(layouter.namespace(|| "result == expected_point"), &expected_point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::pallas;
+    use super::{pack_bits_for_sinsemilla, MyCircuit};
+
+    /// 30 bits with a bit set at every index that is a multiple of 3 or 7.
+    fn pattern() -> Vec<bool> {
+        (0..30).map(|i| i % 3 == 0 || i % 7 == 0).collect()
+    }
+
+    #[test]
+    fn test_pack_bits() {
+        // the first bit is the least significant
+        assert_eq!(pack_bits_for_sinsemilla(&[true, false]), pallas::Base::one());
+        assert_eq!(pack_bits_for_sinsemilla(&[false, true]), pallas::Base::from(2));
+
+        let data = [true, true, false, false, false, false, false, false, false, true];
+        assert_eq!(pack_bits_for_sinsemilla(&data), pallas::Base::from(1 + 2 + 512));
+
+        // trailing zeros do not change the packing
+        assert_eq!(pack_bits_for_sinsemilla(&[true, true, false, false]), pallas::Base::from(3));
+    }
+
+    #[test]
+    fn test_packed_hash() {
+        let k = 11;
+        let patterns = vec![
+            vec![true, true, false, false, false, false, false, false, false, true],
+            vec![true; 20],
+            vec![false; 10],
+            pattern(),
+        ];
+        for bits in patterns {
+            let circuit = MyCircuit {
+                bits,
+                piece_override: None,
+            };
+            let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_reversed_packing() {
+        let k = 11;
+
+        // the piece packed most significant bit first hashes to another point, and
+        // differs from the constrained packing
+        let bits = pattern();
+        let reversed: Vec<_> = bits.iter().rev().copied().collect();
+        let circuit = MyCircuit {
+            bits,
+            piece_override: Some(pack_bits_for_sinsemilla(&reversed)),
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,586 @@
+/// Computes a Merkle root from a leaf and its authentication path of `DEPTH` layers.
+/// Every layer hashes `l || left || right` with the Sinsemilla hash domain, where `l` is
+/// the 10-bit layer index and `left`, `right` are the 255-bit little-endian node encodings.
+/// The 520-bit message is split into three pieces:
+///```txt
+///        piece   |   words   |   bits
+///       ---------------------------------------------
+///          a     |    25     |   l || left[0..240]
+///          b     |     2     |   left[240..255] || right[0..5]
+///          c     |    25     |   right[5..255]
+///```
+/// Each layer orders the node and its sibling with a `CondSwapChip`, and one row checks that
+/// the pieces encode `l` and the ordered pair, using the running sums `z1_a = left[0..240]`
+/// and `z1_b = left[250..255] || right[0..5]` the hash computes anyway, plus the 5-bit
+/// pieces `b_1 = left[250..255]` and `b_2 = right[0..5]`:
+///```txt
+///        a  |  b  |  c  |  left  |  right  |  z1_a  |  z1_b  |  b_1  |  b_2  |  l  |  q_decompose
+///       ------------------------------------------------------------------------------------------
+///        a  |  b  |  c  |  left  |  right  |  z1_a  |  z1_b  |  b_1  |  b_2  |  l  |      1
+///```
+/// The hash output is the node copied into the next layer's swap, so the root is bound to the
+/// leaf cell through every layer. Unlike Orchard's MerkleCRH, the encodings of `left` and
+/// `right` are not checked to be canonical, so a 255-bit encoding of a node plus p is accepted
+/// as well; the path only binds the root to the leaf as field elements.
+use halo2_gadgets::{
+    ecc::chip::{EccChip, EccConfig},
+    sinsemilla::{
+        chip::{SinsemillaChip, SinsemillaConfig},
+        primitives::{self as sinsemilla},
+        HashDomain, Message, MessagePiece,
+    },
+    utilities::lookup_range_check::LookupRangeCheckConfig,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::*,
+    plonk::*,
+    pasta::*,
+    pasta::group::ff::PrimeField,
+    poly::Rotation,
+};
+
+use super::pack::pack_bits_for_sinsemilla;
+use super::{Domain, TestCommitDomain, TestFixedBases, TestHashDomain, DOMAIN};
+use crate::merkle_tree::builder::NodeHasher;
+use crate::merkle_tree::cond_swap::{CondSwapChip, CondSwapConfig};
+
+/// Bits of the layer index prefixed to every layer's message.
+pub(crate) const L_BITS: usize = 10;
+/// Bits of a node encoding, i.e. `pallas::Base::NUM_BITS`.
+const NODE_BITS: usize = 255;
+/// Number of 10-bit words in each message piece.
+const PIECE_WORDS: [usize; 3] = [25, 2, 25];
+/// Bits of `b_1` and `b_2`, the pieces of `b` split off at the boundary of `left` and `right`.
+const B_1_BITS: usize = 5;
+const B_2_BITS: usize = 5;
+/// Upper bound on the rows a single layer occupies (52 hashed words, the pieces, the range
+/// checks of `b_1`, `b_2` and the decomposition row).
+const ROWS_PER_LAYER: usize = 72;
+
+#[derive(Clone, Debug)]
+pub(crate) struct MerklePathConfig {
+    advices: [Column<Advice>; 10],
+    ecc: EccConfig<TestFixedBases>,
+    sinsemilla: SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases>,
+    range_check: LookupRangeCheckConfig<pallas::Base, { sinsemilla::K }>,
+    cond_swap: CondSwapConfig,
+    q_decompose: Selector,
+    instance: Column<Instance>,
+}
+
+impl MerklePathConfig {
+    /// The ecc config of the path, for a circuit to run its own ecc operations on the same
+    /// columns rather than configure a second set.
+    pub(crate) fn ecc(&self) -> &EccConfig<TestFixedBases> {
+        &self.ecc
+    }
+
+    /// The sinsemilla config of the path, whose generator table `load_table` loads.
+    pub(crate) fn sinsemilla(&self) -> &SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases> {
+        &self.sinsemilla
+    }
+}
+
+pub(crate) struct MerklePathChip<const DEPTH: usize> {
+    config: MerklePathConfig,
+}
+
+impl<const DEPTH: usize> MerklePathChip<DEPTH> {
+    pub(crate) fn construct(config: MerklePathConfig) -> Self {
+        Self {
+            config
+        }
+    }
+
+    /// The layout of a layer does not depend on its index, so neither does the configuration.
+    pub(crate) fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> MerklePathConfig {
+        let advices = [(); 10].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        for advice in advices {
+            meta.enable_equality(advice);
+        }
+
+        // Shared fixed column for loading constants
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        let lagrange_coeffs = [(); 8].map(|_| meta.fixed_column());
+        let table_idx = meta.lookup_table_column();
+        let range_check = LookupRangeCheckConfig::configure(
+            meta,
+            advices[9],
+            table_idx
+        );
+
+        let ecc = EccChip::<TestFixedBases>::configure(
+            meta,
+            advices,
+            lagrange_coeffs,
+            range_check
+        );
+
+        // fixed columns for the sinsemilla generator lookup table
+        let lookup = (
+            table_idx,
+            meta.lookup_table_column(),
+            meta.lookup_table_column(),
+        );
+
+        let sinsemilla = SinsemillaChip::configure(
+            meta,
+            advices[..5].try_into().unwrap(),
+            advices[2],
+            lagrange_coeffs[0],
+            lookup,
+            range_check,
+        );
+
+        let q_decompose = meta.selector();
+        meta.create_gate("decompose layer", |meta| {
+            let s = meta.query_selector(q_decompose);
+            let [a, b, c, left, right, z1_a, z1_b, b_1, b_2, l] =
+                advices.map(|advice| meta.query_advice(advice, Rotation::cur()));
+            let two_pow = |exp: u64| Expression::Constant(pallas::Base::from(2).pow_vartime(&[exp]));
+
+            // b = b_0 || z1_b, where b_0 = left[240..250] is the first word of b
+            let b_0 = b - z1_b.clone() * two_pow(10);
+
+            Constraints::with_selector(s, [
+                ("a = l || z1_a", a - l - z1_a.clone() * two_pow(10)),
+                ("z1_b = b_1 || b_2", z1_b - b_1.clone() - b_2.clone() * two_pow(5)),
+                ("left = z1_a || b_0 || b_1", left - z1_a - b_0 * two_pow(240) - b_1 * two_pow(250)),
+                ("right = b_2 || c", right - b_2 - c * two_pow(5)),
+            ])
+        });
+
+        MerklePathConfig {
+            advices,
+            ecc,
+            sinsemilla,
+            range_check,
+            cond_swap: CondSwapChip::configure(meta),
+            q_decompose,
+            instance,
+        }
+    }
+
+    /// The smallest `k` that fits both the 2^10-row generator table and `DEPTH` layers.
+    pub(crate) fn k() -> u32 {
+        let rows = DEPTH * ROWS_PER_LAYER + 16;
+        let mut k = 11;
+        while (1 << k) < rows {
+            k += 1;
+        }
+        k
+    }
+
+    /// Loads the generator table, which the range checks of the decomposition share. A circuit
+    /// loads it once, however many paths it assigns.
+    pub(crate) fn load_table(&self, layouter: &mut impl Layouter<pallas::Base>) -> Result<(), Error> {
+        SinsemillaChip::load(self.config.sinsemilla.clone(), layouter)?;
+        Ok(())
+    }
+
+    /// Witnesses a leaf for `assign_from_cell`.
+    pub(crate) fn witness_leaf(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        leaf: Value<pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        CondSwapChip::construct(self.config.cond_swap).witness(layouter, leaf)
+    }
+
+    /// Hashes `leaf` up through `siblings`, where `position[i]` is true when the node at
+    /// layer `i` is the right child, and returns the cell holding the root.
+    pub(crate) fn assign(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        domain: &Domain,
+        leaf: pallas::Base,
+        siblings: [pallas::Base; DEPTH],
+        position: [bool; DEPTH],
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        let leaf = self.witness_leaf(layouter.namespace(|| "leaf"), Value::known(leaf))?;
+        self.assign_layers(layouter, domain, leaf, siblings, position, None)
+    }
+
+    /// Like `assign`, but with the leaf taken from a cell another chip computed, such as a
+    /// hash. The cell is copied into the first layer's swap.
+    pub(crate) fn assign_from_cell(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        domain: &Domain,
+        leaf: &AssignedCell<pallas::Base, pallas::Base>,
+        siblings: [pallas::Base; DEPTH],
+        position: [bool; DEPTH],
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        self.assign_layers(layouter, domain, leaf.clone(), siblings, position, None)
+    }
+
+    /// Like `assign_from_cell`, but hashes `forged.1` in place of the node entering layer
+    /// `forged.0`, as a cheating prover would, to check that the path rejects it.
+    pub(crate) fn assign_forged(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        domain: &Domain,
+        leaf: &AssignedCell<pallas::Base, pallas::Base>,
+        siblings: [pallas::Base; DEPTH],
+        position: [bool; DEPTH],
+        forged: (usize, pallas::Base),
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        self.assign_layers(layouter, domain, leaf.clone(), siblings, position, Some(forged))
+    }
+
+    fn assign_layers(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        domain: &Domain,
+        leaf: AssignedCell<pallas::Base, pallas::Base>,
+        siblings: [pallas::Base; DEPTH],
+        position: [bool; DEPTH],
+        forged: Option<(usize, pallas::Base)>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        let ecc_chip = EccChip::construct(self.config.ecc.clone());
+        let sinsemilla_chip = SinsemillaChip::construct(self.config.sinsemilla.clone());
+        let cond_swap = CondSwapChip::construct(self.config.cond_swap);
+
+        let hash_handler = HashDomain::new(
+            sinsemilla_chip.clone(),
+            ecc_chip,
+            domain.hash_domain()
+        );
+
+        let mut node = leaf;
+        for l in 0..DEPTH {
+            let (left, right) = cond_swap.swap(
+                layouter.namespace(|| format!("layer {} swap", l)),
+                (node, Value::known(siblings[l])),
+                Value::known(position[l]),
+            )?;
+
+            // the pair the message encodes, which only a cheating prover makes differ from the swap
+            let pair = match forged {
+                Some((layer, forged)) if layer == l => Value::known(if position[l] {
+                    (siblings[l], forged)
+                } else {
+                    (forged, siblings[l])
+                }),
+                _ => left.value().copied().zip(right.value().copied()),
+            };
+            let decomposition = pair.map(|(left, right)| decompose(l, left, right));
+
+            let pieces = (0..PIECE_WORDS.len())
+                .map(|i| {
+                    MessagePiece::from_field_elem(
+                        sinsemilla_chip.clone(),
+                        layouter.namespace(|| format!("layer {} piece {}", l, i)),
+                        decomposition.map(|(pieces, _, _)| pieces[i]),
+                        PIECE_WORDS[i]
+                    )
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            let piece_cells: Vec<_> = pieces.iter().map(|piece| piece.inner().cell_value()).collect();
+
+            let (hash, zs) = hash_handler.hash(
+                layouter.namespace(|| format!("hash layer {}", l)),
+                Message::from_pieces(sinsemilla_chip.clone(), pieces)
+            )?;
+
+            let b_1 = self.config.range_check.witness_short_check(
+                layouter.namespace(|| format!("layer {} b_1", l)),
+                decomposition.map(|(_, b_1, _)| b_1),
+                B_1_BITS,
+            )?;
+            let b_2 = self.config.range_check.witness_short_check(
+                layouter.namespace(|| format!("layer {} b_2", l)),
+                decomposition.map(|(_, _, b_2)| b_2),
+                B_2_BITS,
+            )?;
+
+            layouter.assign_region(
+                || format!("decompose layer {}", l),
+                |mut region| {
+                    self.config.q_decompose.enable(&mut region, 0)?;
+
+                    let cells = [
+                        &piece_cells[0],
+                        &piece_cells[1],
+                        &piece_cells[2],
+                        &left,
+                        &right,
+                        &zs[0][1],
+                        &zs[1][1],
+                        &b_1,
+                        &b_2,
+                    ];
+                    for (cell, advice) in cells.into_iter().zip(self.config.advices) {
+                        cell.copy_advice(|| "decomposition", &mut region, advice, 0)?;
+                    }
+                    region.assign_advice_from_constant(|| "l", self.config.advices[9], 0, pallas::Base::from(l as u64))?;
+                    Ok(())
+                }
+            )?;
+
+            node = hash.inner().clone();
+        }
+
+        Ok(node)
+    }
+
+    pub(crate) fn expose_public(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        cell: &AssignedCell<pallas::Base, pallas::Base>,
+        row: usize
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// The little-endian bits of a node.
+pub(crate) fn node_bits(node: pallas::Base) -> Vec<bool> {
+    let repr = node.to_repr();
+    (0..NODE_BITS).map(|i| (repr[i / 8] >> (i % 8)) & 1 == 1).collect()
+}
+
+/// The bits `l || left || right` hashed at layer `l`.
+fn layer_bits(l: usize, left: pallas::Base, right: pallas::Base) -> Vec<bool> {
+    (0..L_BITS)
+        .map(|i| (l >> i) & 1 == 1)
+        .chain(node_bits(left))
+        .chain(node_bits(right))
+        .collect()
+}
+
+/// The layer message split into the field elements of its pieces, with `b_1` and `b_2`.
+fn decompose(l: usize, left: pallas::Base, right: pallas::Base) -> ([pallas::Base; 3], pallas::Base, pallas::Base) {
+    let bits = layer_bits(l, left, right);
+    let mut pieces = [pallas::Base::zero(); 3];
+    let mut start = 0;
+    for (piece, words) in pieces.iter_mut().zip(PIECE_WORDS) {
+        let end = start + words * sinsemilla::K;
+        *piece = pack_bits_for_sinsemilla(&bits[start..end]);
+        start = end;
+    }
+
+    // b starts at bit 250 with its first word b_0, then b_1 and b_2
+    let b_1 = 260;
+    let b_2 = b_1 + B_1_BITS;
+    (
+        pieces,
+        pack_bits_for_sinsemilla(&bits[b_1..b_2]),
+        pack_bits_for_sinsemilla(&bits[b_2..b_2 + B_2_BITS]),
+    )
+}
+
+/// The node hash of the tree, for building trees with `MerkleTree`. Every layer is hashed
+/// with its index, so `hash` alone is the hash of the leaf layer.
+pub(crate) struct SinsemillaHash {
+    domain: Domain,
+}
+
+impl SinsemillaHash {
+    pub(crate) fn new(domain: Domain) -> Self {
+        SinsemillaHash {
+            domain
+        }
+    }
+}
+
+impl NodeHasher<pallas::Base> for SinsemillaHash {
+    fn hash(&self, left: pallas::Base, right: pallas::Base) -> pallas::Base {
+        self.hash_at_layer(0, left, right)
+    }
+
+    fn hash_at_layer(&self, layer: usize, left: pallas::Base, right: pallas::Base) -> pallas::Base {
+        self.domain.primitive().hash(layer_bits(layer, left, right).into_iter()).unwrap()
+    }
+}
+
+/// Computes the root off-circuit, for checking the circuit against.
+pub(crate) fn merkle_root<const DEPTH: usize>(
+    domain: &Domain,
+    leaf: pallas::Base,
+    siblings: [pallas::Base; DEPTH],
+    position: [bool; DEPTH],
+) -> pallas::Base {
+    let hash_handler = domain.primitive();
+    (0..DEPTH).fold(leaf, |node, l| {
+        let (left, right) = if position[l] {
+            (siblings[l], node)
+        } else {
+            (node, siblings[l])
+        };
+        hash_handler.hash(layer_bits(l, left, right).into_iter()).unwrap()
+    })
+}
+
+#[derive(Clone, Copy)]
+/// `forged` replaces the node entering a layer in the message that layer hashes.
+struct MyCircuit<const DEPTH: usize> {
+    leaf: pallas::Base,
+    siblings: [pallas::Base; DEPTH],
+    position: [bool; DEPTH],
+    forged: Option<(usize, pallas::Base)>,
+}
+
+impl<const DEPTH: usize> Default for MyCircuit<DEPTH> {
+    fn default() -> Self {
+        MyCircuit {
+            leaf: pallas::Base::zero(),
+            siblings: [pallas::Base::zero(); DEPTH],
+            position: [false; DEPTH],
+            forged: None,
+        }
+    }
+}
+
+impl<const DEPTH: usize> Circuit<pallas::Base> for MyCircuit<DEPTH> {
+    type Config = MerklePathConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        MerklePathChip::<DEPTH>::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        let chip = MerklePathChip::<DEPTH>::construct(config);
+        chip.load_table(&mut layouter)?;
+
+        let root = match self.forged {
+            None => chip.assign(
+                layouter.namespace(|| "merkle path"),
+                &DOMAIN,
+                self.leaf,
+                self.siblings,
+                self.position,
+            )?,
+            Some(forged) => {
+                let leaf = chip.witness_leaf(layouter.namespace(|| "leaf"), Value::known(self.leaf))?;
+                chip.assign_forged(
+                    layouter.namespace(|| "merkle path"),
+                    &DOMAIN,
+                    &leaf,
+                    self.siblings,
+                    self.position,
+                    forged,
+                )?
+            }
+        };
+
+        chip.expose_public(layouter.namespace(|| "expose root"), &root, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::pallas;
+    use super::{merkle_root, MerklePathChip, MyCircuit, DOMAIN};
+
+    fn run<const DEPTH: usize>(position: [bool; DEPTH], expected_k: u32) {
+        let leaf = pallas::Base::from(7);
+        let mut siblings = [pallas::Base::zero(); DEPTH];
+        for (i, sibling) in siblings.iter_mut().enumerate() {
+            *sibling = pallas::Base::from(100 + i as u64);
+        }
+        let root = merkle_root(&DOMAIN, leaf, siblings, position);
+
+        let circuit = MyCircuit {
+            leaf,
+            siblings,
+            position,
+            forged: None,
+        };
+        let k = MerklePathChip::<DEPTH>::k();
+        assert_eq!(k, expected_k);
+        let prover = MockProver::run(k, &circuit, vec![vec![root]]).unwrap();
+        prover.assert_satisfied();
+
+        // a wrong root must be rejected
+        let prover = MockProver::run(k, &circuit, vec![vec![root + pallas::Base::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_depth_1() {
+        // k starts at 11 for the 2^10-row generator table, which leaves room for 28 layers
+        run::<1>([true], 11);
+    }
+
+    #[test]
+    fn test_depth_4() {
+        run::<4>([false, true, true, false], 11);
+    }
+
+    #[test]
+    fn test_depth_8() {
+        run::<8>([true, false, false, true, true, false, true, false], 11);
+    }
+
+    #[test]
+    fn test_k_scales_with_depth() {
+        // past 28 layers the rows of the layers outgrow the generator table
+        assert_eq!(MerklePathChip::<28>::k(), 11);
+        assert_eq!(MerklePathChip::<29>::k(), 12);
+        assert_eq!(MerklePathChip::<32>::k(), 12);
+        assert_eq!(MerklePathChip::<64>::k(), 13);
+    }
+
+    #[test]
+    fn test_wrong_sibling() {
+        let leaf = pallas::Base::from(7);
+        let siblings = [100, 101, 102, 103].map(pallas::Base::from);
+        let position = [false, true, true, false];
+        let root = merkle_root(&DOMAIN, leaf, siblings, position);
+
+        let mut wrong = siblings;
+        wrong[2] += pallas::Base::one();
+        let circuit = MyCircuit {
+            leaf,
+            siblings: wrong,
+            position,
+            forged: None,
+        };
+        let prover = MockProver::run(MerklePathChip::<4>::k(), &circuit, vec![vec![root]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_wrong_intermediate_node() {
+        let leaf = pallas::Base::from(7);
+        let siblings = [100, 101, 102, 103].map(pallas::Base::from);
+        let position = [false, true, true, false];
+        let root = merkle_root(&DOMAIN, leaf, siblings, position);
+
+        // the honest node entering layer 2 hashes to the root from there on
+        let node = merkle_root(&DOMAIN, leaf, [siblings[0], siblings[1]], [position[0], position[1]]);
+        let k = MerklePathChip::<4>::k();
+
+        let circuit = MyCircuit {
+            leaf,
+            siblings,
+            position,
+            forged: Some((2, node)),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![root]]).unwrap();
+        prover.assert_satisfied();
+
+        // another leaf cannot splice into the honest path at layer 2, even though the
+        // layers above it hash to the root
+        let circuit = MyCircuit {
+            leaf: pallas::Base::from(8),
+            siblings,
+            position,
+            forged: Some((2, node)),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![root]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
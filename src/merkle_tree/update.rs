@@ -0,0 +1,283 @@
+/// Proves a Merkle root update after a single leaf change: the old leaf hashes up to the old
+/// root and the new leaf hashes up to the new root along the same authentication path, where
+/// every node is `Poseidon(left, right)` as in `poseidon.rs`.
+/// Each layer orders both nodes against one shared sibling and position bit in a single row,
+/// so that the two paths cannot diverge, then hashes both pairs.
+///```txt
+///        old  |  new  |  sibling  |  swap  |  old_left  |  old_right  |  new_left  |  new_right  |  q_swap
+///       ---------------------------------------------------------------------------------------------------
+///        o    |   n   |     s     |   b    |  b ? s : o |  b ? o : s  |  b ? s : n |  b ? n : s  |    1
+///```
+/// The old root is exposed at instance row 0 and the new root at row 1.
+use halo2_gadgets::poseidon::{
+    primitives::{ConstantLength, P128Pow5T3},
+    Hash, Pow5Chip, Pow5Config,
+};
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    pasta::*,
+};
+
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+
+#[derive(Debug, Clone)]
+pub(crate) struct MerkleUpdateConfig {
+    old: Column<Advice>,
+    new: Column<Advice>,
+    sibling: Column<Advice>,
+    swap: Column<Advice>,
+    old_left: Column<Advice>,
+    old_right: Column<Advice>,
+    new_left: Column<Advice>,
+    new_right: Column<Advice>,
+    q_swap: Selector,
+    instance: Column<Instance>,
+    poseidon_config: Pow5Config<pallas::Base, WIDTH, RATE>,
+}
+
+pub(crate) struct MerkleUpdateChip<const DEPTH: usize> {
+    config: MerkleUpdateConfig,
+}
+
+impl<const DEPTH: usize> MerkleUpdateChip<DEPTH> {
+    pub(crate) fn construct(config: MerkleUpdateConfig) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> MerkleUpdateConfig {
+        let [old, new, sibling, swap, old_left, old_right, new_left, new_right] =
+            [(); 8].map(|_| meta.advice_column());
+        let q_swap = meta.selector();
+        let instance = meta.instance_column();
+
+        for column in [old, new, old_left, old_right, new_left, new_right] {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        meta.create_gate("swap both", |meta| {
+            let s = meta.query_selector(q_swap);
+            let old = meta.query_advice(old, Rotation::cur());
+            let new = meta.query_advice(new, Rotation::cur());
+            let sibling = meta.query_advice(sibling, Rotation::cur());
+            let swap = meta.query_advice(swap, Rotation::cur());
+            let old_left = meta.query_advice(old_left, Rotation::cur());
+            let old_right = meta.query_advice(old_right, Rotation::cur());
+            let new_left = meta.query_advice(new_left, Rotation::cur());
+            let new_right = meta.query_advice(new_right, Rotation::cur());
+            let one = Expression::Constant(pallas::Base::one());
+
+            Constraints::with_selector(s, [
+                ("swap is boolean", swap.clone() * (one - swap.clone())),
+                ("old_left = swap ? sibling : old", old_left - old.clone() - swap.clone() * (sibling.clone() - old.clone())),
+                ("old_right = swap ? old : sibling", old_right - sibling.clone() - swap.clone() * (old - sibling.clone())),
+                ("new_left = swap ? sibling : new", new_left - new.clone() - swap.clone() * (sibling.clone() - new.clone())),
+                ("new_right = swap ? new : sibling", new_right - sibling.clone() - swap * (new - sibling)),
+            ])
+        });
+
+        let state = [(); WIDTH].map(|_| meta.advice_column());
+        let partial_sbox = meta.advice_column();
+
+        // fixed columns for the round constants
+        let rc_a = [(); WIDTH].map(|_| meta.fixed_column());
+        let rc_b = [(); WIDTH].map(|_| meta.fixed_column());
+        meta.enable_constant(rc_b[0]);
+
+        let poseidon_config = Pow5Chip::configure::<P128Pow5T3>(
+            meta,
+            state,
+            partial_sbox,
+            rc_a,
+            rc_b,
+        );
+
+        MerkleUpdateConfig {
+            old,
+            new,
+            sibling,
+            swap,
+            old_left,
+            old_right,
+            new_left,
+            new_right,
+            q_swap,
+            instance,
+            poseidon_config,
+        }
+    }
+
+    fn hash(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        pair: [AssignedCell<pallas::Base, pallas::Base>; 2],
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        let hasher = Hash::<_, _, P128Pow5T3, ConstantLength<2>, WIDTH, RATE>::init(
+            Pow5Chip::construct(self.config.poseidon_config.clone()),
+            layouter.namespace(|| "init")
+        )?;
+        hasher.hash(layouter.namespace(|| "hash"), pair)
+    }
+
+    /// Hashes `old_leaf` and `new_leaf` up through the shared `siblings`, where `position[i]`
+    /// is true when the node at layer `i` is the right child, and returns the cells holding
+    /// the old and the new root.
+    pub(crate) fn assign(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        old_leaf: Value<pallas::Base>,
+        new_leaf: Value<pallas::Base>,
+        siblings: [Value<pallas::Base>; DEPTH],
+        position: [Value<bool>; DEPTH],
+    ) -> Result<(AssignedCell<pallas::Base, pallas::Base>, AssignedCell<pallas::Base, pallas::Base>), Error> {
+        let (mut old, mut new) = layouter.assign_region(
+            || "leaves",
+            |mut region| {
+                let old = region.assign_advice(|| "old leaf", self.config.old, 0, || old_leaf)?;
+                let new = region.assign_advice(|| "new leaf", self.config.new, 0, || new_leaf)?;
+                Ok((old, new))
+            }
+        )?;
+
+        for l in 0..DEPTH {
+            let (old_pair, new_pair) = layouter.assign_region(
+                || format!("swap layer {}", l),
+                |mut region| {
+                    self.config.q_swap.enable(&mut region, 0)?;
+
+                    let old = old.copy_advice(|| "old", &mut region, self.config.old, 0)?;
+                    let new = new.copy_advice(|| "new", &mut region, self.config.new, 0)?;
+                    let sibling = region.assign_advice(|| "sibling", self.config.sibling, 0, || siblings[l])?;
+                    let swap = position[l].map(|swap| if swap { pallas::Base::one() } else { pallas::Base::zero() });
+                    region.assign_advice(|| "swap", self.config.swap, 0, || swap)?;
+
+                    let (old, new, sibling) = (old.value().copied(), new.value().copied(), sibling.value().copied());
+                    let old_left = region.assign_advice(|| "old left", self.config.old_left, 0, || old + swap * (sibling - old))?;
+                    let old_right = region.assign_advice(|| "old right", self.config.old_right, 0, || sibling + swap * (old - sibling))?;
+                    let new_left = region.assign_advice(|| "new left", self.config.new_left, 0, || new + swap * (sibling - new))?;
+                    let new_right = region.assign_advice(|| "new right", self.config.new_right, 0, || sibling + swap * (new - sibling))?;
+
+                    Ok(([old_left, old_right], [new_left, new_right]))
+                }
+            )?;
+
+            old = self.hash(layouter.namespace(|| format!("old layer {}", l)), old_pair)?;
+            new = self.hash(layouter.namespace(|| format!("new layer {}", l)), new_pair)?;
+        }
+
+        Ok((old, new))
+    }
+
+    pub(crate) fn expose_public(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        cell: &AssignedCell<pallas::Base, pallas::Base>,
+        row: usize
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct MyCircuit<const DEPTH: usize> {
+    old_leaf: Value<pallas::Base>,
+    new_leaf: Value<pallas::Base>,
+    siblings: [Value<pallas::Base>; DEPTH],
+    position: [Value<bool>; DEPTH],
+}
+
+impl<const DEPTH: usize> Default for MyCircuit<DEPTH> {
+    fn default() -> Self {
+        MyCircuit {
+            old_leaf: Value::unknown(),
+            new_leaf: Value::unknown(),
+            siblings: [Value::unknown(); DEPTH],
+            position: [Value::unknown(); DEPTH],
+        }
+    }
+}
+
+impl<const DEPTH: usize> Circuit<pallas::Base> for MyCircuit<DEPTH> {
+    type Config = MerkleUpdateConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        MerkleUpdateChip::<DEPTH>::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        let chip = MerkleUpdateChip::<DEPTH>::construct(config);
+
+        let (old_root, new_root) = chip.assign(
+            layouter.namespace(|| "merkle update"),
+            self.old_leaf,
+            self.new_leaf,
+            self.siblings,
+            self.position,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "expose old root"), &old_root, 0)?;
+        chip.expose_public(layouter.namespace(|| "expose new root"), &new_root, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::Value;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::pallas;
+    use crate::merkle_tree::builder::MerkleTree;
+    use crate::merkle_tree::poseidon::PoseidonHash;
+    use crate::merkle_tree::update::MyCircuit;
+
+    fn circuit(tree: &MerkleTree<pallas::Base>, index: usize, new_leaf: pallas::Base) -> MyCircuit<3> {
+        let (siblings, positions, _) = tree.authentication_path(index);
+        let siblings: [pallas::Base; 3] = siblings.try_into().unwrap();
+        let positions: [bool; 3] = positions.try_into().unwrap();
+
+        MyCircuit {
+            old_leaf: Value::known(pallas::Base::from(index as u64)),
+            new_leaf: Value::known(new_leaf),
+            siblings: siblings.map(Value::known),
+            position: positions.map(Value::known),
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let mut leaves: Vec<_> = (0..8).map(|i| pallas::Base::from(i as u64)).collect();
+        let old_tree = MerkleTree::new(leaves.clone(), &PoseidonHash);
+        leaves[5] = pallas::Base::from(42);
+        let new_tree = MerkleTree::new(leaves, &PoseidonHash);
+
+        let circuit = circuit(&old_tree, 5, pallas::Base::from(42));
+        let prover = MockProver::run(10, &circuit, vec![vec![old_tree.root(), new_tree.root()]]).unwrap();
+        prover.assert_satisfied();
+
+        // the old root must be the one the tree had
+        let prover = MockProver::run(10, &circuit, vec![vec![new_tree.root(), new_tree.root()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_update_other_leaf() {
+        // the new root of a change at leaf 5 does not follow from the path of leaf 4
+        let mut leaves: Vec<_> = (0..8).map(|i| pallas::Base::from(i as u64)).collect();
+        let old_tree = MerkleTree::new(leaves.clone(), &PoseidonHash);
+        leaves[5] = pallas::Base::from(42);
+        let new_tree = MerkleTree::new(leaves, &PoseidonHash);
+
+        let circuit = circuit(&old_tree, 4, pallas::Base::from(42));
+        let prover = MockProver::run(10, &circuit, vec![vec![old_tree.root(), new_tree.root()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,129 @@
+/// Computes `[k]P` for a witnessed scalar `k` and a witnessed point `P` with the ECC chip's
+/// variable-base scalar multiplication, and exposes the coordinates of the result at instance
+/// rows 0 and 1. Unlike the fixed-base multiplications of `pedersen.rs`, the base is a witness,
+/// so no precomputed window tables are involved.
+/// The scalar is witnessed as a base field element, which fits in the scalar field of pallas.
+use halo2_gadgets::{
+    ecc::{
+        chip::*,
+        NonIdentityPoint, ScalarVar,
+    },
+    sinsemilla::chip::{SinsemillaChip, SinsemillaConfig},
+};
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    circuit::*,
+    plonk::*,
+    pasta::*,
+};
+use halo2_proofs::pasta::group::{ff::PrimeField, prime::PrimeCurveAffine, Curve};
+use crate::merkle_tree::sinsemilla::{
+    configure_chips, TestCommitDomain, TestFixedBases, TestHashDomain,
+};
+
+/// `[k]P` computed off-circuit.
+pub(crate) fn var_base_mul(k: pallas::Base, p: pallas::Affine) -> pallas::Affine {
+    let k = pallas::Scalar::from_repr(k.to_repr()).unwrap();
+    (p * k).to_affine()
+}
+
+#[derive(Copy, Clone)]
+struct MyCircuit {
+    k: pallas::Base,
+    p: pallas::Affine,
+}
+
+impl Default for MyCircuit {
+    fn default() -> Self {
+        MyCircuit {
+            k: pallas::Base::zero(),
+            p: pallas::Affine::generator(),
+        }
+    }
+}
+
+impl Circuit<pallas::Base> for MyCircuit {
+    type Config = (
+        EccConfig<TestFixedBases>,
+        SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases>,
+        Column<Advice>,
+        Column<Instance>,
+    );
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        let (ecc_config, sinsemilla_config) = configure_chips(meta);
+        let scalar = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(scalar);
+        meta.enable_equality(instance);
+
+        (ecc_config, sinsemilla_config, scalar, instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        // load the lookup table the ecc range checks use
+        SinsemillaChip::load(config.1, &mut layouter)?;
+
+        let ecc_chip = EccChip::construct(config.0);
+
+        let k = layouter.assign_region(
+            || "k",
+            |mut region| region.assign_advice(|| "k", config.2, 0, || Value::known(self.k))
+        )?;
+        let k = ScalarVar::from_base(ecc_chip.clone(), layouter.namespace(|| "scalar k"), &k)?;
+
+        let p = NonIdentityPoint::new(ecc_chip, layouter.namespace(|| "P"), Value::known(self.p))?;
+        let (result, _) = p.mul(layouter.namespace(|| "[k]P"), k)?;
+
+        layouter.constrain_instance(result.inner().x().cell(), config.3, 0)?;
+        layouter.constrain_instance(result.inner().y().cell(), config.3, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::arithmetic::CurveAffine;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::group::{Curve, Group};
+    use halo2_proofs::pasta::pallas;
+    use crate::merkle_tree::var_base_mul::{var_base_mul, MyCircuit};
+
+    fn coordinates(point: pallas::Affine) -> Vec<pallas::Base> {
+        let coordinates = point.coordinates().unwrap();
+        vec![*coordinates.x(), *coordinates.y()]
+    }
+
+    #[test]
+    fn test_var_base_mul() {
+        let k = 11;
+        let p = (pallas::Point::generator() * pallas::Scalar::from(1234)).to_affine();
+
+        for scalar in [pallas::Base::one(), pallas::Base::from(42), -pallas::Base::one()] {
+            let circuit = MyCircuit {
+                k: scalar,
+                p,
+            };
+            let prover = MockProver::run(k, &circuit, vec![coordinates(var_base_mul(scalar, p))]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_wrong_result() {
+        let k = 11;
+        let p = (pallas::Point::generator() * pallas::Scalar::from(1234)).to_affine();
+        let circuit = MyCircuit {
+            k: pallas::Base::from(42),
+            p,
+        };
+
+        // [43]P is not [42]P
+        let prover = MockProver::run(k, &circuit, vec![coordinates(var_base_mul(pallas::Base::from(43), p))]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,433 @@
+/// Computes the root of a Merkle tree of fan-in `ARITY` from a leaf and its authentication path.
+/// Every layer hashes `l || child_0 || .. || child_{ARITY-1}` with the Sinsemilla hash domain,
+/// where `l` is the 10-bit layer index and each child is its 255-bit little-endian encoding
+/// padded with zeros to 26 words. The current node is placed among the `ARITY - 1` siblings of
+/// its layer by a position index in `0..ARITY`. With `ARITY = 4` and the node at index 2:
+///```txt
+///        layer  |  child_0    |  child_1    |  child_2  |  child_3
+///       ----------------------------------------------------------------
+///          l    |  sibling_0  |  sibling_1  |  node     |  sibling_2
+///```
+/// The message is witnessed in pieces: `l` as one word, then every child as `lo`, its low 25
+/// words, and `hi`, its last word. A row per child recomposes `child = lo + 2^250 * hi` from
+/// the piece cells, and one-hot bits `sel` pick the node, the previous layer's hash cell,
+/// out of the children:
+///```txt
+///        sel    |  lo     |  hi     |  acc                             |  count               |  q_first  |  q_rest
+///       ---------------------------------------------------------------------------------------------------------------
+///        sel_0  |  lo_0   |  hi_0   |  sel_0 * child_0                 |  sel_0               |     1     |    0
+///        sel_1  |  lo_1   |  hi_1   |  acc_prev + sel_1 * child_1      |  count_prev + sel_1  |     0     |    1
+///         ..    |  ..     |  ..     |   ..                             |   ..                 |     0     |    1
+///```
+/// The last `acc` is constrained equal to the node and the last `count` to 1.
+use halo2_gadgets::{
+    ecc::chip::{EccChip, EccConfig},
+    sinsemilla::{
+        chip::{SinsemillaChip, SinsemillaConfig},
+        primitives as sinsemilla,
+        HashDomain, Message, MessagePiece,
+    },
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::*,
+    plonk::*,
+    pasta::*,
+    poly::Rotation,
+};
+use crate::merkle_tree::sinsemilla::pack::pack_bits_for_sinsemilla;
+use crate::merkle_tree::sinsemilla::path::{node_bits, L_BITS};
+use crate::merkle_tree::sinsemilla::{
+    configure_with_columns, Domain, TestCommitDomain, TestFixedBases, TestHashDomain, DOMAIN,
+};
+
+/// Words of the low and high pieces of a child.
+const LO_WORDS: usize = 25;
+const HI_WORDS: usize = 1;
+
+#[derive(Clone, Debug)]
+pub(crate) struct WideMerklePathConfig {
+    sel: Column<Advice>,
+    lo: Column<Advice>,
+    hi: Column<Advice>,
+    acc: Column<Advice>,
+    count: Column<Advice>,
+    q_first: Selector,
+    q_rest: Selector,
+    ecc: EccConfig<TestFixedBases>,
+    sinsemilla: SinsemillaConfig<TestHashDomain, TestCommitDomain, TestFixedBases>,
+    instance: Column<Instance>,
+}
+
+pub(crate) struct WideMerklePathChip<const ARITY: usize> {
+    config: WideMerklePathConfig,
+}
+
+impl<const ARITY: usize> WideMerklePathChip<ARITY> {
+    pub(crate) fn construct(config: WideMerklePathConfig) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> WideMerklePathConfig {
+        assert!(ARITY >= 2, "a node has at least two children");
+        assert!(
+            layer_bits(0, &[pallas::Base::zero(); ARITY]).len() <= sinsemilla::K * sinsemilla::C,
+            "the children of a node do not fit in one sinsemilla message"
+        );
+
+        let advices = [(); 10].map(|_| meta.advice_column());
+        for advice in advices {
+            meta.enable_equality(advice);
+        }
+        let (ecc, sinsemilla) = configure_with_columns(meta, advices);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let [sel, lo, hi, acc, count] = [advices[0], advices[1], advices[2], advices[3], advices[4]];
+        let q_first = meta.selector();
+        let q_rest = meta.selector();
+
+        meta.create_gate("wide layer children", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let q_rest = meta.query_selector(q_rest);
+            let sel = meta.query_advice(sel, Rotation::cur());
+            let lo = meta.query_advice(lo, Rotation::cur());
+            let hi = meta.query_advice(hi, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let count = meta.query_advice(count, Rotation::cur());
+            let count_prev = meta.query_advice(count, Rotation::prev());
+            let one = Expression::Constant(pallas::Base::one());
+            let two_pow_250 = Expression::Constant(pallas::Base::from(2).pow_vartime(&[250]));
+
+            let selected = sel.clone() * (lo + hi * two_pow_250);
+            let q_any = q_first.clone() + q_rest.clone();
+
+            vec![
+                q_any * sel.clone() * (one - sel.clone()),
+                q_first.clone() * (acc.clone() - selected.clone()),
+                q_first * (count.clone() - sel.clone()),
+                q_rest.clone() * (acc - acc_prev - selected),
+                q_rest * (count - count_prev - sel),
+            ]
+        });
+
+        WideMerklePathConfig {
+            sel,
+            lo,
+            hi,
+            acc,
+            count,
+            q_first,
+            q_rest,
+            ecc,
+            sinsemilla,
+            instance,
+        }
+    }
+
+    /// Loads the generator table. A circuit loads it once, however many paths it assigns.
+    pub(crate) fn load_table(&self, layouter: &mut impl Layouter<pallas::Base>) -> Result<(), Error> {
+        SinsemillaChip::load(self.config.sinsemilla.clone(), layouter)?;
+        Ok(())
+    }
+
+    /// Hashes `leaf` up through `siblings`, the `ARITY - 1` siblings of every layer from the
+    /// leaves upwards, where `position[i]` is the index of the node among the children at
+    /// layer `i`, and returns the cell holding the root.
+    pub(crate) fn assign(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        domain: &Domain,
+        leaf: pallas::Base,
+        siblings: &[Vec<pallas::Base>],
+        position: &[usize],
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        self.assign_layers(layouter, domain, leaf, siblings, position, None)
+    }
+
+    /// Like `assign`, but hashes `forged.1` in place of the node entering layer `forged.0`,
+    /// as a cheating prover would, to check that the path rejects it.
+    pub(crate) fn assign_forged(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        domain: &Domain,
+        leaf: pallas::Base,
+        siblings: &[Vec<pallas::Base>],
+        position: &[usize],
+        forged: (usize, pallas::Base),
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        self.assign_layers(layouter, domain, leaf, siblings, position, Some(forged))
+    }
+
+    fn assign_layers(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        domain: &Domain,
+        leaf: pallas::Base,
+        siblings: &[Vec<pallas::Base>],
+        position: &[usize],
+        forged: Option<(usize, pallas::Base)>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        assert!(!position.is_empty(), "the path has at least one layer");
+        assert_eq!(siblings.len(), position.len(), "one position per layer");
+
+        let ecc_chip = EccChip::construct(self.config.ecc.clone());
+        let sinsemilla_chip = SinsemillaChip::construct(self.config.sinsemilla.clone());
+        let hash_handler = HashDomain::new(sinsemilla_chip.clone(), ecc_chip, domain.hash_domain());
+
+        let mut node = layouter.assign_region(
+            || "leaf",
+            |mut region| region.assign_advice(|| "leaf", self.config.acc, 0, || Value::known(leaf))
+        )?;
+        for (l, (siblings, index)) in siblings.iter().zip(position).enumerate() {
+            // the node the message encodes, which only a cheating prover makes differ from the cell
+            let value = match forged {
+                Some((layer, forged)) if layer == l => Value::known(forged),
+                _ => node.value().copied(),
+            };
+            let children = value.map(|node| children::<ARITY>(node, siblings, *index));
+
+            let layer = MessagePiece::from_field_elem(
+                sinsemilla_chip.clone(),
+                layouter.namespace(|| format!("layer {} index", l)),
+                Value::known(pallas::Base::from(l as u64)),
+                1
+            )?;
+            layouter.assign_region(
+                || format!("layer {} index is {}", l, l),
+                |mut region| region.constrain_constant(layer.inner().cell_value().cell(), pallas::Base::from(l as u64))
+            )?;
+
+            let mut pieces = vec![layer];
+            for j in 0..ARITY {
+                let bits = children.clone().map(|children| node_bits(children[j]));
+                for (name, range, words) in [("lo", 0..250, LO_WORDS), ("hi", 250..255, HI_WORDS)] {
+                    pieces.push(MessagePiece::from_field_elem(
+                        sinsemilla_chip.clone(),
+                        layouter.namespace(|| format!("layer {} child {} {}", l, j, name)),
+                        bits.clone().map(|bits| pack_bits_for_sinsemilla(&bits[range.clone()])),
+                        words
+                    )?);
+                }
+            }
+            let piece_cells: Vec<_> = pieces.iter().map(|piece| piece.inner().cell_value()).collect();
+
+            let (hash, _) = hash_handler.hash(
+                layouter.namespace(|| format!("hash layer {}", l)),
+                Message::from_pieces(sinsemilla_chip.clone(), pieces)
+            )?;
+
+            layouter.assign_region(
+                || format!("layer {} children", l),
+                |mut region| {
+                    let mut acc = Value::known(pallas::Base::zero());
+                    let mut count = Value::known(pallas::Base::zero());
+                    let mut cells = None;
+                    for j in 0..ARITY {
+                        if j == 0 {
+                            self.config.q_first.enable(&mut region, j)?;
+                        } else {
+                            self.config.q_rest.enable(&mut region, j)?;
+                        }
+
+                        let sel = if j == *index { pallas::Base::one() } else { pallas::Base::zero() };
+                        region.assign_advice(|| "sel", self.config.sel, j, || Value::known(sel))?;
+                        piece_cells[1 + 2 * j].copy_advice(|| "lo", &mut region, self.config.lo, j)?;
+                        piece_cells[2 + 2 * j].copy_advice(|| "hi", &mut region, self.config.hi, j)?;
+
+                        let child = children.clone().map(|children| children[j]);
+                        acc = acc + child * Value::known(sel);
+                        count = count + Value::known(sel);
+                        cells = Some((
+                            region.assign_advice(|| "acc", self.config.acc, j, || acc)?,
+                            region.assign_advice(|| "count", self.config.count, j, || count)?,
+                        ));
+                    }
+
+                    let (acc, count) = cells.unwrap();
+                    region.constrain_equal(acc.cell(), node.cell())?;
+                    region.constrain_constant(count.cell(), pallas::Base::one())
+                }
+            )?;
+
+            node = hash.inner().clone();
+        }
+
+        Ok(node)
+    }
+
+    pub(crate) fn expose_public(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        cell: &AssignedCell<pallas::Base, pallas::Base>,
+        row: usize
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// The children of a layer, with `node` inserted among `siblings` at `index`.
+fn children<const ARITY: usize>(node: pallas::Base, siblings: &[pallas::Base], index: usize) -> Vec<pallas::Base> {
+    assert_eq!(siblings.len(), ARITY - 1, "a layer has ARITY - 1 siblings");
+    assert!(index < ARITY, "position index {} out of 0..{}", index, ARITY);
+
+    let mut children = siblings.to_vec();
+    children.insert(index, node);
+    children
+}
+
+/// The bits `l || child_0 || .. || child_{ARITY-1}` hashed at layer `l`, every child padded to
+/// `LO_WORDS + HI_WORDS` words.
+fn layer_bits(l: usize, children: &[pallas::Base]) -> Vec<bool> {
+    let child_bits = (LO_WORDS + HI_WORDS) * sinsemilla::K;
+    (0..L_BITS)
+        .map(|i| (l >> i) & 1 == 1)
+        .chain(children.iter().flat_map(|child| {
+            let mut bits = node_bits(*child);
+            bits.resize(child_bits, false);
+            bits
+        }))
+        .collect()
+}
+
+/// Computes the root off-circuit, for checking the circuit against.
+pub(crate) fn wide_merkle_root<const ARITY: usize>(
+    domain: &Domain,
+    leaf: pallas::Base,
+    siblings: &[Vec<pallas::Base>],
+    position: &[usize],
+) -> pallas::Base {
+    let hash_handler = domain.primitive();
+    siblings.iter().zip(position).enumerate().fold(leaf, |node, (l, (siblings, index))| {
+        let children = children::<ARITY>(node, siblings, *index);
+        hash_handler.hash(layer_bits(l, &children).into_iter()).unwrap()
+    })
+}
+
+/// `forged` replaces the node entering a layer in the message that layer hashes.
+#[derive(Clone, Default)]
+struct MyCircuit<const ARITY: usize> {
+    leaf: pallas::Base,
+    siblings: Vec<Vec<pallas::Base>>,
+    position: Vec<usize>,
+    forged: Option<(usize, pallas::Base)>,
+}
+
+impl<const ARITY: usize> Circuit<pallas::Base> for MyCircuit<ARITY> {
+    type Config = WideMerklePathConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            leaf: pallas::Base::zero(),
+            siblings: vec![vec![pallas::Base::zero(); ARITY - 1]; self.siblings.len()],
+            position: vec![0; self.position.len()],
+            forged: None,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+        WideMerklePathChip::<ARITY>::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<pallas::Base>) -> Result<(), Error> {
+        let chip = WideMerklePathChip::<ARITY>::construct(config);
+        chip.load_table(&mut layouter)?;
+
+        let root = match self.forged {
+            None => chip.assign(
+                layouter.namespace(|| "wide merkle path"),
+                &DOMAIN,
+                self.leaf,
+                &self.siblings,
+                &self.position,
+            )?,
+            Some(forged) => chip.assign_forged(
+                layouter.namespace(|| "wide merkle path"),
+                &DOMAIN,
+                self.leaf,
+                &self.siblings,
+                &self.position,
+                forged,
+            )?,
+        };
+
+        chip.expose_public(layouter.namespace(|| "expose root"), &root, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::pallas;
+    use crate::merkle_tree::sinsemilla::DOMAIN;
+    use crate::merkle_tree::wide::{wide_merkle_root, MyCircuit};
+
+    fn circuit(position: Vec<usize>) -> MyCircuit<4> {
+        let siblings = (0..3)
+            .map(|l| (0..3).map(|i| pallas::Base::from(100 + 10 * l + i)).collect())
+            .collect();
+
+        MyCircuit {
+            leaf: pallas::Base::from(7),
+            siblings,
+            position,
+            forged: None,
+        }
+    }
+
+    #[test]
+    fn test_arity_4() {
+        let k = 11;
+        for position in [vec![0, 0, 0], vec![3, 1, 2], vec![2, 3, 1]] {
+            let circuit = circuit(position);
+            let root = wide_merkle_root::<4>(
+                &DOMAIN,
+                circuit.leaf,
+                &circuit.siblings,
+                &circuit.position
+            );
+
+            let prover = MockProver::run(k, &circuit, vec![vec![root]]).unwrap();
+            prover.assert_satisfied();
+
+            // a wrong root must be rejected
+            let prover = MockProver::run(k, &circuit, vec![vec![root + pallas::Base::one()]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_arity_4_wrong_position() {
+        let k = 11;
+        let circuit = circuit(vec![3, 1, 2]);
+        let root = wide_merkle_root::<4>(&DOMAIN, circuit.leaf, &circuit.siblings, &[3, 2, 2]);
+
+        // the node placed at another index of the middle layer gives another root
+        let prover = MockProver::run(k, &circuit, vec![vec![root]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_arity_4_wrong_intermediate_node() {
+        let k = 11;
+        let honest = circuit(vec![3, 1, 2]);
+        let root = wide_merkle_root::<4>(&DOMAIN, honest.leaf, &honest.siblings, &honest.position);
+
+        // the honest node entering the last layer
+        let node = wide_merkle_root::<4>(&DOMAIN, honest.leaf, &honest.siblings[..2], &honest.position[..2]);
+
+        // another leaf cannot splice into the honest path there, though the last layer
+        // hashes to the root
+        let circuit = MyCircuit {
+            leaf: pallas::Base::from(8),
+            forged: Some((2, node)),
+            ..honest
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![root]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,269 @@
+//! Transcripts and randomness for real proofs, pinned so that proving the same circuit twice
+//! gives the same bytes. Every proof here uses a Blake2b transcript with 255-bit challenges
+//! over the Pasta `EqAffine` commitments.
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use blake2b_simd::Params as Blake2bParams;
+use halo2_proofs::{
+    pasta::{EqAffine, Fp},
+    plonk::{keygen_vk, Circuit, Error, VerifyingKey},
+    poly::commitment::Params,
+    transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+};
+#[cfg(any(test, feature = "bench"))]
+use halo2_proofs::plonk::{create_proof, ProvingKey};
+#[cfg(any(test, feature = "bench"))]
+use rand_core::{impls, RngCore};
+
+/// The seed of the randomness `serialize_proof` blinds with.
+#[cfg(any(test, feature = "bench"))]
+const SEED: u64 = 0x6861_6c6f_3273_7475;
+
+/// A transcript writing a proof.
+pub(crate) type ProofWriter = Blake2bWrite<Vec<u8>, EqAffine, Challenge255<EqAffine>>;
+
+/// A transcript reading back a proof written by a `ProofWriter`.
+pub(crate) type ProofReader<'a> = Blake2bRead<&'a [u8], EqAffine, Challenge255<EqAffine>>;
+
+pub(crate) fn make_writer() -> ProofWriter {
+    Blake2bWrite::init(vec![])
+}
+
+pub(crate) fn make_reader(proof: &[u8]) -> ProofReader<'_> {
+    Blake2bRead::init(proof)
+}
+
+/// A splitmix64 generator, deterministic for a given seed, which also draws reproducible test
+/// values. It is not a source of secure randomness: proofs blinded with it do not hide their
+/// witness, so it only exists in tests and benches.
+#[cfg(any(test, feature = "bench"))]
+pub(crate) struct DeterministicRng(u64);
+
+#[cfg(any(test, feature = "bench"))]
+impl DeterministicRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        DeterministicRng(seed)
+    }
+}
+
+#[cfg(any(test, feature = "bench"))]
+impl RngCore for DeterministicRng {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Proves `circuit` against `instances`, one vector per instance column, and returns the
+/// proof bytes. The blinding comes from a fixed seed, so the bytes are stable across runs but
+/// the proof is not zero-knowledge; it is only built for tests and benches, and proofs that
+/// must hide their witness use `OsRng`.
+#[cfg(any(test, feature = "bench"))]
+pub(crate) fn serialize_proof<C: Circuit<Fp>>(
+    params: &Params<EqAffine>,
+    pk: &ProvingKey<EqAffine>,
+    circuit: C,
+    instances: &[&[Fp]],
+) -> Result<Vec<u8>, Error> {
+    let mut transcript = make_writer();
+    create_proof(params, pk, &[circuit], &[instances], DeterministicRng::new(SEED), &mut transcript)?;
+    Ok(transcript.finalize())
+}
+
+/// The version of the format `vk_fingerprint` writes, its first byte.
+const FINGERPRINT_VERSION: u8 = 2;
+
+fn invalid_fingerprint(reason: &str) -> Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("invalid key fingerprint: {}", reason)).into()
+}
+
+/// The personalization of the Blake2b digest `vk_fingerprint` takes.
+const FINGERPRINT_PERSONALIZATION: &[u8; 16] = b"halo2-study-vkfp";
+
+/// Fingerprints `vk` for verifiers to check the key they derive against: the version byte,
+/// `k` as four little-endian bytes, then the 64-byte Blake2b digest of the pinned description
+/// of the key, which covers its constraint system and fixed commitments.
+///
+/// The commitments of a verifying key are private in this halo2 revision, so the key cannot
+/// be written out; the fingerprint identifies a key without holding it.
+pub(crate) fn vk_fingerprint(vk: &VerifyingKey<EqAffine>) -> Vec<u8> {
+    let mut bytes = vec![FINGERPRINT_VERSION];
+    bytes.extend_from_slice(&vk.get_domain().k().to_le_bytes());
+    let digest = Blake2bParams::new()
+        .hash_length(64)
+        .personal(FINGERPRINT_PERSONALIZATION)
+        .hash(format!("{:?}", vk.pinned()).as_bytes());
+    bytes.extend_from_slice(digest.as_bytes());
+    bytes
+}
+
+/// Derives the verifying key of `circuit` at the `k` of `fingerprint`, as `vk_fingerprint`
+/// writes it, and checks that it is the fingerprinted key, returning the parameters and the
+/// key. Fails if the version is not `FINGERPRINT_VERSION` or the key of `circuit` differs.
+pub(crate) fn check_vk_fingerprint<C: Circuit<Fp>>(
+    fingerprint: &[u8],
+    circuit: &C,
+) -> Result<(Params<EqAffine>, VerifyingKey<EqAffine>), Error> {
+    match fingerprint.first() {
+        Some(&FINGERPRINT_VERSION) => (),
+        Some(version) => return Err(invalid_fingerprint(&format!("unsupported version {}", version))),
+        None => return Err(invalid_fingerprint("empty")),
+    }
+    let k = fingerprint.get(1..5).ok_or_else(|| invalid_fingerprint("truncated"))?;
+    let k = u32::from_le_bytes(k.try_into().unwrap());
+
+    let params: Params<EqAffine> = Params::new(k);
+    let vk = keygen_vk(&params, circuit)?;
+    if vk_fingerprint(&vk) != fingerprint {
+        return Err(invalid_fingerprint("the circuit has another key"));
+    }
+    Ok((params, vk))
+}
+
+/// The file `load_or_create_params` caches the parameters at `k` in. The parameters depend
+/// on `k` alone, so every circuit proven at `k` shares the entry.
+fn params_cache_path(k: u32, cache_dir: &Path) -> PathBuf {
+    cache_dir.join(format!("params-k{}.bin", k))
+}
+
+/// Returns the parameters at `k`, read from the cache in `cache_dir` if it has them, and
+/// otherwise generated and written to it.
+///
+/// Only the parameters are cached; the keys of this halo2 revision cannot be serialized, so
+/// the caller runs `keygen_vk` and `keygen_pk` over the parameters. The cache file is written
+/// under a temporary name and renamed into place once flushed, so a concurrent or interrupted
+/// run never reads a partial file.
+pub(crate) fn load_or_create_params(k: u32, cache_dir: &Path) -> Result<Params<EqAffine>, Error> {
+    let path = params_cache_path(k, cache_dir);
+
+    let params = if path.exists() {
+        Params::read(&mut BufReader::new(File::open(&path)?))?
+    } else {
+        let params: Params<EqAffine> = Params::new(k);
+        fs::create_dir_all(cache_dir)?;
+
+        let tmp = path.with_extension(format!("tmp-{}", std::process::id()));
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        params.write(&mut writer)?;
+        writer.into_inner().map_err(|err| err.into_error())?.sync_all()?;
+        fs::rename(&tmp, &path)?;
+        params
+    };
+
+    Ok(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::pasta::{EqAffine, Fp};
+    use halo2_proofs::plonk::{keygen_pk, keygen_vk, verify_proof, SingleVerifier};
+    use halo2_proofs::poly::commitment::Params;
+    use crate::fibonacci::example1::MyCircuit;
+    use crate::prover::{
+        check_vk_fingerprint, load_or_create_params, make_reader, params_cache_path, serialize_proof, vk_fingerprint,
+    };
+
+    #[test]
+    fn test_stable_proof() {
+        let circuit = MyCircuit::<Fp>::default();
+        let public_input = [Fp::from(1), Fp::from(1), Fp::from(55)];
+
+        let params: Params<EqAffine> = Params::new(4);
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk, &circuit).unwrap();
+
+        let proof = serialize_proof(&params, &pk, circuit, &[&public_input]).unwrap();
+        assert_eq!(serialize_proof(&params, &pk, circuit, &[&public_input]).unwrap(), proof);
+
+        // the proof still verifies
+        let strategy = SingleVerifier::new(&params);
+        let mut transcript = make_reader(&proof);
+        verify_proof(&params, pk.get_vk(), strategy, &[&[&public_input]], &mut transcript).unwrap();
+    }
+
+    #[test]
+    fn test_params_cache() {
+        let circuit = MyCircuit::<Fp>::default();
+        let cache_dir = std::env::temp_dir().join("halo2-study-params-cache");
+        let _ = std::fs::remove_dir_all(&cache_dir);
+
+        let params = load_or_create_params(4, &cache_dir).unwrap();
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let path = params_cache_path(4, &cache_dir);
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+
+        // only the renamed file is left behind
+        assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 1);
+
+        // the second run reads the cache instead of writing it
+        let params = load_or_create_params(4, &cache_dir).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().modified().unwrap(), modified);
+
+        // and gives the same keys, which prove
+        let cached_vk = keygen_vk(&params, &circuit).unwrap();
+        assert_eq!(vk_fingerprint(&cached_vk), vk_fingerprint(&vk));
+        let pk = keygen_pk(&params, cached_vk.clone(), &circuit).unwrap();
+        let public_input = [Fp::from(1), Fp::from(1), Fp::from(55)];
+        let proof = serialize_proof(&params, &pk, circuit, &[&public_input]).unwrap();
+        let strategy = SingleVerifier::new(&params);
+        let mut transcript = make_reader(&proof);
+        verify_proof(&params, &cached_vk, strategy, &[&[&public_input]], &mut transcript).unwrap();
+
+        // another k is another entry
+        assert_ne!(params_cache_path(5, &cache_dir), path);
+    }
+
+    #[test]
+    fn test_vk_fingerprint() {
+        let circuit = MyCircuit::<Fp>::default();
+        let params: Params<EqAffine> = Params::new(4);
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk.clone(), &circuit).unwrap();
+
+        let fingerprint = vk_fingerprint(&vk);
+        assert_eq!(fingerprint.len(), 1 + 4 + 64);
+        assert_eq!(fingerprint[1..5], 4u32.to_le_bytes());
+        let (verifier_params, verifier_vk) = check_vk_fingerprint(&fingerprint, &circuit).unwrap();
+        assert_eq!(vk_fingerprint(&verifier_vk), fingerprint);
+
+        // the checked key verifies the prover's proofs
+        let public_input = [Fp::from(1), Fp::from(1), Fp::from(55)];
+        let proof = serialize_proof(&params, &pk, circuit, &[&public_input]).unwrap();
+        let strategy = SingleVerifier::new(&verifier_params);
+        let mut transcript = make_reader(&proof);
+        verify_proof(&verifier_params, &verifier_vk, strategy, &[&[&public_input]], &mut transcript).unwrap();
+    }
+
+    #[test]
+    fn test_vk_fingerprint_mismatch() {
+        let circuit = MyCircuit::<Fp>::default();
+        let params: Params<EqAffine> = Params::new(4);
+        let mut fingerprint = vk_fingerprint(&keygen_vk(&params, &circuit).unwrap());
+
+        // a key fingerprinted at another k is another key
+        let mut other_k = fingerprint.clone();
+        other_k[1..5].copy_from_slice(&5u32.to_le_bytes());
+        assert!(check_vk_fingerprint(&other_k, &circuit).is_err());
+
+        fingerprint[0] += 1;
+        assert!(check_vk_fingerprint(&fingerprint, &circuit).is_err());
+        assert!(check_vk_fingerprint(&[], &circuit).is_err());
+    }
+}
@@ -1,2 +1,17 @@
-mod example1;
-mod example2;
+pub(crate) mod batch;
+pub(crate) mod bytes;
+pub(crate) mod div_rem;
+pub(crate) mod example1;
+pub(crate) mod example2;
+pub(crate) mod function_table;
+pub(crate) mod k_hot;
+pub(crate) mod less_than;
+pub(crate) mod lookup_table;
+pub(crate) mod mod_reduce;
+pub(crate) mod one_of;
+pub(crate) mod parity;
+pub(crate) mod pow2;
+pub(crate) mod popcount;
+pub(crate) mod running_sum;
+pub(crate) mod sorted;
+pub(crate) mod wide_add;
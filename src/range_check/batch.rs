@@ -0,0 +1,128 @@
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::commitment::Params,
+    arithmetic::FieldExt,
+    pasta::{EqAffine, Fp},
+    transcript::{Blake2bWrite, Challenge255},
+};
+use rand_core::OsRng;
+use crate::StudyChip;
+use crate::range_check::example1::{RangeCheckChip, RangeCheckConfig};
+
+/// The `k` range-check proofs are created and verified at.
+pub(crate) const K: u32 = 4;
+
+/// The range check of `example1`, with the checked value exposed at instance row 0, so
+/// that each proof is bound to the value it is about.
+#[derive(Default, Copy, Clone)]
+struct MyCircuit<F> {
+    v: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (RangeCheckConfig<F, 8>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        (RangeCheckChip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, (config, instance): Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = RangeCheckChip::construct(config);
+        let cell = chip.assign(layouter.namespace(|| "range check"), self.v)?;
+
+        layouter.constrain_instance(cell.cell(), instance, 0)
+    }
+}
+
+/// Generates the parameters and the proving key every range-check proof shares.
+pub(crate) fn setup() -> Result<(Params<EqAffine>, ProvingKey<EqAffine>), Error> {
+    let circuit = MyCircuit::<Fp>::default();
+
+    let params: Params<EqAffine> = Params::new(K);
+    let vk = keygen_vk(&params, &circuit)?;
+    let pk = keygen_pk(&params, vk, &circuit)?;
+
+    Ok((params, pk))
+}
+
+/// Proves that `v` is in `[0, 8)`, returning the proof bytes and its public inputs.
+pub(crate) fn prove(params: &Params<EqAffine>, pk: &ProvingKey<EqAffine>, v: u64)
+    -> Result<(Vec<u8>, Vec<Fp>), Error> {
+    let circuit = MyCircuit { v: Fp::from(v) };
+    let public_input = vec![Fp::from(v)];
+
+    let mut transcript = Blake2bWrite::<_, EqAffine, Challenge255<_>>::init(vec![]);
+    create_proof(params, pk, &[circuit], &[&[&public_input]], OsRng, &mut transcript)?;
+
+    Ok((transcript.finalize(), public_input))
+}
+
+/// Verifies many range-check proofs against the same verifying key at once. The batch
+/// verifier folds every proof's final multi-scalar multiplication into a single one,
+/// so the cost of that step is paid once per batch instead of once per proof.
+///
+/// The batch verifier only reports whether every proof holds, not which one does not,
+/// so any failure surfaces as `Error::Opening`.
+pub(crate) fn batch_verify(
+    params: &Params<EqAffine>,
+    vk: &VerifyingKey<EqAffine>,
+    proofs: &[(Vec<u8>, Vec<Fp>)],
+) -> Result<(), Error> {
+    let mut verifier = BatchVerifier::new();
+    for (proof, public_input) in proofs {
+        verifier.add_proof(vec![vec![public_input.clone()]], proof.clone());
+    }
+
+    if verifier.finalize(params, vk) {
+        Ok(())
+    } else {
+        Err(Error::Opening)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::Error;
+    use crate::range_check::batch::{batch_verify, prove, setup};
+
+    #[test]
+    fn test_batch_verify() {
+        let (params, pk) = setup().unwrap();
+        let mut proofs = [2, 5, 7]
+            .into_iter()
+            .map(|v| prove(&params, &pk, v))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert!(batch_verify(&params, pk.get_vk(), &proofs).is_ok());
+
+        // one corrupted proof fails the whole batch
+        let corrupted = proofs[1].0.len() / 2;
+        proofs[1].0[corrupted] ^= 1;
+        assert!(matches!(batch_verify(&params, pk.get_vk(), &proofs), Err(Error::Opening)));
+    }
+
+    #[test]
+    fn test_batch_verify_wrong_public_input() {
+        let (params, pk) = setup().unwrap();
+        let mut proofs = [1, 3]
+            .into_iter()
+            .map(|v| prove(&params, &pk, v))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        // a valid proof does not verify against another value
+        proofs[0].1 = vec![Fp::from(4)];
+        assert!(batch_verify(&params, pk.get_vk(), &proofs).is_err());
+    }
+}
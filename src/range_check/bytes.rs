@@ -0,0 +1,328 @@
+/// Decomposes a field element into its 32 little-endian bytes, so that the bytes can feed a
+/// byte-oriented hash while staying bound to the value. Every byte is looked up in an 8-bit
+/// range-check table, and a running sum from the most significant byte rebuilds the value,
+/// `acc_i = byte_i + 2^8 * acc_{i+1}`:
+///```txt
+///        byte     |  acc      |  max      |  inv  |  eq      |  q_lookup  |  q_step  |  q_last
+///       --------------------------------------------------------------------------------------------
+///        byte_0   |  value    |  max_0    |  ..   |  eq_0    |     1      |    1     |    0
+///        byte_1   |  acc_1    |  max_1    |  ..   |  eq_1    |     1      |    1     |    0
+///         ..      |   ..      |   ..      |  ..   |   ..     |     1      |    ..    |    0
+///        byte_31  |  byte_31  |  max_31   |  ..   |  eq_31   |     1      |    0     |    1
+///```
+/// The running sum only rebuilds the value modulo the field, so the bytes of `value + p`, or
+/// of any other multiple of `p` added while the sum fits 256 bits, would rebuild it too. The bytes
+/// are therefore compared with those of `p - 1`, the fixed `max`, from the most significant one:
+/// `eq_i` is whether the bytes from `i` upwards equal those of `p - 1`, computed with `inv` as in
+/// `IsZeroChip`, and wherever the bytes above are equal, `max_i - byte_i` is looked up in the
+/// byte table. The bytes thus encode an integer of at most `p - 1`, the canonical decomposition.
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use crate::range_check::example2::table::RangeCheckTable;
+
+/// The bytes of a field element.
+const NUM_BYTES: usize = 32;
+
+#[derive(Debug, Clone)]
+pub(crate) struct BytesConfig<F: FieldExt> {
+    byte: Column<Advice>,
+    acc: Column<Advice>,
+    max: Column<Fixed>,
+    inv: Column<Advice>,
+    eq: Column<Advice>,
+    q_lookup: Selector,
+    q_step: Selector,
+    q_last: Selector,
+    table: RangeCheckTable<F, 8>,
+}
+
+pub(crate) struct BytesChip<F: FieldExt> {
+    config: BytesConfig<F>,
+}
+
+impl<F: FieldExt> BytesChip<F> {
+    pub(crate) fn construct(config: BytesConfig<F>) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> BytesConfig<F> {
+        let byte = meta.advice_column();
+        let acc = meta.advice_column();
+        let max = meta.fixed_column();
+        let inv = meta.advice_column();
+        let eq = meta.advice_column();
+        let q_lookup = meta.complex_selector();
+        let q_step = meta.complex_selector();
+        let q_last = meta.complex_selector();
+        let table = RangeCheckTable::configure(meta);
+
+        meta.enable_equality(byte);
+        meta.enable_equality(acc);
+
+        meta.lookup(|meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let byte = meta.query_advice(byte, Rotation::cur());
+
+            vec![
+                (q_lookup * byte, table.value)
+            ]
+        });
+
+        meta.create_gate("running sum", |meta| {
+            let s = meta.query_selector(q_step);
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let acc_cur = meta.query_advice(acc, Rotation::cur());
+            let acc_next = meta.query_advice(acc, Rotation::next());
+
+            Constraints::with_selector(s, [
+                ("acc_cur = byte + 2^8 * acc_next", acc_cur - byte - acc_next * Expression::Constant(F::from(1u64 << 8))),
+            ])
+        });
+
+        meta.create_gate("last byte", |meta| {
+            let s = meta.query_selector(q_last);
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+
+            Constraints::with_selector(s, [("acc = byte", acc - byte)])
+        });
+
+        // is_eq_i = 1 - (max_i - byte_i) * inv_i is whether byte_i = max_i
+        let is_eq = |meta: &mut VirtualCells<F>| {
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let max = meta.query_fixed(max, Rotation::cur());
+            let inv = meta.query_advice(inv, Rotation::cur());
+            let diff = max - byte;
+            (diff.clone(), Expression::Constant(F::one()) - diff * inv)
+        };
+
+        meta.create_gate("canonical", |meta| {
+            let q_step = meta.query_selector(q_step);
+            let q_last = meta.query_selector(q_last);
+            let eq = meta.query_advice(eq, Rotation::cur());
+            let eq_next = meta.query_advice(eq, Rotation::next());
+            let (diff, is_eq) = is_eq(meta);
+
+            vec![
+                (q_step.clone() + q_last.clone()) * diff * is_eq.clone(),
+                q_step * (eq.clone() - eq_next * is_eq.clone()),
+                q_last * (eq - is_eq),
+            ]
+        });
+
+        // byte_i <= max_i wherever the bytes above equal those of p - 1
+        meta.lookup(|meta| {
+            let q_step = meta.query_selector(q_step);
+            let q_last = meta.query_selector(q_last);
+            let eq_next = meta.query_advice(eq, Rotation::next());
+            let (diff, _) = is_eq(meta);
+
+            vec![
+                ((q_step * eq_next + q_last) * diff, table.value)
+            ]
+        });
+
+        BytesConfig {
+            byte,
+            acc,
+            max,
+            inv,
+            eq,
+            q_lookup,
+            q_step,
+            q_last,
+            table,
+        }
+    }
+
+    pub(crate) fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.table.assign(layouter)
+    }
+
+    /// Decomposes `value` into its little-endian bytes, returning the cell of the value
+    /// and the cells of the bytes.
+    pub(crate) fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<(AssignedCell<F, F>, Vec<AssignedCell<F, F>>), Error> {
+        let bytes = (0..NUM_BYTES)
+            .map(|i| value.map(|value| F::from(value.to_repr().as_ref()[i] as u64)))
+            .collect::<Vec<_>>();
+        self.assign_bytes(layouter, &bytes)
+    }
+
+    /// Assigns `bytes` as given, with the running sums rebuilt from them.
+    fn assign_bytes(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bytes: &[Value<F>],
+    ) -> Result<(AssignedCell<F, F>, Vec<AssignedCell<F, F>>), Error> {
+        assert_eq!(bytes.len(), NUM_BYTES);
+        let max = (-F::one()).to_repr();
+
+        layouter.assign_region(
+            || "bytes",
+            |mut region| {
+                let mut acc = Value::known(F::zero());
+                let mut eq = Value::known(true);
+                let mut acc_cells = vec![];
+                let mut byte_cells = vec![];
+
+                for row in (0..NUM_BYTES).rev() {
+                    self.config.q_lookup.enable(&mut region, row)?;
+                    if row == NUM_BYTES - 1 {
+                        self.config.q_last.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_step.enable(&mut region, row)?;
+                    }
+
+                    let max = F::from(max.as_ref()[row] as u64);
+                    region.assign_fixed(|| format!("max {}", row), self.config.max, row, || Value::known(max))?;
+                    let diff = bytes[row].map(|byte| max - byte);
+                    region.assign_advice(
+                        || format!("inv {}", row),
+                        self.config.inv,
+                        row,
+                        || diff.map(|diff| diff.invert().unwrap_or(F::zero()))
+                    )?;
+                    eq = eq.zip(diff).map(|(eq, diff)| eq && diff == F::zero());
+                    region.assign_advice(
+                        || format!("eq {}", row),
+                        self.config.eq,
+                        row,
+                        || eq.map(|eq| if eq { F::one() } else { F::zero() })
+                    )?;
+
+                    acc = acc.zip(bytes[row]).map(|(acc, byte)| byte + acc * F::from(1u64 << 8));
+                    byte_cells.push(region.assign_advice(|| format!("byte {}", row), self.config.byte, row, || bytes[row])?);
+                    acc_cells.push(region.assign_advice(|| format!("acc {}", row), self.config.acc, row, || acc)?);
+                }
+
+                byte_cells.reverse();
+                Ok((acc_cells.pop().unwrap(), byte_cells))
+            }
+        )
+    }
+}
+
+/// Decomposes `value` and exposes it at instance row 0 followed by its bytes.
+#[derive(Default)]
+struct MyCircuit<F> {
+    value: F,
+    /// Overrides the honest bytes.
+    bytes: Option<Vec<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (BytesConfig<F>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (BytesChip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = BytesChip::construct(config.0);
+        chip.load_table(&mut layouter)?;
+
+        let (value, bytes) = match &self.bytes {
+            Some(bytes) => {
+                let bytes: Vec<_> = bytes.iter().copied().map(Value::known).collect();
+                chip.assign_bytes(layouter.namespace(|| "bytes"), &bytes)?
+            }
+            None => chip.assign(layouter.namespace(|| "bytes"), Value::known(self.value))?,
+        };
+
+        layouter.constrain_instance(value.cell(), config.1, 0)?;
+        for (i, byte) in bytes.iter().enumerate() {
+            layouter.constrain_instance(byte.cell(), config.1, i + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::pasta::group::ff::PrimeField;
+    use crate::range_check::bytes::{MyCircuit, NUM_BYTES};
+
+    /// The instance of `MyCircuit`: `value` followed by `bytes`, zero-padded.
+    fn instances(value: u64, bytes: &[u64]) -> Vec<Fp> {
+        let mut instances = vec![Fp::from(value)];
+        instances.extend((0..NUM_BYTES).map(|i| Fp::from(bytes.get(i).copied().unwrap_or(0))));
+        instances
+    }
+
+    #[test]
+    fn test_reconstruct() {
+        let circuit = MyCircuit {
+            value: Fp::from(0x0102_0304_0506),
+            bytes: None,
+        };
+        let instances = instances(0x0102_0304_0506, &[0x06, 0x05, 0x04, 0x03, 0x02, 0x01]);
+        let prover = MockProver::run(9, &circuit, vec![instances]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_byte_out_of_range() {
+        // 0x1234 = 0x134 + 0x11 * 2^8 rebuilds the value, but 0x134 is not a byte
+        let mut bytes = vec![Fp::zero(); NUM_BYTES];
+        bytes[0] = Fp::from(0x134);
+        bytes[1] = Fp::from(0x11);
+        let circuit = MyCircuit {
+            value: Fp::from(0x1234),
+            bytes: Some(bytes),
+        };
+        let prover = MockProver::run(9, &circuit, vec![instances(0x1234, &[0x134, 0x11])]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_non_canonical() {
+        // the bytes of p - 1 decompose it canonically
+        let max: Vec<u64> = (-Fp::one()).to_repr().iter().map(|byte| *byte as u64).collect();
+        let circuit = MyCircuit {
+            value: -Fp::one(),
+            bytes: None,
+        };
+        let mut public = vec![-Fp::one()];
+        public.extend(max.iter().map(|byte| Fp::from(*byte)));
+        let prover = MockProver::run(9, &circuit, vec![public]).unwrap();
+        prover.assert_satisfied();
+
+        // the bytes of 5 + p = 4 + (p - 1) + 1 rebuild 5 as well
+        let mut bytes = vec![];
+        let mut carry = 5;
+        for byte in max {
+            let sum = byte + carry;
+            bytes.push(sum & 0xff);
+            carry = sum >> 8;
+        }
+        assert_eq!(carry, 0);
+
+        let circuit = MyCircuit {
+            value: Fp::from(5),
+            bytes: Some(bytes.iter().map(|byte| Fp::from(*byte)).collect()),
+        };
+        let prover = MockProver::run(9, &circuit, vec![instances(5, &bytes)]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,250 @@
+/// This helper divides a 32-bit `dividend` by a witnessed 32-bit `divisor`, witnessing the
+/// quotient and remainder:
+///```txt
+///        dividend  |  divisor  |  quotient  |  remainder  |  q_div_rem
+///       -------------------------------------------------------------------
+///           n      |     d     |     q      |      r      |     1
+///```
+/// The gate checks `n = q * d + r`, and a `LessThanChip` row checks `r < d`, which also rejects
+/// `d = 0`. Over a field any `r` has some `q` satisfying the gate, so all four values are range
+/// checked to 32 bits with `LessThanChip::range_check`; then `q * d + r` is
+/// below the modulus and the gate holds over the integers.
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    arithmetic::FieldExt,
+    poly::Rotation,
+};
+use crate::range_check::less_than::{LessThanChip, LessThanConfig};
+
+#[derive(Debug, Clone)]
+pub(crate) struct DivRemConfig<F: FieldExt> {
+    dividend: Column<Advice>,
+    divisor: Column<Advice>,
+    quotient: Column<Advice>,
+    remainder: Column<Advice>,
+    q_div_rem: Selector,
+    less_than: LessThanConfig<F, 4>,
+}
+
+pub(crate) struct DivRemChip<F: FieldExt> {
+    config: DivRemConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> DivRemChip<F> {
+    pub(crate) fn construct(config: DivRemConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> DivRemConfig<F> {
+        let [dividend, divisor, quotient, remainder] = [(); 4].map(|_| meta.advice_column());
+        let constants = meta.fixed_column();
+        let q_div_rem = meta.selector();
+
+        for column in [dividend, divisor, quotient, remainder] {
+            meta.enable_equality(column);
+        }
+        meta.enable_constant(constants);
+
+        meta.create_gate("div rem", |meta| {
+            let s = meta.query_selector(q_div_rem);
+            let dividend = meta.query_advice(dividend, Rotation::cur());
+            let divisor = meta.query_advice(divisor, Rotation::cur());
+            let quotient = meta.query_advice(quotient, Rotation::cur());
+            let remainder = meta.query_advice(remainder, Rotation::cur());
+
+            Constraints::with_selector(s, [
+                ("dividend = quotient * divisor + remainder", dividend - (quotient * divisor + remainder)),
+            ])
+        });
+
+        DivRemConfig {
+            dividend,
+            divisor,
+            quotient,
+            remainder,
+            q_div_rem,
+            less_than: LessThanChip::configure(meta),
+        }
+    }
+
+    pub(crate) fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        LessThanChip::construct(self.config.less_than.clone()).load_table(layouter)
+    }
+
+    /// Returns the cells of `dividend / divisor` and `dividend % divisor`. A zero `divisor`
+    /// is witnessed with quotient 0 and remainder `dividend`, which fails `r < d`.
+    pub(crate) fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        dividend: Value<u64>,
+        divisor: Value<u64>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let quotient = dividend.zip(divisor).map(|(n, d)| n.checked_div(d).unwrap_or(0));
+        let remainder = dividend.zip(divisor).map(|(n, d)| n.checked_rem(d).unwrap_or(n));
+        self.assign_values(
+            layouter,
+            dividend.map(F::from),
+            divisor.map(F::from),
+            quotient.map(F::from),
+            remainder.map(F::from),
+        )
+    }
+
+    /// `assign` with the quotient and remainder witnessed as `quotient` and `remainder`, which
+    /// need not be 32-bit values.
+    fn assign_values(
+        &self,
+        mut layouter: impl Layouter<F>,
+        dividend: Value<F>,
+        divisor: Value<F>,
+        quotient: Value<F>,
+        remainder: Value<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let (dividend, divisor, quotient, remainder) = layouter.assign_region(
+            || "div rem",
+            |mut region| {
+                self.config.q_div_rem.enable(&mut region, 0)?;
+
+                Ok((
+                    region.assign_advice(|| "dividend", self.config.dividend, 0, || dividend)?,
+                    region.assign_advice(|| "divisor", self.config.divisor, 0, || divisor)?,
+                    region.assign_advice(|| "quotient", self.config.quotient, 0, || quotient)?,
+                    region.assign_advice(|| "remainder", self.config.remainder, 0, || remainder)?,
+                ))
+            }
+        )?;
+
+        let less_than = LessThanChip::construct(self.config.less_than.clone());
+        for (name, cell) in [("dividend", &dividend), ("divisor", &divisor), ("quotient", &quotient), ("remainder", &remainder)] {
+            less_than.range_check(layouter.namespace(|| format!("{} < 2^32", name)), cell)?;
+        }
+
+        let lt = less_than.assign_from_cells(layouter.namespace(|| "remainder < divisor"), &remainder, &divisor)?;
+        layouter.assign_region(
+            || "remainder < divisor holds",
+            |mut region| region.constrain_constant(lt.cell(), F::one())
+        )?;
+
+        Ok((quotient, remainder))
+    }
+}
+
+/// Divides `dividend` by `divisor`, exposing the quotient at instance row 0 and the remainder
+/// at row 1. `quotient_remainder` overrides the honest `(q, r)`.
+#[derive(Default)]
+struct MyCircuit<F> {
+    dividend: u64,
+    divisor: u64,
+    quotient_remainder: Option<(F, F)>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (DivRemConfig<F>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (DivRemChip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = DivRemChip::construct(config.0);
+        chip.load_table(&mut layouter)?;
+
+        let (dividend, divisor) = (Value::known(self.dividend), Value::known(self.divisor));
+        let (quotient, remainder) = match self.quotient_remainder {
+            None => chip.assign(layouter.namespace(|| "div rem"), dividend, divisor)?,
+            Some((q, r)) => chip.assign_values(
+                layouter.namespace(|| "div rem"),
+                dividend.map(F::from),
+                divisor.map(F::from),
+                Value::known(q),
+                Value::known(r),
+            )?,
+        };
+
+        layouter.constrain_instance(quotient.cell(), config.1, 0)?;
+        layouter.constrain_instance(remainder.cell(), config.1, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::range_check::div_rem::MyCircuit;
+
+    #[test]
+    fn test_div_rem() {
+        let circuit = MyCircuit {
+            dividend: 17,
+            divisor: 5,
+            quotient_remainder: None,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(3), Fp::from(2)]]).unwrap();
+        prover.assert_satisfied();
+
+        // 17 = 2 * 5 + 7, but 7 is not a remainder of 5
+        let circuit = MyCircuit {
+            dividend: 17,
+            divisor: 5,
+            quotient_remainder: Some((Fp::from(2), Fp::from(7))),
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(2), Fp::from(7)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_exact_division() {
+        let circuit = MyCircuit {
+            dividend: 4096,
+            divisor: 64,
+            quotient_remainder: None,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(64), Fp::zero()]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        let circuit = MyCircuit {
+            dividend: 17,
+            divisor: 0,
+            quotient_remainder: None,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::zero(), Fp::from(17)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_remainder_wraps() {
+        // 3 * 6 + (p - 1) = 17 in the field, and p - 1 < 6 passes a 4-byte comparison,
+        // but the remainder is not a 32-bit value
+        let circuit = MyCircuit {
+            dividend: 17,
+            divisor: 6,
+            quotient_remainder: None,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(2), Fp::from(5)]]).unwrap();
+        prover.assert_satisfied();
+
+        let circuit = MyCircuit {
+            dividend: 17,
+            divisor: 6,
+            quotient_remainder: Some((Fp::from(3), -Fp::one())),
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(3), -Fp::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
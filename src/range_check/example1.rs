@@ -5,33 +5,39 @@ use halo2_proofs::{
     poly::Rotation,
     arithmetic::FieldExt,
 };
+use crate::StudyChip;
 
 
 /// This helper checks that the value witnessed in a given cell is within a given range.
 #[derive(Clone, Copy)]
-struct RangeCheckConfig<F: FieldExt, const RANGE: usize> {
+pub(crate) struct RangeCheckConfig<F: FieldExt, const RANGE: usize> {
     value: Column<Advice>,
     q_range_check: Selector,
     _marker: PhantomData<F>
 }
 
 
-struct RangeCheckChip<F: FieldExt, const RANGE: usize> {
+pub(crate) struct RangeCheckChip<F: FieldExt, const RANGE: usize> {
     config: RangeCheckConfig<F, RANGE>,
 }
 
 
-impl<F: FieldExt, const RANGE: usize> RangeCheckChip<F, RANGE> {
-    fn construct(config: RangeCheckConfig<F, RANGE>) -> Self {
+impl<F: FieldExt, const RANGE: usize> StudyChip<F> for RangeCheckChip<F, RANGE> {
+    type Config = RangeCheckConfig<F, RANGE>;
+    type Output = AssignedCell<F, F>;
+
+    fn construct(config: Self::Config) -> Self {
         Self {
             config
         }
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> RangeCheckConfig<F, RANGE> {
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
         let value = meta.advice_column();
         let q_range_check = meta.selector();
 
+        meta.enable_equality(value);
+
         meta.create_gate("range check", |meta| {
             let v = meta.query_advice(value, Rotation::cur());
             let s = meta.query_selector(q_range_check);
@@ -51,28 +57,30 @@ impl<F: FieldExt, const RANGE: usize> RangeCheckChip<F, RANGE> {
             _marker: PhantomData,
         }
     }
+}
 
-    fn assign(&self, mut layouter: impl Layouter<F>, value: F) -> Result<(), Error> {
+impl<F: FieldExt, const RANGE: usize> RangeCheckChip<F, RANGE> {
+    /// Returns the cell of the range-checked value.
+    pub(crate) fn assign(&self, mut layouter: impl Layouter<F>, value: F) -> Result<AssignedCell<F, F>, Error> {
         layouter.assign_region(
             || "assign value",
             |mut region| {
                 self.config.q_range_check.enable(&mut region, 0)?;
 
-                region.assign_advice(||"value", self.config.value, 0, || Value::known(value))?;
-
-                Ok(())
+                region.assign_advice(||"value", self.config.value, 0, || Value::known(value))
             }
         )
     }
 }
 
+/// Checks that `v` is in `[0, RANGE)`.
 #[derive(Default, Copy, Clone)]
-struct MyCircuit<F> {
-    v: F
+pub(crate) struct MyCircuit<F, const RANGE: usize> {
+    pub(crate) v: F
 }
 
-impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
-    type Config = RangeCheckConfig<F, 8>;
+impl<F: FieldExt, const RANGE: usize> Circuit<F> for MyCircuit<F, RANGE> {
+    type Config = RangeCheckConfig<F, RANGE>;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
@@ -85,7 +93,9 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
 
     fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
         let chip = RangeCheckChip::construct(config);
-        chip.assign(layouter, self.v)
+        chip.assign(layouter, self.v)?;
+
+        Ok(())
     }
 }
 
@@ -98,7 +108,7 @@ mod tests {
 
     #[test]
     fn test_circuit() {
-        let circuit = MyCircuit {
+        let circuit = MyCircuit::<_, 8> {
             v: Fp::from(2)
         };
 
@@ -14,20 +14,20 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
-mod table;
+pub(crate) mod table;
 use table::RangeCheckTable;
 
-const RANGE_CHECK_BITS: usize = 3;
-
+/// Values of at most `RANGE_CHECK_BITS` bits are checked by the gate, larger ones by the lookup.
+/// The gate's degree doubles with every bit, so the threshold trades degree for table rows.
 #[derive(Clone)]
-struct RangeCheckConfig<F: FieldExt, const NUM_BITS: usize> {
+struct RangeCheckConfig<F: FieldExt, const NUM_BITS: usize, const RANGE_CHECK_BITS: usize> {
     value: Column<Advice>,
     q_range_check: Selector,
     q_lookup: Selector,
     table: RangeCheckTable<F, NUM_BITS>
 }
 
-impl<F: FieldExt, const NUM_BITS: usize> RangeCheckConfig<F, NUM_BITS> {
+impl<F: FieldExt, const NUM_BITS: usize, const RANGE_CHECK_BITS: usize> RangeCheckConfig<F, NUM_BITS, RANGE_CHECK_BITS> {
     fn configure(
         meta: &mut ConstraintSystem<F>,
         value: Column<Advice>
@@ -37,6 +37,7 @@ impl<F: FieldExt, const NUM_BITS: usize> RangeCheckConfig<F, NUM_BITS> {
 
         let table = RangeCheckTable::configure(meta);
 
+        meta.enable_equality(value);
 
         // Range-check gate
         // for a value v and a range R, check that v < R
@@ -51,7 +52,7 @@ impl<F: FieldExt, const NUM_BITS: usize> RangeCheckConfig<F, NUM_BITS> {
                 })
             };
 
-            Constraints::with_selector(s, [("range check", range_check(1<<NUM_BITS, v))])
+            Constraints::with_selector(s, [("range check", range_check(1<<RANGE_CHECK_BITS, v))])
         });
 
         // Range-check lookup
@@ -99,19 +100,91 @@ impl<F: FieldExt, const NUM_BITS: usize> RangeCheckConfig<F, NUM_BITS> {
             )
         }
     }
+
+    /// Copies `cell` into a range-check region, checking that the value it holds is
+    /// at most `num_bits` bits.
+    fn assign_from_cell(
+        &self,
+        layouter: impl Layouter<F>,
+        cell: &AssignedCell<Assigned<F>, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<Assigned<F>, F>, Error> {
+        self.assign_copy(layouter, cell, cell.value().copied(), num_bits)
+    }
+
+    /// Like `assign_from_cell`, but witnessing `value` in the range-check region; only the
+    /// equality constraint ties it to `cell`.
+    fn assign_copy(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<Assigned<F>, F>,
+        value: Value<Assigned<F>>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<Assigned<F>, F>, Error> {
+        assert!(num_bits <= NUM_BITS);
+
+        layouter.assign_region(
+            || "copy value for range check",
+            |mut region| {
+                if num_bits <= RANGE_CHECK_BITS {
+                    self.q_range_check.enable(&mut region, 0)?;
+                } else {
+                    self.q_lookup.enable(&mut region, 0)?;
+                }
+                let copy = region.assign_advice(|| "value", self.value, 0, || value)?;
+                region.constrain_equal(cell.cell(), copy.cell())?;
+
+                Ok(copy)
+            }
+        )
+    }
+
+    /// Checks every one of `values` to be at most `num_bits` bits, one per row of a single region.
+    fn assign_many(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<Assigned<F>>],
+        num_bits: usize,
+    ) -> Result<Vec<AssignedCell<Assigned<F>, F>>, Error> {
+        assert!(num_bits <= NUM_BITS);
+
+        layouter.assign_region(
+            || "assign values for range check",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, value)| {
+                        if num_bits <= RANGE_CHECK_BITS {
+                            self.q_range_check.enable(&mut region, offset)?;
+                        } else {
+                            self.q_lookup.enable(&mut region, offset)?;
+                        }
+                        region.assign_advice(|| format!("value {}", offset), self.value, offset, || *value)
+                    })
+                    .collect()
+            }
+        )
+    }
 }
 
+/// `v_override` simulates a malicious prover: the value actually assigned in place of `v`.
 #[derive(Default)]
-struct MyCircuit<F> {
-    v: F,
+pub(crate) struct MyCircuit<F, const RANGE_CHECK_BITS: usize> {
+    pub(crate) v: F,
+    pub(crate) num_bits: usize,
+    pub(crate) v_override: Option<F>,
 }
 
-impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
-    type Config = RangeCheckConfig<F, 8>;
+impl<F: FieldExt, const RANGE_CHECK_BITS: usize> Circuit<F> for MyCircuit<F, RANGE_CHECK_BITS> {
+    type Config = RangeCheckConfig<F, 8, RANGE_CHECK_BITS>;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        MyCircuit::default()
+        MyCircuit {
+            num_bits: self.num_bits,
+            ..Default::default()
+        }
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -124,9 +197,110 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
 
         config.assign(
             layouter.namespace(|| "assign value"),
-            Value::known(Assigned::from(self.v)),
-            8
+            Value::known(Assigned::from(self.v_override.unwrap_or(self.v))),
+            self.num_bits
+        )?;
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct MyBatchCircuit<F> {
+    values: Vec<F>,
+    num_bits: usize,
+}
+
+impl<F: FieldExt> Circuit<F> for MyBatchCircuit<F> {
+    type Config = RangeCheckConfig<F, 8, 3>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyBatchCircuit {
+            values: vec![F::zero(); self.values.len()],
+            num_bits: self.num_bits,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let v = meta.advice_column();
+        RangeCheckConfig::configure(meta, v)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        config.table.assign(&mut layouter)?;
+
+        let values: Vec<_> = self.values.iter().map(|v| Value::known(Assigned::from(*v))).collect();
+        let cells = config.assign_many(
+            layouter.namespace(|| "assign values"),
+            &values,
+            self.num_bits
         )?;
+        assert_eq!(cells.len(), self.values.len());
+
+        Ok(())
+    }
+}
+
+/// Computes `a + b` in one region, then range-checks the sum in another through a copy.
+/// `corrupt` witnesses the copied sum off by one.
+#[derive(Default)]
+struct MySumCircuit<F> {
+    a: F,
+    b: F,
+    corrupt: bool,
+}
+
+impl<F: FieldExt> Circuit<F> for MySumCircuit<F> {
+    type Config = (RangeCheckConfig<F, 8, 3>, [Column<Advice>; 3], Selector);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MySumCircuit {
+            corrupt: self.corrupt,
+            ..Default::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let v = meta.advice_column();
+        let config = RangeCheckConfig::configure(meta, v);
+
+        let columns = [meta.advice_column(), meta.advice_column(), meta.advice_column()];
+        meta.enable_equality(columns[2]);
+        let q_add = meta.selector();
+
+        meta.create_gate("add", |meta| {
+            let a = meta.query_advice(columns[0], Rotation::cur());
+            let b = meta.query_advice(columns[1], Rotation::cur());
+            let c = meta.query_advice(columns[2], Rotation::cur());
+            let s = meta.query_selector(q_add);
+
+            vec![s * (a + b - c)]
+        });
+
+        (config, columns, q_add)
+    }
+
+    fn synthesize(&self, (config, columns, q_add): Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        config.table.assign(&mut layouter)?;
+
+        let sum = layouter.assign_region(
+            || "add",
+            |mut region| {
+                q_add.enable(&mut region, 0)?;
+                region.assign_advice(|| "a", columns[0], 0, || Value::known(Assigned::from(self.a)))?;
+                region.assign_advice(|| "b", columns[1], 0, || Value::known(Assigned::from(self.b)))?;
+                region.assign_advice(|| "c", columns[2], 0, || Value::known(Assigned::from(self.a + self.b)))
+            }
+        )?;
+
+        if self.corrupt {
+            let value = sum.value().map(|sum| *sum + Assigned::from(F::one()));
+            config.assign_copy(layouter.namespace(|| "range check sum"), &sum, value, 8)?;
+        } else {
+            config.assign_from_cell(layouter.namespace(|| "range check sum"), &sum, 8)?;
+        }
 
         Ok(())
     }
@@ -136,20 +310,172 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
 mod tests {
     use halo2_proofs::dev::MockProver;
     use halo2_proofs::pasta::Fp;
-    use crate::range_check::example2::MyCircuit;
+    use rand_core::RngCore;
+    use crate::prover::DeterministicRng;
+    use crate::range_check::example2::{MyBatchCircuit, MyCircuit, MySumCircuit};
+    use crate::testing::assert_cells_equal_under_mutation;
+    use crate::utils::region_names;
 
     #[test]
     fn test_circuit() {
-        let circuit = MyCircuit {
-            v: Fp::from(55)
+        let circuit = MyCircuit::<_, 3> {
+            v: Fp::from(55),
+            num_bits: 8,
+            v_override: None,
         };
         let prover = MockProver::run(9, &circuit, vec![]).unwrap();
         prover.assert_satisfied();
 
-        let circuit = MyCircuit {
-            v: Fp::from(6)
+        let circuit = MyCircuit::<_, 3> {
+            v: Fp::from(6),
+            num_bits: 3,
+            v_override: None,
         };
         let prover = MockProver::run(9, &circuit, vec![]).unwrap();
         prover.assert_satisfied();
     }
+
+    #[test]
+    fn test_threshold_2() {
+        // 3 bits exceed the threshold, so 6 goes through the lookup
+        let circuit = MyCircuit::<_, 2> {
+            v: Fp::from(6),
+            num_bits: 3,
+            v_override: None,
+        };
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_threshold_5() {
+        // 3 bits are within the threshold, so 6 goes through the gate
+        let circuit = MyCircuit::<_, 5> {
+            v: Fp::from(6),
+            num_bits: 3,
+            v_override: None,
+        };
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // 32 is out of the gate's 5-bit range
+        let circuit = MyCircuit::<_, 5> {
+            v: Fp::from(32),
+            num_bits: 3,
+            v_override: None,
+        };
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_assign_many() {
+        let values: Vec<_> = (0..64).map(|i| Fp::from(i * 3)).collect();
+        let circuit = MyBatchCircuit {
+            values,
+            num_bits: 8,
+        };
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // the whole batch is laid out in a single region
+        let regions = region_names(&circuit);
+        assert_eq!(regions.iter().filter(|name| *name == "assign values for range check").count(), 1);
+
+        // a batch of small values goes through the gate
+        let values: Vec<_> = (0..64).map(|i| Fp::from(i % 8)).collect();
+        let circuit = MyBatchCircuit {
+            values,
+            num_bits: 3,
+        };
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // a single value out of range fails the batch
+        let mut values = vec![Fp::from(1); 64];
+        values[40] = Fp::from(256);
+        let circuit = MyBatchCircuit {
+            values,
+            num_bits: 8,
+        };
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_assign_from_cell() {
+        // the copied sum is bound to the sum computed by the add gate
+        let circuit = |corrupt: Option<usize>| MySumCircuit {
+            a: Fp::from(100),
+            b: Fp::from(55),
+            corrupt: corrupt.is_some(),
+        };
+        assert_cells_equal_under_mutation(9, vec![], circuit, 1);
+
+        // a sum out of range fails, though both summands are in range
+        let circuit = MySumCircuit {
+            a: Fp::from(200),
+            b: Fp::from(100),
+            corrupt: false,
+        };
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_malicious_prover() {
+        // the prover claims 55 but assigns 300, which the lookup rejects
+        let circuit = MyCircuit::<_, 3> {
+            v: Fp::from(55),
+            num_bits: 8,
+            v_override: Some(Fp::from(300)),
+        };
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // and 9, which the 3-bit gate rejects
+        let circuit = MyCircuit::<_, 3> {
+            v: Fp::from(6),
+            num_bits: 3,
+            v_override: Some(Fp::from(9)),
+        };
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_random_values() {
+        // rerun a failure with RANGE_CHECK_SEED set to the seed it reports
+        let seed = std::env::var("RANGE_CHECK_SEED")
+            .map(|seed| seed.parse().expect("RANGE_CHECK_SEED is not a u64"))
+            .unwrap_or_else(|_| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_nanos() as u64
+            });
+        let mut rng = DeterministicRng::new(seed);
+
+        for _ in 0..16 {
+            // values of up to 3 bits go through the gate, larger ones through the lookup
+            let v = rng.next_u64() % (1 << 8);
+            let num_bits = if v < (1 << 3) { 3 } else { 8 };
+            let circuit = MyCircuit::<_, 3> {
+                v: Fp::from(v),
+                num_bits,
+                v_override: None,
+            };
+            let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_ok(), "{} rejected with {} bits, seed {}", v, num_bits, seed);
+
+            let v = (1 << 8) + rng.next_u64() % 1000;
+            let circuit = MyCircuit::<_, 3> {
+                v: Fp::from(v),
+                num_bits: 8,
+                v_override: None,
+            };
+            let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err(), "{} accepted with 8 bits, seed {}", v, seed);
+        }
+    }
 }
@@ -2,6 +2,7 @@ use std::marker::PhantomData;
 use halo2_proofs:: {
     circuit::*,
     plonk::*,
+    poly::Rotation,
     arithmetic::FieldExt,
 };
 
@@ -9,13 +10,13 @@ use halo2_proofs:: {
 /// e.g. NUM_BITS = 8, values = [0, 255]
 
 #[derive(Debug, Clone)]
-pub(super) struct RangeCheckTable<F: FieldExt, const NUM_BITS: usize> {
-    pub(super) value: TableColumn,
-    pub(super) _marker: PhantomData<F>,
+pub(crate) struct RangeCheckTable<F: FieldExt, const NUM_BITS: usize> {
+    pub(crate) value: TableColumn,
+    pub(crate) _marker: PhantomData<F>,
 }
 
 impl<F: FieldExt, const NUM_BITS: usize> RangeCheckTable<F, NUM_BITS> {
-    pub(super) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
         let value = meta.lookup_table_column();
         Self {
             value,
@@ -24,7 +25,7 @@ impl<F: FieldExt, const NUM_BITS: usize> RangeCheckTable<F, NUM_BITS> {
     }
 
 
-    pub(super) fn assign(
+    pub(crate) fn assign(
         &self,
         layouter: &mut impl Layouter<F>
     ) -> Result<(), Error> {
@@ -40,4 +41,201 @@ impl<F: FieldExt, const NUM_BITS: usize> RangeCheckTable<F, NUM_BITS> {
             Ok(())
         })
     }
-}
\ No newline at end of file
+}
+
+/// A denser lookup table of values of NUM_BITS length: a value is split as
+/// `value = high * WIDTH + low`, and `high` and `low` are looked up in two columns of one
+/// table, so its height is the larger of `2^NUM_BITS / WIDTH` and `WIDTH` instead of `2^NUM_BITS`.
+/// e.g. NUM_BITS = 10, WIDTH = 32, both columns hold [0, 31]
+///```txt
+///        high  |  low
+///       --------------
+///         0    |   0
+///         1    |   1
+///         ..   |   ..
+///        H-1   |  W-1
+///```
+/// The shorter column is padded with 0, which is in range for both.
+#[derive(Debug, Clone)]
+pub(crate) struct RangeCheckTable2D<F: FieldExt, const NUM_BITS: usize, const WIDTH: usize> {
+    pub(crate) high: TableColumn,
+    pub(crate) low: TableColumn,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const NUM_BITS: usize, const WIDTH: usize> RangeCheckTable2D<F, NUM_BITS, WIDTH> {
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        assert!(WIDTH.is_power_of_two() && WIDTH <= 1 << NUM_BITS, "WIDTH must be a power of two that divides 2^NUM_BITS");
+
+        Self {
+            high: meta.lookup_table_column(),
+            low: meta.lookup_table_column(),
+            _marker: PhantomData
+        }
+    }
+
+    /// The rows of the table.
+    pub(crate) fn height() -> usize {
+        ((1 << NUM_BITS) / WIDTH).max(WIDTH)
+    }
+
+    /// Looks up `high` and `low` on the rows where `q_lookup` is enabled; together with
+    /// `value = high * WIDTH + low` this range checks `value`.
+    pub(crate) fn lookup(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+        q_lookup: Selector,
+        high: Column<Advice>,
+        low: Column<Advice>,
+    ) {
+        for (column, table) in [(high, self.high), (low, self.low)] {
+            meta.lookup(|meta| {
+                let q_lookup = meta.query_selector(q_lookup);
+                let value = meta.query_advice(column, Rotation::cur());
+
+                vec![
+                    (q_lookup * value, table)
+                ]
+            });
+        }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>
+    ) -> Result<(), Error> {
+        let highs = (1 << NUM_BITS) / WIDTH;
+
+        layouter.assign_table(|| "assign 2d range-check table", |mut table| {
+            for i in 0..Self::height() {
+                let high = if i < highs { i } else { 0 };
+                let low = if i < WIDTH { i } else { 0 };
+                table.assign_cell(|| "high", self.high, i, || Value::known(F::from(high as u64)))?;
+                table.assign_cell(|| "low", self.low, i, || Value::known(F::from(low as u64)))?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Range checks `value` to 10 bits with a `RangeCheckTable`.
+#[derive(Default)]
+struct MyCircuit1D {
+    value: u64,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit1D {
+    type Config = (Column<Advice>, Selector, RangeCheckTable<F, 10>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit1D::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        let q_lookup = meta.complex_selector();
+        let table = RangeCheckTable::configure(meta);
+
+        meta.lookup(|meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let value = meta.query_advice(value, Rotation::cur());
+
+            vec![
+                (q_lookup * value, table.value)
+            ]
+        });
+
+        (value, q_lookup, table)
+    }
+
+    fn synthesize(&self, (value, q_lookup, table): Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        table.assign(&mut layouter)?;
+
+        layouter.assign_region(
+            || "value",
+            |mut region| {
+                q_lookup.enable(&mut region, 0)?;
+                region.assign_advice(|| "value", value, 0, || Value::known(F::from(self.value)))?;
+                Ok(())
+            }
+        )
+    }
+}
+
+/// Range checks `value` to 10 bits with a `RangeCheckTable2D` of width 32.
+#[derive(Default)]
+struct MyCircuit2D {
+    value: u64,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit2D {
+    type Config = ([Column<Advice>; 3], Selector, RangeCheckTable2D<F, 10, 32>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit2D::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let [value, high, low] = [(); 3].map(|_| meta.advice_column());
+        let q_lookup = meta.complex_selector();
+        let table = RangeCheckTable2D::configure(meta);
+
+        meta.create_gate("value = high * WIDTH + low", |meta| {
+            let s = meta.query_selector(q_lookup);
+            let value = meta.query_advice(value, Rotation::cur());
+            let high = meta.query_advice(high, Rotation::cur());
+            let low = meta.query_advice(low, Rotation::cur());
+
+            Constraints::with_selector(s, [value - (high * Expression::Constant(F::from(32u64)) + low)])
+        });
+        table.lookup(meta, q_lookup, high, low);
+
+        ([value, high, low], q_lookup, table)
+    }
+
+    fn synthesize(&self, ([value, high, low], q_lookup, table): Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        table.assign(&mut layouter)?;
+
+        layouter.assign_region(
+            || "value",
+            |mut region| {
+                q_lookup.enable(&mut region, 0)?;
+                region.assign_advice(|| "value", value, 0, || Value::known(F::from(self.value)))?;
+                region.assign_advice(|| "high", high, 0, || Value::known(F::from(self.value / 32)))?;
+                region.assign_advice(|| "low", low, 0, || Value::known(F::from(self.value % 32)))?;
+                Ok(())
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::range_check::example2::table::{MyCircuit1D, MyCircuit2D, RangeCheckTable2D};
+    use crate::utils::minimum_k;
+
+    #[test]
+    fn test_2d_table_rows() {
+        assert_eq!(RangeCheckTable2D::<Fp, 10, 32>::height(), 32);
+        assert_eq!(RangeCheckTable2D::<Fp, 10, 4>::height(), 256);
+
+        // the 1024 rows of the 1d table need 2^11 rows, the 32 of the 2d table 2^6
+        assert_eq!(minimum_k(&MyCircuit1D { value: 1000 }, vec![]), 11);
+        assert_eq!(minimum_k(&MyCircuit2D { value: 1000 }, vec![]), 6);
+    }
+
+    #[test]
+    fn test_2d_table_range() {
+        let prover = MockProver::<Fp>::run(6, &MyCircuit2D { value: 1023 }, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // high = 32 is out of the table
+        let prover = MockProver::<Fp>::run(6, &MyCircuit2D { value: 1024 }, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,180 @@
+/// This helper constrains `output = f(input)` for a function `f` given by its table of
+/// `(input, output)` pairs, generalizing the range-check table to arbitrary functions.
+/// The table is tagged so that a disabled row, which looks up `(0, 0, 0)`, does not make
+/// `(0, 0)` a valid pair of `f`:
+///```txt
+///        input  |  output  |  q_lookup        table:  tag  |  input  |  output
+///       ------------------------------------        ------------------------------
+///          x    |    y     |     1                    0   |    0    |    0
+///                                                     1   |   x_i   |  f(x_i)
+///```
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    arithmetic::FieldExt,
+    poly::Rotation,
+};
+
+/// A lookup table of the pairs `(x, f(x))` of a function over a finite domain.
+#[derive(Debug, Clone)]
+pub(crate) struct FunctionTable<F: FieldExt> {
+    pub(crate) tag: TableColumn,
+    pub(crate) input: TableColumn,
+    pub(crate) output: TableColumn,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> FunctionTable<F> {
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            tag: meta.lookup_table_column(),
+            input: meta.lookup_table_column(),
+            output: meta.lookup_table_column(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        pairs: &[(F, F)],
+    ) -> Result<(), Error> {
+        layouter.assign_table(|| "assign function table", |mut table| {
+            // the row looked up by disabled rows
+            table.assign_cell(|| "tag", self.tag, 0, || Value::known(F::zero()))?;
+            table.assign_cell(|| "input", self.input, 0, || Value::known(F::zero()))?;
+            table.assign_cell(|| "output", self.output, 0, || Value::known(F::zero()))?;
+
+            for (i, (x, y)) in pairs.iter().enumerate() {
+                table.assign_cell(|| "tag", self.tag, i + 1, || Value::known(F::one()))?;
+                table.assign_cell(|| "input", self.input, i + 1, || Value::known(*x))?;
+                table.assign_cell(|| "output", self.output, i + 1, || Value::known(*y))?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FunctionConfig<F: FieldExt> {
+    input: Column<Advice>,
+    output: Column<Advice>,
+    q_lookup: Selector,
+    pub(crate) table: FunctionTable<F>,
+}
+
+impl<F: FieldExt> FunctionConfig<F> {
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        let input = meta.advice_column();
+        let output = meta.advice_column();
+        let q_lookup = meta.complex_selector();
+        let table = FunctionTable::configure(meta);
+
+        meta.enable_equality(input);
+        meta.enable_equality(output);
+
+        meta.lookup(|meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let input = meta.query_advice(input, Rotation::cur());
+            let output = meta.query_advice(output, Rotation::cur());
+
+            vec![
+                (q_lookup.clone(), table.tag),
+                (q_lookup.clone() * input, table.input),
+                (q_lookup * output, table.output),
+            ]
+        });
+
+        Self {
+            input,
+            output,
+            q_lookup,
+            table,
+        }
+    }
+
+    /// Assigns the pair `(input, output)`, returning the cell of `output`.
+    pub(crate) fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input: Value<F>,
+        output: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "function lookup",
+            |mut region| {
+                self.q_lookup.enable(&mut region, 0)?;
+                region.assign_advice(|| "input", self.input, 0, || input)?;
+                region.assign_advice(|| "output", self.output, 0, || output)
+            }
+        )
+    }
+}
+
+/// The modulus of the squaring table of `MyCircuit`.
+const MODULUS: u64 = 17;
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    input: F,
+    output: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = FunctionConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FunctionConfig::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        // x -> x^2 mod 17
+        let pairs: Vec<_> = (0..MODULUS)
+            .map(|x| (F::from(x), F::from(x * x % MODULUS)))
+            .collect();
+        config.table.assign(&mut layouter, &pairs)?;
+
+        config.assign(
+            layouter.namespace(|| "square"),
+            Value::known(self.input),
+            Value::known(self.output)
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::range_check::function_table::MyCircuit;
+
+    #[test]
+    fn test_valid_pair() {
+        // 5^2 = 25 = 8 mod 17
+        let circuit = MyCircuit {
+            input: Fp::from(5),
+            output: Fp::from(8),
+        };
+        let prover = MockProver::run(5, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_invalid_pair() {
+        let circuit = MyCircuit {
+            input: Fp::from(5),
+            output: Fp::from(25),
+        };
+        let prover = MockProver::run(5, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,169 @@
+/// This helper checks that a vector of bits has exactly `K` ones, the selection constraint of
+/// "choose `K` of `n`". Like `popcount`, every entry is constrained boolean and a running
+/// `count` adds them up, which must end at `K`:
+///```txt
+///        bit   |  count                |  q_first  |  q_rest  |  q_last
+///       -------------------------------------------------------------------
+///        b_0   |  b_0                  |     1     |    0     |    0
+///        b_1   |  count_prev + b_1     |     0     |    1     |    0
+///         ..   |   ..                  |     0     |    1     |    0
+///        b_n   |  sum(b_i) = K         |     0     |    1     |    1
+///```
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct KHotConfig {
+    bit: Column<Advice>,
+    count: Column<Advice>,
+    q_first: Selector,
+    q_rest: Selector,
+    q_last: Selector,
+}
+
+pub(crate) struct KHotChip<F: FieldExt, const K: u64> {
+    config: KHotConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const K: u64> KHotChip<F, K> {
+    pub(crate) fn construct(config: KHotConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> KHotConfig {
+        let [bit, count] = [(); 2].map(|_| meta.advice_column());
+        let q_first = meta.selector();
+        let q_rest = meta.selector();
+        let q_last = meta.selector();
+
+        meta.enable_equality(bit);
+
+        meta.create_gate("count", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let q_rest = meta.query_selector(q_rest);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let count = meta.query_advice(count, Rotation::cur());
+            let count_prev = meta.query_advice(count, Rotation::prev());
+
+            vec![
+                (q_first.clone() + q_rest.clone()) * bit.clone() * (Expression::Constant(F::one()) - bit.clone()),
+                q_first * (count.clone() - bit.clone()),
+                q_rest * (count - (count_prev + bit)),
+            ]
+        });
+
+        meta.create_gate("exactly K", |meta| {
+            let s = meta.query_selector(q_last);
+            let count = meta.query_advice(count, Rotation::cur());
+
+            Constraints::with_selector(s, [("sum(b_i) = K", count - Expression::Constant(F::from(K)))])
+        });
+
+        KHotConfig {
+            bit,
+            count,
+            q_first,
+            q_rest,
+            q_last,
+        }
+    }
+
+    /// Assigns `bits`, returning their cells.
+    pub(crate) fn assign(&self, mut layouter: impl Layouter<F>, bits: &[Value<F>]) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert!(!bits.is_empty());
+
+        layouter.assign_region(
+            || "k hot",
+            |mut region| {
+                let mut count = Value::known(F::zero());
+                let mut cells = vec![];
+                for (row, bit) in bits.iter().enumerate() {
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_rest.enable(&mut region, row)?;
+                    }
+
+                    cells.push(region.assign_advice(|| "bit", self.config.bit, row, || *bit)?);
+
+                    count = count.zip(*bit).map(|(count, bit)| count + bit);
+                    region.assign_advice(|| "count", self.config.count, row, || count)?;
+                }
+
+                self.config.q_last.enable(&mut region, bits.len() - 1)?;
+
+                Ok(cells)
+            }
+        )
+    }
+}
+
+/// Checks `bits` has exactly 2 ones.
+#[derive(Default)]
+struct MyCircuit<F> {
+    bits: Vec<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = KHotConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            bits: vec![F::zero(); self.bits.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        KHotChip::<F, 2>::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = KHotChip::<F, 2>::construct(config);
+        let bits = self.bits.iter().map(|bit| Value::known(*bit)).collect::<Vec<_>>();
+        chip.assign(layouter.namespace(|| "k hot"), &bits)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::range_check::k_hot::MyCircuit;
+
+    fn circuit(bits: &[u64]) -> MyCircuit<Fp> {
+        MyCircuit {
+            bits: bits.iter().map(|bit| Fp::from(*bit)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_exactly_k() {
+        let prover = MockProver::run(4, &circuit(&[0, 1, 0, 0, 1, 0]), vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_too_many_ones() {
+        let prover = MockProver::run(4, &circuit(&[0, 1, 1, 0, 1, 0]), vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_non_boolean() {
+        // a single 2 sums to K as well
+        let prover = MockProver::run(4, &circuit(&[0, 0, 2, 0, 0, 0]), vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,254 @@
+/// This helper checks whether `lhs < rhs` for values of `NUM_BYTES` bytes, outputting a boolean.
+/// `lhs - rhs + 2^(8 * NUM_BYTES)` lies in `[1, 2^(8 * NUM_BYTES + 1))`, and its top bit is
+/// set exactly when `lhs >= rhs`. The lower bytes are range checked against the lookup table.
+///```txt
+///        lhs  |  rhs  |  byte_0  |  ..  |  byte_{n-1}  |  lt  |  q_lt
+///       -------------------------------------------------------------------
+///         l   |   r   |    ..    |  ..  |      ..      |  lt  |   1
+///```
+/// The caller is responsible for `lhs` and `rhs` being less than `2^(8 * NUM_BYTES)`, which
+/// `range_check` constrains.
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    arithmetic::FieldExt,
+    poly::Rotation,
+};
+use crate::range_check::example2::table::RangeCheckTable;
+
+#[derive(Debug, Clone)]
+pub(crate) struct LessThanConfig<F: FieldExt, const NUM_BYTES: usize> {
+    lhs: Column<Advice>,
+    rhs: Column<Advice>,
+    bytes: [Column<Advice>; NUM_BYTES],
+    lt: Column<Advice>,
+    q_lt: Selector,
+    table: RangeCheckTable<F, 8>,
+}
+
+pub(crate) struct LessThanChip<F: FieldExt, const NUM_BYTES: usize> {
+    config: LessThanConfig<F, NUM_BYTES>,
+}
+
+impl<F: FieldExt, const NUM_BYTES: usize> LessThanChip<F, NUM_BYTES> {
+    pub(crate) fn construct(config: LessThanConfig<F, NUM_BYTES>) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> LessThanConfig<F, NUM_BYTES> {
+        let lhs = meta.advice_column();
+        let rhs = meta.advice_column();
+        let bytes = [(); NUM_BYTES].map(|_| meta.advice_column());
+        let lt = meta.advice_column();
+        let q_lt = meta.complex_selector();
+        let table = RangeCheckTable::configure(meta);
+
+        meta.enable_equality(lhs);
+        meta.enable_equality(rhs);
+        meta.enable_equality(lt);
+
+        // lhs - rhs + 2^(8n) = sum(byte_i * 2^(8i)) + (1 - lt) * 2^(8n)
+        meta.create_gate("less than", |meta| {
+            let s = meta.query_selector(q_lt);
+            let lhs = meta.query_advice(lhs, Rotation::cur());
+            let rhs = meta.query_advice(rhs, Rotation::cur());
+            let lt = meta.query_advice(lt, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            let range = Expression::Constant(F::from(2u64).pow_vartime(&[8 * NUM_BYTES as u64]));
+
+            let sum = bytes.iter().rev().fold(Expression::Constant(F::zero()), |acc, byte| {
+                acc * Expression::Constant(F::from(1u64 << 8)) + meta.query_advice(*byte, Rotation::cur())
+            });
+
+            Constraints::with_selector(s, [
+                ("lt is boolean", lt.clone() * (one.clone() - lt.clone())),
+                ("decompose lhs - rhs + 2^(8n)", lhs - rhs + range.clone() - sum - (one - lt) * range),
+            ])
+        });
+
+        // every byte is in [0, 2^8)
+        for byte in bytes {
+            meta.lookup(|meta| {
+                let s = meta.query_selector(q_lt);
+                let byte = meta.query_advice(byte, Rotation::cur());
+
+                vec![
+                    (s * byte, table.value)
+                ]
+            });
+        }
+
+        LessThanConfig {
+            lhs,
+            rhs,
+            bytes,
+            lt,
+            q_lt,
+            table,
+        }
+    }
+
+    pub(crate) fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.table.assign(layouter)
+    }
+
+    /// Returns the cell holding 1 if `lhs < rhs`, otherwise 0.
+    pub(crate) fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lhs: Value<u64>,
+        rhs: Value<u64>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "less than",
+            |mut region| {
+                self.config.q_lt.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "lhs", self.config.lhs, 0, || lhs.map(F::from))?;
+                region.assign_advice(|| "rhs", self.config.rhs, 0, || rhs.map(F::from))?;
+
+                self.assign_lt(&mut region, lhs, rhs)
+            }
+        )
+    }
+
+    /// Like `assign`, but with `lhs` and `rhs` copied from existing cells.
+    pub(crate) fn assign_from_cells(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "less than",
+            |mut region| {
+                self.config.q_lt.enable(&mut region, 0)?;
+
+                let lhs = lhs.copy_advice(|| "lhs", &mut region, self.config.lhs, 0)?;
+                let rhs = rhs.copy_advice(|| "rhs", &mut region, self.config.rhs, 0)?;
+
+                self.assign_lt(
+                    &mut region,
+                    lhs.value().map(|lhs| lhs.get_lower_128() as u64),
+                    rhs.value().map(|rhs| rhs.get_lower_128() as u64),
+                )
+            }
+        )
+    }
+
+    /// Constrains `cell < 2^(8n)` by a row checking `cell < 0` with `lt` pinned to 0: then
+    /// `cell + 2^(8n)` must equal the bytes plus `2^(8n)`. The constraint system needs a fixed
+    /// column with constants enabled.
+    pub(crate) fn range_check(&self, mut layouter: impl Layouter<F>, cell: &AssignedCell<F, F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "range check",
+            |mut region| {
+                self.config.q_lt.enable(&mut region, 0)?;
+
+                let lhs = cell.copy_advice(|| "lhs", &mut region, self.config.lhs, 0)?;
+                region.assign_advice_from_constant(|| "rhs", self.config.rhs, 0, F::zero())?;
+
+                let lt = self.assign_lt(
+                    &mut region,
+                    lhs.value().map(|lhs| lhs.get_lower_128() as u64),
+                    Value::known(0),
+                )?;
+                region.constrain_constant(lt.cell(), F::zero())
+            }
+        )
+    }
+
+    /// Assigns the bytes of `lhs - rhs + 2^(8n)` and the `lt` flag on the region's first row.
+    fn assign_lt(&self, region: &mut Region<'_, F>, lhs: Value<u64>, rhs: Value<u64>)
+        -> Result<AssignedCell<F, F>, Error> {
+        // the low 8n bits of lhs - rhs + 2^(8n)
+        let diff = lhs.zip(rhs).map(|(lhs, rhs)| lhs.wrapping_sub(rhs));
+        for (i, byte) in self.config.bytes.iter().enumerate() {
+            region.assign_advice(
+                || format!("byte {}", i),
+                *byte,
+                0,
+                || diff.map(|diff| F::from(((diff >> (8 * i)) & 0xff) as u64))
+            )?;
+        }
+
+        let lt = lhs.zip(rhs).map(|(lhs, rhs)| if lhs < rhs { F::one() } else { F::zero() });
+        region.assign_advice(|| "lt", self.config.lt, 0, || lt)
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit {
+    lhs: u64,
+    rhs: u64,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit {
+    type Config = (LessThanConfig<F, 4>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (LessThanChip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = LessThanChip::construct(config.0);
+        chip.load_table(&mut layouter)?;
+
+        let lt = chip.assign(
+            layouter.namespace(|| "lhs < rhs"),
+            Value::known(self.lhs),
+            Value::known(self.rhs),
+        )?;
+
+        layouter.constrain_instance(lt.cell(), config.1, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::range_check::less_than::MyCircuit;
+
+    #[test]
+    fn test_less_than() {
+        let circuit = MyCircuit {
+            lhs: 3,
+            rhs: 5,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::one()]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_greater_than() {
+        let circuit = MyCircuit {
+            lhs: 5,
+            rhs: 3,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::zero()]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_equal() {
+        let circuit = MyCircuit {
+            lhs: 5,
+            rhs: 5,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::zero()]]).unwrap();
+        prover.assert_satisfied();
+    }
+}
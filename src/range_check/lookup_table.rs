@@ -0,0 +1,130 @@
+/// This helper builds a single-column lookup table from a function over a range of integers,
+/// so that a table-based gadget need not write its own assignment loop. Several tables built
+/// over the same domain line up row by row, and can be looked up together:
+///```txt
+///        x  |  y  |  q_lookup        table:  i   |  i * i
+///       ---------------------------        ----------------
+///        3  |  9  |     1                    0   |    0
+///                                            1   |    1
+///                                            ..  |    ..
+///```
+use std::ops::Range;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    arithmetic::FieldExt,
+    poly::Rotation,
+};
+
+/// A table column holding `f(i)` at row `i - domain.start`, for every `i` in the domain.
+#[derive(Debug, Clone)]
+pub(crate) struct LookupTableBuilder<F: FieldExt> {
+    pub(crate) column: TableColumn,
+    values: Vec<F>,
+}
+
+impl<F: FieldExt> LookupTableBuilder<F> {
+    /// Allocates the table column and evaluates `f` over `domain`; `assign` fills the column in.
+    pub(crate) fn from_fn(meta: &mut ConstraintSystem<F>, domain: Range<u64>, f: impl Fn(u64) -> F) -> Self {
+        Self {
+            column: meta.lookup_table_column(),
+            values: domain.map(f).collect(),
+        }
+    }
+
+    pub(crate) fn assign(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(|| "assign lookup table", |mut table| {
+            for (offset, value) in self.values.iter().enumerate() {
+                table.assign_cell(|| "value", self.column, offset, || Value::known(*value))?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Looks up `(x, y)` in the tables of `i` and `i * i` over `[0, 16)`.
+#[derive(Default)]
+struct MyCircuit<F> {
+    x: F,
+    y: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (Column<Advice>, Column<Advice>, Selector, LookupTableBuilder<F>, LookupTableBuilder<F>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let x = meta.advice_column();
+        let y = meta.advice_column();
+        let q_lookup = meta.complex_selector();
+        let input = LookupTableBuilder::from_fn(meta, 0..16, F::from);
+        let square = LookupTableBuilder::from_fn(meta, 0..16, |i| F::from(i * i));
+
+        // disabled rows look up (0, 0), which is the first row of the tables
+        meta.lookup(|meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let x = meta.query_advice(x, Rotation::cur());
+            let y = meta.query_advice(y, Rotation::cur());
+
+            vec![
+                (q_lookup.clone() * x, input.column),
+                (q_lookup * y, square.column),
+            ]
+        });
+
+        (x, y, q_lookup, input, square)
+    }
+
+    fn synthesize(&self, (x, y, q_lookup, input, square): Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        input.assign(&mut layouter)?;
+        square.assign(&mut layouter)?;
+
+        layouter.assign_region(
+            || "y = x * x",
+            |mut region| {
+                q_lookup.enable(&mut region, 0)?;
+                region.assign_advice(|| "x", x, 0, || Value::known(self.x))?;
+                region.assign_advice(|| "y", y, 0, || Value::known(self.y))?;
+
+                Ok(())
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::range_check::lookup_table::MyCircuit;
+
+    #[test]
+    fn test_square_table() {
+        let circuit = MyCircuit {
+            x: Fp::from(3),
+            y: Fp::from(9),
+        };
+        let prover = MockProver::run(5, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        let circuit = MyCircuit {
+            x: Fp::from(3),
+            y: Fp::from(10),
+        };
+        let prover = MockProver::run(5, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 16 is outside the domain of the table
+        let circuit = MyCircuit {
+            x: Fp::from(16),
+            y: Fp::from(256),
+        };
+        let prover = MockProver::run(5, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
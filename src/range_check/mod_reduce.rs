@@ -0,0 +1,199 @@
+/// This helper reduces a value modulo a small `MODULUS`, witnessing the quotient and remainder:
+///```txt
+///        value  |  q  |  r  |  q_reduce
+///       -------------------------------------
+///          v    |  q  |  r  |     1
+///```
+/// The gate checks `v = q * MODULUS + r` and `r < MODULUS`, the latter as the product
+/// `r * (1 - r) * .. * (MODULUS - 1 - r)`, so its degree grows with `MODULUS`. Over a field
+/// any `r` has some `q` satisfying the first check, so `q` is also looked up in a table of
+/// 8-bit values: the value must be less than `MODULUS * 2^8`.
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    arithmetic::FieldExt,
+    poly::Rotation,
+};
+use crate::range_check::example2::table::RangeCheckTable;
+
+#[derive(Debug, Clone)]
+pub(crate) struct ModReduceConfig<F: FieldExt> {
+    value: Column<Advice>,
+    q: Column<Advice>,
+    r: Column<Advice>,
+    q_reduce: Selector,
+    table: RangeCheckTable<F, 8>,
+}
+
+pub(crate) struct ModReduceChip<F: FieldExt, const MODULUS: u64> {
+    config: ModReduceConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const MODULUS: u64> ModReduceChip<F, MODULUS> {
+    pub(crate) fn construct(config: ModReduceConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> ModReduceConfig<F> {
+        let [value, q, r] = [(); 3].map(|_| meta.advice_column());
+        let q_reduce = meta.complex_selector();
+        let table = RangeCheckTable::configure(meta);
+
+        meta.enable_equality(value);
+        meta.enable_equality(r);
+
+        meta.create_gate("mod reduce", |meta| {
+            let s = meta.query_selector(q_reduce);
+            let value = meta.query_advice(value, Rotation::cur());
+            let q = meta.query_advice(q, Rotation::cur());
+            let r = meta.query_advice(r, Rotation::cur());
+
+            let range_check = (1..MODULUS).fold(r.clone(), |expr, i| {
+                expr * (Expression::Constant(F::from(i)) - r.clone())
+            });
+
+            Constraints::with_selector(s, [
+                ("value = q * MODULUS + r", value - (q * Expression::Constant(F::from(MODULUS)) + r)),
+                ("r < MODULUS", range_check),
+            ])
+        });
+
+        // q is in [0, 2^8)
+        meta.lookup(|meta| {
+            let s = meta.query_selector(q_reduce);
+            let q = meta.query_advice(q, Rotation::cur());
+
+            vec![
+                (s * q, table.value)
+            ]
+        });
+
+        ModReduceConfig {
+            value,
+            q,
+            r,
+            q_reduce,
+            table,
+        }
+    }
+
+    pub(crate) fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.table.assign(layouter)
+    }
+
+    /// Returns the cell of `value mod MODULUS`.
+    pub(crate) fn assign(&self, layouter: impl Layouter<F>, value: Value<u64>) -> Result<AssignedCell<F, F>, Error> {
+        self.assign_values(
+            layouter,
+            value,
+            value.map(|value| value / MODULUS),
+            value.map(|value| value % MODULUS),
+        )
+    }
+
+    /// `assign` with the quotient and remainder witnessed as `q` and `r`.
+    fn assign_values(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<u64>,
+        q: Value<u64>,
+        r: Value<u64>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "mod reduce",
+            |mut region| {
+                self.config.q_reduce.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "value", self.config.value, 0, || value.map(F::from))?;
+                region.assign_advice(|| "q", self.config.q, 0, || q.map(F::from))?;
+                region.assign_advice(|| "r", self.config.r, 0, || r.map(F::from))
+            }
+        )
+    }
+}
+
+/// Reduces `value` modulo 5, exposing the remainder at instance row 0.
+/// `quotient_remainder` overrides the honest `(q, r)`.
+#[derive(Default)]
+struct MyCircuit {
+    value: u64,
+    quotient_remainder: Option<(u64, u64)>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit {
+    type Config = (ModReduceConfig<F>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (ModReduceChip::<F, 5>::configure(meta), instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = ModReduceChip::<F, 5>::construct(config.0);
+        chip.load_table(&mut layouter)?;
+
+        let r = match self.quotient_remainder {
+            None => chip.assign(layouter.namespace(|| "value mod 5"), Value::known(self.value))?,
+            Some((q, r)) => chip.assign_values(
+                layouter.namespace(|| "value mod 5"),
+                Value::known(self.value),
+                Value::known(q),
+                Value::known(r),
+            )?,
+        };
+
+        layouter.constrain_instance(r.cell(), config.1, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::range_check::mod_reduce::MyCircuit;
+
+    #[test]
+    fn test_mod_reduce() {
+        for (value, r) in [(17, 2), (10, 0), (4, 4), (1279, 4)] {
+            let circuit = MyCircuit {
+                value,
+                quotient_remainder: None,
+            };
+            let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(r)]]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_remainder_out_of_range() {
+        // 17 = 2 * 5 + 7, but 7 is not a remainder mod 5
+        let circuit = MyCircuit {
+            value: 17,
+            quotient_remainder: Some((2, 7)),
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(7)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_quotient_out_of_range() {
+        // 1501 = 300 * 5 + 1, and 300 does not fit in the 8-bit quotient
+        let circuit = MyCircuit {
+            value: 1501,
+            quotient_remainder: None,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(1)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,137 @@
+/// This helper checks that the value witnessed in a given cell is one of a fixed set
+/// `{s_0, ..., s_n}`, generalizing the range check of `example1` from `[0, RANGE)` to an
+/// arbitrary set:
+///```txt
+///        value   |   q_one_of
+///       ----------------------
+///          v     |      1
+///```
+/// The gate constrains `prod(v - s_i) = 0`, so its degree grows with the size of the set;
+/// large sets are better served by a lookup.
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct OneOfConfig<F: FieldExt> {
+    value: Column<Advice>,
+    q_one_of: Selector,
+    set: Vec<F>,
+}
+
+pub(crate) struct OneOfChip<F: FieldExt> {
+    config: OneOfConfig<F>,
+}
+
+impl<F: FieldExt> OneOfChip<F> {
+    pub(crate) fn construct(config: OneOfConfig<F>) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>, set: &[F]) -> OneOfConfig<F> {
+        assert!(!set.is_empty(), "the set is not empty");
+
+        let value = meta.advice_column();
+        let q_one_of = meta.selector();
+
+        meta.enable_equality(value);
+
+        meta.create_gate("one of", |meta| {
+            let v = meta.query_advice(value, Rotation::cur());
+            let s = meta.query_selector(q_one_of);
+
+            let product = set.iter().fold(Expression::Constant(F::one()), |expr, s_i| {
+                expr * (v.clone() - Expression::Constant(*s_i))
+            });
+
+            Constraints::with_selector(s, [("value is in the set", product)])
+        });
+
+        OneOfConfig {
+            value,
+            q_one_of,
+            set: set.to_vec(),
+        }
+    }
+
+    /// Returns the cell of the checked value.
+    pub(crate) fn assign(&self, mut layouter: impl Layouter<F>, value: Value<F>) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "assign value",
+            |mut region| {
+                self.config.q_one_of.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "value", self.config.value, 0, || value)
+            }
+        )
+    }
+}
+
+/// The set `MyCircuit` checks its value against.
+const SET: [u64; 3] = [1, 3, 5];
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    value: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = OneOfConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        OneOfChip::configure(meta, &SET.map(F::from))
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = OneOfChip::construct(config);
+        chip.assign(layouter, Value::known(self.value))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+    use crate::range_check::one_of::{MyCircuit, SET};
+
+    #[test]
+    fn test_member() {
+        let circuit = MyCircuit {
+            value: Fp::from(3),
+        };
+        let prover = MockProver::run(4, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_non_member() {
+        let circuit = MyCircuit {
+            value: Fp::from(4),
+        };
+        let prover = MockProver::run(4, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_degree() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        MyCircuit::<Fp>::configure(&mut meta);
+
+        // one factor per element of the set, and one more for the selector
+        let degree = meta.gates()[0].polynomials()[0].degree();
+        assert_eq!(degree - 1, SET.len());
+    }
+}
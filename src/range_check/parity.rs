@@ -0,0 +1,196 @@
+/// This helper proves the parity of a value by decomposing it as `value = 2 * q + lsb`,
+/// with `lsb` boolean and `q` looked up in an 8-bit range-check table, so the value is
+/// range checked to 9 bits and `lsb` is its least significant bit:
+///```txt
+///        value  |  q   |  lsb  |  q_parity
+///       ------------------------------------
+///          v    |  q   |   b   |     1
+///```
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+use crate::range_check::example2::table::RangeCheckTable;
+
+#[derive(Debug, Clone)]
+pub(crate) struct ParityConfig<F: FieldExt> {
+    value: Column<Advice>,
+    q: Column<Advice>,
+    lsb: Column<Advice>,
+    q_parity: Selector,
+    table: RangeCheckTable<F, 8>,
+}
+
+pub(crate) struct ParityChip<F: FieldExt> {
+    config: ParityConfig<F>,
+}
+
+impl<F: FieldExt> ParityChip<F> {
+    pub(crate) fn construct(config: ParityConfig<F>) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> ParityConfig<F> {
+        let [value, q, lsb] = [(); 3].map(|_| meta.advice_column());
+        let q_parity = meta.complex_selector();
+        let table = RangeCheckTable::configure(meta);
+
+        meta.enable_equality(value);
+        meta.enable_equality(lsb);
+
+        meta.create_gate("parity", |meta| {
+            let s = meta.query_selector(q_parity);
+            let value = meta.query_advice(value, Rotation::cur());
+            let q = meta.query_advice(q, Rotation::cur());
+            let lsb = meta.query_advice(lsb, Rotation::cur());
+
+            Constraints::with_selector(s, [
+                ("lsb is boolean", lsb.clone() * (Expression::Constant(F::one()) - lsb.clone())),
+                ("value = 2 * q + lsb", value - (q * Expression::Constant(F::from(2u64)) + lsb)),
+            ])
+        });
+
+        meta.lookup(|meta| {
+            let s = meta.query_selector(q_parity);
+            let q = meta.query_advice(q, Rotation::cur());
+
+            vec![
+                (s * q, table.value)
+            ]
+        });
+
+        ParityConfig {
+            value,
+            q,
+            lsb,
+            q_parity,
+            table,
+        }
+    }
+
+    pub(crate) fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.table.assign(layouter)
+    }
+
+    /// Returns the cell of the least significant bit of `value`.
+    pub(crate) fn assign(&self, layouter: impl Layouter<F>, value: Value<F>) -> Result<AssignedCell<F, F>, Error> {
+        let q = value.map(|value| F::from_u128(value.get_lower_128() >> 1));
+        let lsb = value.map(|value| F::from_u128(value.get_lower_128() & 1));
+        self.assign_values(layouter, value, q, lsb)
+    }
+
+    /// `assign` with the decomposition witnessed as `q` and `lsb`.
+    fn assign_values(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+        q: Value<F>,
+        lsb: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "parity",
+            |mut region| {
+                self.config.q_parity.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "value", self.config.value, 0, || value)?;
+                region.assign_advice(|| "q", self.config.q, 0, || q)?;
+                region.assign_advice(|| "lsb", self.config.lsb, 0, || lsb)
+            }
+        )
+    }
+}
+
+/// Exposes the least significant bit of `value` at instance row 0.
+/// `decomposition`, as `(q, lsb)`, overrides the honest one.
+#[derive(Default)]
+struct MyCircuit<F> {
+    value: F,
+    decomposition: Option<(F, F)>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (ParityConfig<F>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (ParityChip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = ParityChip::construct(config.0);
+        chip.load_table(&mut layouter)?;
+
+        let value = Value::known(self.value);
+        let lsb = match self.decomposition {
+            None => chip.assign(layouter.namespace(|| "parity"), value)?,
+            Some((q, lsb)) => chip.assign_values(
+                layouter.namespace(|| "parity"),
+                value,
+                Value::known(q),
+                Value::known(lsb)
+            )?,
+        };
+
+        layouter.constrain_instance(lsb.cell(), config.1, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::pasta::group::ff::Field;
+    use crate::range_check::parity::MyCircuit;
+
+    #[test]
+    fn test_even() {
+        let circuit = MyCircuit {
+            value: Fp::from(200),
+            decomposition: None,
+        };
+        let prover = MockProver::run(9, &circuit, vec![vec![Fp::zero()]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(9, &circuit, vec![vec![Fp::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_odd() {
+        let circuit = MyCircuit {
+            value: Fp::from(511),
+            decomposition: None,
+        };
+        let prover = MockProver::run(9, &circuit, vec![vec![Fp::one()]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_decomposition_binds() {
+        // claiming 7 is even: 2 * 3 + 0 does not rebuild 7
+        let circuit = MyCircuit {
+            value: Fp::from(7),
+            decomposition: Some((Fp::from(3), Fp::zero())),
+        };
+        let prover = MockProver::run(9, &circuit, vec![vec![Fp::zero()]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 7 = 2 * (7 / 2) + 0 in the field, but 7 / 2 is not in the table
+        let circuit = MyCircuit {
+            value: Fp::from(7),
+            decomposition: Some((Fp::from(7) * Fp::from(2).invert().unwrap(), Fp::zero())),
+        };
+        let prover = MockProver::run(9, &circuit, vec![vec![Fp::zero()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
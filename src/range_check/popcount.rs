@@ -0,0 +1,213 @@
+/// This helper counts the set bits of a value of `NUM_BITS` bits. The value is decomposed
+/// into boolean bits from the most significant one down, doubling a running sum `acc` and
+/// adding the bits up in `count`; `pow2` builds on it:
+///```txt
+///        bit        |  acc                     |  count                  |  value  |  q_first  |  q_rest  |  q_last
+///       -----------------------------------------------------------------------------------------------------------------
+///        b_{n-1}    |  b_{n-1}                 |  b_{n-1}                |         |     1     |    0     |    0
+///        b_{n-2}    |  2 * acc_prev + b_{n-2}  |  count_prev + b_{n-2}   |         |     0     |    1     |    0
+///         ..        |   ..                     |   ..                    |         |     0     |    1     |    0
+///        b_0        |  sum(b_i * 2^i)          |  sum(b_i)               |    v    |     0     |    1     |    1
+///```
+/// On the last row `acc` must equal the value, and `count` holds its popcount.
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PopcountConfig {
+    bit: Column<Advice>,
+    acc: Column<Advice>,
+    count: Column<Advice>,
+    value: Column<Advice>,
+    q_first: Selector,
+    q_rest: Selector,
+    q_last: Selector,
+}
+
+pub(crate) struct PopcountChip<F: FieldExt, const NUM_BITS: usize> {
+    config: PopcountConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const NUM_BITS: usize> PopcountChip<F, NUM_BITS> {
+    pub(crate) fn construct(config: PopcountConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> PopcountConfig {
+        let [bit, acc, count, value] = [(); 4].map(|_| meta.advice_column());
+        let q_first = meta.selector();
+        let q_rest = meta.selector();
+        let q_last = meta.selector();
+
+        meta.enable_equality(count);
+        meta.enable_equality(value);
+
+        meta.create_gate("decompose", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let q_rest = meta.query_selector(q_rest);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let count = meta.query_advice(count, Rotation::cur());
+            let count_prev = meta.query_advice(count, Rotation::prev());
+            let two = Expression::Constant(F::from(2u64));
+
+            vec![
+                (q_first.clone() + q_rest.clone()) * bit.clone() * (Expression::Constant(F::one()) - bit.clone()),
+                q_first.clone() * (acc.clone() - bit.clone()),
+                q_first * (count.clone() - bit.clone()),
+                q_rest.clone() * (acc - (acc_prev * two + bit.clone())),
+                q_rest * (count - (count_prev + bit)),
+            ]
+        });
+
+        meta.create_gate("recompose", |meta| {
+            let s = meta.query_selector(q_last);
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let value = meta.query_advice(value, Rotation::cur());
+
+            Constraints::with_selector(s, [("value = sum(b_i * 2^i)", value - acc)])
+        });
+
+        PopcountConfig {
+            bit,
+            acc,
+            count,
+            value,
+            q_first,
+            q_rest,
+            q_last,
+        }
+    }
+
+    /// Decomposes `value` into `NUM_BITS` bits and returns the cell of the number of set bits.
+    pub(crate) fn assign(&self, layouter: impl Layouter<F>, value: Value<F>) -> Result<AssignedCell<F, F>, Error> {
+        let bits = (0..NUM_BITS)
+            .rev()
+            .map(|i| value.map(|v| F::from(((v.get_lower_128() >> i) & 1) as u64)))
+            .collect::<Vec<_>>();
+        self.assign_bits(layouter, value, &bits)
+    }
+
+    /// `assign` with the decomposition of `value` witnessed as `bits`, most significant first.
+    fn assign_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+        bits: &[Value<F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(NUM_BITS > 0 && NUM_BITS <= 128);
+        assert_eq!(bits.len(), NUM_BITS);
+
+        layouter.assign_region(
+            || "popcount",
+            |mut region| {
+                let mut acc = Value::known(F::zero());
+                let mut count = Value::known(F::zero());
+                let mut cell = None;
+                for (row, bit) in bits.iter().enumerate() {
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_rest.enable(&mut region, row)?;
+                    }
+
+                    region.assign_advice(|| "bit", self.config.bit, row, || *bit)?;
+
+                    acc = acc.zip(*bit).map(|(acc, bit)| acc.double() + bit);
+                    count = count.zip(*bit).map(|(count, bit)| count + bit);
+                    region.assign_advice(|| "acc", self.config.acc, row, || acc)?;
+                    cell = Some(region.assign_advice(|| "count", self.config.count, row, || count)?);
+                }
+
+                let last = NUM_BITS - 1;
+                self.config.q_last.enable(&mut region, last)?;
+                region.assign_advice(|| "value", self.config.value, last, || value)?;
+
+                Ok(cell.unwrap())
+            }
+        )
+    }
+}
+
+/// Counts the set bits of an 8-bit `value`, exposing the count at instance row 0.
+/// `bits`, most significant first, overrides the honest decomposition.
+#[derive(Default)]
+struct MyCircuit<F> {
+    value: F,
+    bits: Option<Vec<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = (PopcountConfig, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        (PopcountChip::<F, 8>::configure(meta), instance)
+    }
+
+    fn synthesize(&self, (config, instance): Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = PopcountChip::<F, 8>::construct(config);
+
+        let count = match &self.bits {
+            None => chip.assign(layouter.namespace(|| "popcount"), Value::known(self.value))?,
+            Some(bits) => {
+                let bits = bits.iter().map(|bit| Value::known(*bit)).collect::<Vec<_>>();
+                chip.assign_bits(layouter.namespace(|| "popcount"), Value::known(self.value), &bits)?
+            }
+        };
+
+        layouter.constrain_instance(count.cell(), instance, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::range_check::popcount::MyCircuit;
+
+    #[test]
+    fn test_popcount() {
+        for (value, count) in [(7u64, 3u64), (0, 0), (255, 8), (0b1010_0100, 3)] {
+            let circuit = MyCircuit {
+                value: Fp::from(value),
+                bits: None,
+            };
+            let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(count)]]).unwrap();
+            prover.assert_satisfied();
+
+            let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(count + 1)]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_non_boolean_bit() {
+        // 2 recomposes to 2 from a lowest "bit" of 2, which would count 2 set bits
+        let mut bits = vec![Fp::zero(); 8];
+        bits[7] = Fp::from(2);
+        let circuit = MyCircuit {
+            value: Fp::from(2),
+            bits: Some(bits),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(2)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
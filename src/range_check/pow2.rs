@@ -0,0 +1,103 @@
+/// This helper constrains a value to be a power of two, i.e. to have exactly one bit set.
+/// The value is decomposed into `NUM_BITS` bits by `PopcountChip`, which counts its set
+/// bits, and the count is constrained to the constant 1.
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    arithmetic::FieldExt,
+};
+use crate::range_check::popcount::{PopcountChip, PopcountConfig};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct IsPowerOfTwoConfig {
+    popcount: PopcountConfig,
+}
+
+pub(crate) struct IsPowerOfTwoChip<F: FieldExt, const NUM_BITS: usize> {
+    config: IsPowerOfTwoConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const NUM_BITS: usize> IsPowerOfTwoChip<F, NUM_BITS> {
+    pub(crate) fn construct(config: IsPowerOfTwoConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> IsPowerOfTwoConfig {
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+
+        IsPowerOfTwoConfig {
+            popcount: PopcountChip::<F, NUM_BITS>::configure(meta),
+        }
+    }
+
+    /// Constrains `value`, of at most `NUM_BITS` bits, to be a power of two and returns
+    /// the cell counting its set bits, which is 1 in any satisfied circuit.
+    pub(crate) fn assign(&self, mut layouter: impl Layouter<F>, value: Value<F>) -> Result<AssignedCell<F, F>, Error> {
+        let chip = PopcountChip::<F, NUM_BITS>::construct(self.config.popcount);
+        let count = chip.assign(layouter.namespace(|| "popcount"), value)?;
+
+        layouter.constrain_constant(count.cell(), F::one())?;
+        Ok(count)
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    value: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = IsPowerOfTwoConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        IsPowerOfTwoChip::<F, 16>::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = IsPowerOfTwoChip::<F, 16>::construct(config);
+
+        chip.assign(layouter.namespace(|| "is power of two"), Value::known(self.value))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::range_check::pow2::MyCircuit;
+
+    #[test]
+    fn test_power_of_two() {
+        for value in [1u64, 2, 4, 256] {
+            let circuit = MyCircuit {
+                value: Fp::from(value),
+            };
+            let prover = MockProver::run(5, &circuit, vec![]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_not_power_of_two() {
+        for value in [0u64, 3, 6] {
+            let circuit = MyCircuit {
+                value: Fp::from(value),
+            };
+            let prover = MockProver::run(5, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
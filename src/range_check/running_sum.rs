@@ -0,0 +1,236 @@
+/// Decomposes a field element into `WINDOW_BITS`-bit windows with a running sum, the same way
+/// `halo2_gadgets::utilities::decompose_running_sum` does. Starting from `z_0 = value`, every row
+/// holds `z_{i+1} = (z_i - k_i) / 2^W`, so the window `k_i = z_i - 2^W * z_{i+1}` is range checked
+/// without being witnessed, by a lookup into a `RangeCheckTable` of `[0, 2^W)`. A gate on the
+/// product `k * (1-k) * ... * (2^W-1-k)` would have degree `2^W + 1` instead. In strict mode the
+/// last running sum must be 0, i.e. the value fits in `num_windows * WINDOW_BITS` bits.
+///```txt
+///        z        |  q_range_check  |  q_strict
+///       --------------------------------------------
+///        z_0      |        1        |     0
+///        z_1      |        1        |     0
+///         ..      |        ..       |     0
+///        z_{n-1}  |        1        |     0
+///        z_n      |        0        |     1
+///```
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+use crate::range_check::example2::table::RangeCheckTable;
+
+#[derive(Debug, Clone)]
+pub(crate) struct RunningSumConfig<F: FieldExt, const WINDOW_BITS: usize> {
+    z: Column<Advice>,
+    q_range_check: Selector,
+    q_strict: Selector,
+    table: RangeCheckTable<F, WINDOW_BITS>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const WINDOW_BITS: usize> RunningSumConfig<F, WINDOW_BITS> {
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>, z: Column<Advice>) -> Self {
+        let q_range_check = meta.complex_selector();
+        let q_strict = meta.selector();
+        let table = RangeCheckTable::configure(meta);
+
+        meta.enable_equality(z);
+
+        // k = z_cur - 2^W * z_next lies in [0, 2^W)
+        meta.lookup(|meta| {
+            let s = meta.query_selector(q_range_check);
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+            let window = z_cur - z_next * Expression::Constant(F::from(1u64 << WINDOW_BITS));
+
+            vec![
+                (s * window, table.value)
+            ]
+        });
+
+        meta.create_gate("strict", |meta| {
+            let s = meta.query_selector(q_strict);
+            let z = meta.query_advice(z, Rotation::cur());
+
+            Constraints::with_selector(s, [("z_n = 0", z)])
+        });
+
+        Self {
+            z,
+            q_range_check,
+            q_strict,
+            table,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Loads the table of `[0, 2^W)` the windows are looked up in.
+    pub(crate) fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.table.assign(layouter)
+    }
+
+    /// Decomposes `value` into `num_windows` windows, returning the cells `[z_0, ..., z_n]`.
+    pub(crate) fn decompose(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Value<F>,
+        num_windows: usize,
+        strict: bool,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let zs = (0..=num_windows)
+            .map(|i| value.map(|value| {
+                // z_i = value >> (i * W), which stays exact while value fits in u128
+                F::from_u128(value.get_lower_128().checked_shr((i * WINDOW_BITS) as u32).unwrap_or(0))
+            }))
+            .collect::<Vec<_>>();
+        self.assign_running_sum(layouter, &zs, strict)
+    }
+
+    /// Assigns the running sums `zs` as given, without computing them from `z_0`.
+    fn assign_running_sum(
+        &self,
+        mut layouter: impl Layouter<F>,
+        zs: &[Value<F>],
+        strict: bool,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert!(zs.len() >= 2);
+
+        layouter.assign_region(
+            || "running sum",
+            |mut region| {
+                let last = zs.len() - 1;
+                for row in 0..last {
+                    self.q_range_check.enable(&mut region, row)?;
+                }
+                if strict {
+                    self.q_strict.enable(&mut region, last)?;
+                }
+
+                zs.iter()
+                    .enumerate()
+                    .map(|(row, z)| region.assign_advice(|| format!("z_{}", row), self.z, row, || *z))
+                    .collect()
+            }
+        )
+    }
+}
+
+/// The windows `k_i = z_i - 2^W * z_{i+1}` of the running sums `zs`.
+fn windows<F: FieldExt, const WINDOW_BITS: usize>(zs: &[F]) -> Vec<F> {
+    zs.windows(2)
+        .map(|z| z[0] - z[1] * F::from(1u64 << WINDOW_BITS))
+        .collect()
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    value: F,
+    num_windows: usize,
+    /// Overrides the honest running sums, to witness a bad window.
+    zs: Option<Vec<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = RunningSumConfig<F, 3>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            num_windows: self.num_windows,
+            ..Default::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let z = meta.advice_column();
+        RunningSumConfig::configure(meta, z)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        config.load_table(&mut layouter)?;
+
+        match &self.zs {
+            None => config.decompose(
+                layouter.namespace(|| "decompose"),
+                Value::known(self.value),
+                self.num_windows,
+                true,
+            )?,
+            Some(zs) => {
+                let zs: Vec<_> = zs.iter().map(|z| Value::known(*z)).collect();
+                config.assign_running_sum(layouter.namespace(|| "running sum"), &zs, true)?
+            }
+        };
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+    use crate::range_check::running_sum::{windows, MyCircuit};
+
+    #[test]
+    fn test_degree() {
+        // the lookup keeps the degree below the 2^3 + 1 of a product over the window
+        let mut cs = ConstraintSystem::<Fp>::default();
+        MyCircuit::<Fp>::configure(&mut cs);
+        assert!(cs.degree() < (1 << 3) + 1);
+    }
+
+    #[test]
+    fn test_decompose() {
+        // 0o7531 in four 3-bit windows
+        let value = Fp::from(0o7531);
+        let zs = [0o7531u64, 0o753, 0o75, 0o7, 0].map(Fp::from);
+        let ks = windows::<_, 3>(&zs);
+        assert_eq!(ks, [1u64, 3, 5, 7].map(Fp::from).to_vec());
+
+        // the windows reconstruct the value
+        let reconstructed = ks.iter().rev().fold(Fp::zero(), |acc, k| acc * Fp::from(8) + k);
+        assert_eq!(reconstructed, value);
+
+        let circuit = MyCircuit {
+            value,
+            num_windows: 4,
+            zs: None,
+        };
+        let prover = MockProver::run(4, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // the value does not fit in three windows
+        let circuit = MyCircuit {
+            value,
+            num_windows: 3,
+            zs: None,
+        };
+        let prover = MockProver::run(4, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_bad_window() {
+        // 13 = 5 + 8 * 1, but z_1 = 0 leaves the window 13 out of range
+        let circuit = MyCircuit {
+            value: Fp::from(13),
+            num_windows: 2,
+            zs: Some([13u64, 0, 0].map(Fp::from).to_vec()),
+        };
+        let prover = MockProver::run(4, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+
+        let circuit = MyCircuit {
+            value: Fp::from(13),
+            num_windows: 2,
+            zs: Some([13u64, 1, 0].map(Fp::from).to_vec()),
+        };
+        let prover = MockProver::run(4, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+}
@@ -0,0 +1,163 @@
+/// This helper checks that a sequence of 32-bit values is sorted in non-decreasing order.
+/// The values are witnessed down a single column and range checked to 32 bits, without which
+/// a comparison could wrap around the modulus, and each adjacent pair is copied into a
+/// `LessThanChip` row whose `lt` flag, `v_{i+1} < v_i`, is pinned to 0:
+///```txt
+///        value  |                 lhs      |  rhs      |  ..  |  lt
+///       -----------------------------------------------------------------
+///         v_0   |                 v_1      |  v_0      |  ..  |  0
+///         v_1   |                 v_2      |  v_1      |  ..  |  0
+///         ..    |                  ..      |   ..      |  ..  |  0
+///```
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    arithmetic::FieldExt,
+};
+use crate::range_check::less_than::{LessThanChip, LessThanConfig};
+
+#[derive(Debug, Clone)]
+pub(crate) struct IsSortedConfig<F: FieldExt> {
+    value: Column<Advice>,
+    less_than: LessThanConfig<F, 4>,
+}
+
+pub(crate) struct IsSortedChip<F: FieldExt> {
+    config: IsSortedConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> IsSortedChip<F> {
+    pub(crate) fn construct(config: IsSortedConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> IsSortedConfig<F> {
+        let value = meta.advice_column();
+        let constants = meta.fixed_column();
+
+        meta.enable_equality(value);
+        meta.enable_constant(constants);
+
+        IsSortedConfig {
+            value,
+            less_than: LessThanChip::configure(meta),
+        }
+    }
+
+    pub(crate) fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        LessThanChip::construct(self.config.less_than.clone()).load_table(layouter)
+    }
+
+    /// Witnesses `values`, constrains each below `2^32` and `values[i] <= values[i + 1]` for
+    /// every adjacent pair, returning the cells of the values.
+    pub(crate) fn assign_sequence(&self, mut layouter: impl Layouter<F>, values: &[F])
+        -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let less_than = LessThanChip::construct(self.config.less_than.clone());
+
+        let cells = layouter.assign_region(
+            || "sequence",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, value)| {
+                        region.assign_advice(|| format!("v_{}", offset), self.config.value, offset, || Value::known(*value))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            }
+        )?;
+
+        for (i, cell) in cells.iter().enumerate() {
+            less_than.range_check(layouter.namespace(|| format!("v_{} < 2^32", i)), cell)?;
+        }
+
+        for (i, pair) in cells.windows(2).enumerate() {
+            let lt = less_than.assign_from_cells(
+                layouter.namespace(|| format!("v_{} < v_{}", i + 1, i)),
+                &pair[1],
+                &pair[0],
+            )?;
+            layouter.assign_region(
+                || format!("v_{} <= v_{}", i, i + 1),
+                |mut region| region.constrain_constant(lt.cell(), F::zero())
+            )?;
+        }
+
+        Ok(cells)
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    values: Vec<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = IsSortedConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            values: vec![F::zero(); self.values.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        IsSortedChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = IsSortedChip::construct(config);
+        chip.load_table(&mut layouter)?;
+
+        chip.assign_sequence(layouter.namespace(|| "is sorted"), &self.values)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::range_check::sorted::MyCircuit;
+
+    fn circuit(values: &[u64]) -> MyCircuit<Fp> {
+        MyCircuit {
+            values: values.iter().map(|v| Fp::from(*v)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_sorted() {
+        let prover = MockProver::run(9, &circuit(&[1, 3, 7, 200, 70000]), vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_unsorted() {
+        let prover = MockProver::run(9, &circuit(&[1, 7, 3, 200]), vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_equal_adjacent() {
+        let prover = MockProver::run(9, &circuit(&[2, 2, 5, 5, 5]), vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_wrapped_value() {
+        // 0 - (p - 1) + 2^32 = 2^32 + 1 decomposes with lt = 0, as if p - 1 <= 0
+        let circuit = MyCircuit {
+            values: vec![-Fp::one(), Fp::zero()],
+        };
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,242 @@
+/// This chip adds two 64-bit values, `c = a + b (mod 2^64)`, as pairs of 32-bit limbs, so that
+/// no intermediate sum comes near the field modulus. The carry out of the low limbs feeds the
+/// high limbs, and the carry out of the high limbs is the overflow:
+///```txt
+///        limb   |  byte_0  |  ..  |  byte_3  |  carry     |  q_add  |  q_decompose
+///       ---------------------------------------------------------------------------
+///        a_lo   |    ..    |  ..  |    ..    |  carry_lo  |    1    |      1
+///        a_hi   |    ..    |  ..  |    ..    |  carry_hi  |    0    |      1
+///        b_lo   |    ..    |  ..  |    ..    |            |    0    |      1
+///        b_hi   |    ..    |  ..  |    ..    |            |    0    |      1
+///        c_lo   |    ..    |  ..  |    ..    |            |    0    |      1
+///        c_hi   |    ..    |  ..  |    ..    |            |    0    |      1
+///```
+/// Every limb is decomposed into bytes looked up in an 8-bit range-check table, so it is
+/// less than 2^32, and the gate checks
+///   a_lo + b_lo = c_lo + carry_lo * 2^32
+///   a_hi + b_hi + carry_lo = c_hi + carry_hi * 2^32
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+use crate::range_check::example2::table::RangeCheckTable;
+
+/// Bytes in a 32-bit limb.
+const LIMB_BYTES: usize = 4;
+
+#[derive(Debug, Clone)]
+pub(crate) struct WideAddConfig<F: FieldExt> {
+    limb: Column<Advice>,
+    bytes: [Column<Advice>; LIMB_BYTES],
+    carry: Column<Advice>,
+    q_add: Selector,
+    q_decompose: Selector,
+    table: RangeCheckTable<F, 8>,
+}
+
+pub(crate) struct WideAddChip<F: FieldExt> {
+    config: WideAddConfig<F>,
+}
+
+impl<F: FieldExt> WideAddChip<F> {
+    pub(crate) fn construct(config: WideAddConfig<F>) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> WideAddConfig<F> {
+        let limb = meta.advice_column();
+        let bytes = [(); LIMB_BYTES].map(|_| meta.advice_column());
+        let carry = meta.advice_column();
+        let q_add = meta.selector();
+        let q_decompose = meta.complex_selector();
+        let table = RangeCheckTable::configure(meta);
+
+        meta.enable_equality(limb);
+        meta.enable_equality(carry);
+
+        // limb = byte_0 + byte_1 * 2^8 + byte_2 * 2^16 + byte_3 * 2^24
+        meta.create_gate("decompose", |meta| {
+            let s = meta.query_selector(q_decompose);
+            let limb = meta.query_advice(limb, Rotation::cur());
+            let sum = bytes.iter().rev().fold(Expression::Constant(F::zero()), |acc, byte| {
+                acc * Expression::Constant(F::from(1u64 << 8)) + meta.query_advice(*byte, Rotation::cur())
+            });
+
+            Constraints::with_selector(s, [("limb = sum of bytes", limb - sum)])
+        });
+
+        // every byte is in [0, 2^8)
+        for byte in bytes {
+            meta.lookup(|meta| {
+                let s = meta.query_selector(q_decompose);
+                let byte = meta.query_advice(byte, Rotation::cur());
+
+                vec![
+                    (s * byte, table.value)
+                ]
+            });
+        }
+
+        meta.create_gate("wide add", |meta| {
+            let s = meta.query_selector(q_add);
+            let [a_lo, a_hi, b_lo, b_hi, c_lo, c_hi] = [0, 1, 2, 3, 4, 5]
+                .map(|rotation| meta.query_advice(limb, Rotation(rotation)));
+            let carry_lo = meta.query_advice(carry, Rotation::cur());
+            let carry_hi = meta.query_advice(carry, Rotation::next());
+            let one = Expression::Constant(F::one());
+            let base = Expression::Constant(F::from(1u64 << 32));
+
+            Constraints::with_selector(s, [
+                ("carry_lo is boolean", carry_lo.clone() * (one.clone() - carry_lo.clone())),
+                ("carry_hi is boolean", carry_hi.clone() * (one - carry_hi.clone())),
+                ("a_lo + b_lo = c_lo + carry_lo * 2^32", a_lo + b_lo - c_lo - carry_lo.clone() * base.clone()),
+                ("a_hi + b_hi + carry_lo = c_hi + carry_hi * 2^32", a_hi + b_hi + carry_lo - c_hi - carry_hi * base),
+            ])
+        });
+
+        WideAddConfig {
+            limb,
+            bytes,
+            carry,
+            q_add,
+            q_decompose,
+            table,
+        }
+    }
+
+    pub(crate) fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.table.assign(layouter)
+    }
+
+    fn assign_limb(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        limb: Value<u32>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.config.q_decompose.enable(region, offset)?;
+        for (i, byte) in self.config.bytes.iter().enumerate() {
+            region.assign_advice(
+                || format!("byte {}", i),
+                *byte,
+                offset,
+                || limb.map(|limb| F::from(((limb >> (8 * i)) & 0xff) as u64))
+            )?;
+        }
+        region.assign_advice(|| "limb", self.config.limb, offset, || limb.map(|limb| F::from(limb as u64)))
+    }
+
+    /// Assigns `a`, `b` and `c`, returning the cells of the limbs of `c`, low first,
+    /// and of the overflow `carry_hi`.
+    pub(crate) fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Value<u64>,
+        b: Value<u64>,
+        c: Value<u64>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let lo = |value: Value<u64>| value.map(|value| value as u32);
+        let hi = |value: Value<u64>| value.map(|value| (value >> 32) as u32);
+
+        layouter.assign_region(
+            || "wide add",
+            |mut region| {
+                self.config.q_add.enable(&mut region, 0)?;
+
+                self.assign_limb(&mut region, 0, lo(a))?;
+                self.assign_limb(&mut region, 1, hi(a))?;
+                self.assign_limb(&mut region, 2, lo(b))?;
+                self.assign_limb(&mut region, 3, hi(b))?;
+                let c_lo = self.assign_limb(&mut region, 4, lo(c))?;
+                let c_hi = self.assign_limb(&mut region, 5, hi(c))?;
+
+                let carry_lo = lo(a).zip(lo(b)).map(|(a, b)| a.checked_add(b).is_none());
+                let carry_hi = a.zip(b).map(|(a, b)| a.checked_add(b).is_none());
+                let bit = |carry: Value<bool>| carry.map(|carry| if carry { F::one() } else { F::zero() });
+                region.assign_advice(|| "carry_lo", self.config.carry, 0, || bit(carry_lo))?;
+                let carry_hi = region.assign_advice(|| "carry_hi", self.config.carry, 1, || bit(carry_hi))?;
+
+                Ok((c_lo, c_hi, carry_hi))
+            }
+        )
+    }
+}
+
+/// Adds `a` and `b` into `c`, exposing the limbs of `c` and the overflow as `[c_lo, c_hi, overflow]`.
+#[derive(Default)]
+struct MyCircuit {
+    a: u64,
+    b: u64,
+    c: u64,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit {
+    type Config = (WideAddConfig<F>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (WideAddChip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = WideAddChip::construct(config.0);
+        chip.load_table(&mut layouter)?;
+
+        let (c_lo, c_hi, overflow) = chip.assign(
+            layouter.namespace(|| "wide add"),
+            Value::known(self.a),
+            Value::known(self.b),
+            Value::known(self.c),
+        )?;
+
+        layouter.constrain_instance(c_lo.cell(), config.1, 0)?;
+        layouter.constrain_instance(c_hi.cell(), config.1, 1)?;
+        layouter.constrain_instance(overflow.cell(), config.1, 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::range_check::wide_add::MyCircuit;
+
+    fn run(a: u64, b: u64, c: u64, overflow: bool) -> Result<(), ()> {
+        let circuit = MyCircuit { a, b, c };
+        let instances = vec![Fp::from(c & 0xffff_ffff), Fp::from(c >> 32), Fp::from(overflow as u64)];
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instances]).unwrap();
+        prover.verify().map_err(|_| ())
+    }
+
+    #[test]
+    fn test_no_carry() {
+        assert!(run(12, 30, 42, false).is_ok());
+        assert!(run(12, 30, 43, false).is_err());
+    }
+
+    #[test]
+    fn test_carry_between_limbs() {
+        // the low limbs overflow into the high limb
+        let a = u32::MAX as u64 + (5 << 32);
+        assert!(run(a, 3, (6 << 32) + 2, false).is_ok());
+        // dropping the carry
+        assert!(run(a, 3, (5 << 32) + 2, false).is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert!(run(u64::MAX, 2, 1, true).is_ok());
+        // the overflow is reported
+        assert!(run(u64::MAX, 2, 1, false).is_err());
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    pasta::Fp,
+    plonk::Circuit,
+};
+use crate::utils::assigned_advice;
+
+/// Checks that the equality constraints of a copy chain bind every copy.
+/// `circuit(None)` is the honest circuit, and `circuit(Some(i))` corrupts the witness of
+/// copy `i`, for `i < copies`; the honest circuit must verify, and every corrupted one
+/// must fail with at least one permutation failure.
+pub(crate) fn assert_cells_equal_under_mutation<C: Circuit<Fp>>(
+    k: u32,
+    instances: Vec<Vec<Fp>>,
+    circuit: impl Fn(Option<usize>) -> C,
+    copies: usize,
+) {
+    let prover = MockProver::run(k, &circuit(None), instances.clone()).unwrap();
+    prover.assert_satisfied();
+
+    for i in 0..copies {
+        let prover = MockProver::run(k, &circuit(Some(i)), instances.clone()).unwrap();
+        let failures = prover.verify().expect_err(&format!("corrupting copy {} verified", i));
+        assert!(
+            failures.iter().any(|failure| matches!(failure, VerifyFailure::Permutation { .. })),
+            "corrupting copy {} did not break an equality constraint: {:?}", i, failures
+        );
+    }
+}
+
+/// Reads back the value `circuit` assigns to advice column `column` at `row` when laid out
+/// against `instances`, or `None` if the cell is never assigned a known value.
+/// This takes the circuit rather than a finished `MockProver`: the prover's advice storage is
+/// a private field with no accessor, so the only way to read a cell back is to synthesize the
+/// circuit again into our own `Assignment`, which `assigned_advice` does.
+pub(crate) fn read_advice<C: Circuit<Fp>>(circuit: &C, instances: &[Vec<Fp>], column: usize, row: usize) -> Option<Fp> {
+    assigned_advice(circuit, instances).get(&(column, row)).copied()
+}
+
+/// The key `summarize_failures` counts `failure` under. The names of gates are private to
+/// `MockProver`'s failure metadata, so this is the one place reading them back from its
+/// `Display` output, where they are quoted last, as in `Gate 0 ('add')`.
+fn failure_key(failure: &VerifyFailure) -> String {
+    let display = match failure {
+        VerifyFailure::CellNotAssigned { gate, .. } => gate.to_string(),
+        VerifyFailure::ConstraintNotSatisfied { constraint, .. }
+        | VerifyFailure::ConstraintPoisoned { constraint } => constraint.to_string(),
+        VerifyFailure::Lookup { lookup_index, .. } => return format!("lookup {}", lookup_index),
+        VerifyFailure::Permutation { .. } => return "permutation".to_string(),
+    };
+
+    let quoted = display.rfind("('").and_then(|start| {
+        let start = start + 2;
+        display[start..].find("')").map(|end| display[start..start + end].to_string())
+    });
+    quoted.unwrap_or(display)
+}
+
+/// Runs `prover.verify()` and counts the failures by what broke: a gate failure is counted
+/// under the name of its gate, a lookup failure under `lookup <index>`, and an equality
+/// failure under `permutation`. A satisfied circuit gives an empty map.
+pub(crate) fn summarize_failures(prover: &MockProver<Fp>) -> BTreeMap<String, usize> {
+    let mut summary = BTreeMap::new();
+
+    for failure in prover.verify().err().unwrap_or_default() {
+        *summary.entry(failure_key(&failure)).or_insert(0) += 1;
+    }
+
+    summary
+}
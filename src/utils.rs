@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use halo2_proofs::{
+    circuit::Value,
+    dev::MockProver,
+    pasta::Fp,
+    plonk::{
+        Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, Fixed, FloorPlanner,
+        Instance, Selector,
+    },
+};
+#[cfg(any(test, feature = "bench"))]
+use halo2_proofs::{
+    pasta::EqAffine,
+    plonk::{create_proof, keygen_pk, keygen_vk},
+    poly::commitment::Params,
+    transcript::{Blake2bWrite, Challenge255},
+};
+#[cfg(any(test, feature = "bench"))]
+use rand_core::OsRng;
+
+/// The largest `k` tried by `minimum_k`.
+const MAX_K: u32 = 20;
+
+/// Finds the smallest `k` for which `MockProver::run` can lay out `circuit`,
+/// probing upwards from 1. Panics if no `k` up to `MAX_K` fits.
+pub(crate) fn minimum_k<C: Circuit<Fp>>(circuit: &C, instances: Vec<Vec<Fp>>) -> u32 {
+    (1..=MAX_K)
+        .find(|k| MockProver::run(*k, circuit, instances.clone()).is_ok())
+        .unwrap_or_else(|| panic!("the circuit does not fit in k = {}", MAX_K))
+}
+
+/// Records the name of every region the floor planner lays out and the rows it touches,
+/// together with the known values assigned to advice cells, read by `(column, row)`.
+#[derive(Default)]
+struct LayoutRecorder {
+    /// The instance columns `query_instance` reads from.
+    instances: Vec<Vec<Fp>>,
+    /// The name of each region with the first and last row it touched, if any.
+    regions: Vec<(String, Option<(usize, usize)>)>,
+    in_region: bool,
+    advice: BTreeMap<(usize, usize), Fp>,
+}
+
+impl LayoutRecorder {
+    /// Extends the rows of the current region, if inside one, to `row`.
+    fn touch(&mut self, row: usize) {
+        if !self.in_region {
+            return;
+        }
+        if let Some((_, rows)) = self.regions.last_mut() {
+            *rows = Some(rows.map_or((row, row), |(first, last)| (first.min(row), last.max(row))));
+        }
+    }
+}
+
+impl Assignment<Fp> for LayoutRecorder {
+    fn enter_region<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.regions.push((name_fn().into(), None));
+        self.in_region = true;
+    }
+
+    fn exit_region(&mut self) {
+        self.in_region = false;
+    }
+
+    fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, row: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        Ok(())
+    }
+
+    fn query_instance(&self, column: Column<Instance>, row: usize) -> Result<Value<Fp>, Error> {
+        let value = self.instances.get(column.index()).and_then(|column| column.get(row));
+        Ok(value.map_or(Value::unknown(), |value| Value::known(*value)))
+    }
+
+    fn assign_advice<V, VR, A, AR>(&mut self, _: A, column: Column<Advice>, row: usize, to: V) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fp>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        let advice = &mut self.advice;
+        to().map(|value| advice.insert((column.index(), row), value.into().evaluate()));
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(&mut self, _: A, _: Column<Fixed>, row: usize, _: V) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fp>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        Ok(())
+    }
+
+    fn copy(&mut self, _: Column<Any>, _: usize, _: Column<Any>, _: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn fill_from_row(&mut self, _: Column<Fixed>, _: usize, _: Value<Assigned<Fp>>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {}
+}
+
+/// Lays out `circuit` against `instances`, one vector per instance column, without proving it.
+fn record<C: Circuit<Fp>>(circuit: &C, instances: &[Vec<Fp>]) -> LayoutRecorder {
+    let mut meta = ConstraintSystem::default();
+    let config = C::configure(&mut meta);
+
+    let mut recorder = LayoutRecorder {
+        instances: instances.to_vec(),
+        ..Default::default()
+    };
+    C::FloorPlanner::synthesize(&mut recorder, circuit, config, meta.constants().clone())
+        .expect("the circuit lays out");
+    recorder
+}
+
+/// Lays out `circuit` without proving it, returning the names of its regions in order.
+pub(crate) fn region_names<C: Circuit<Fp>>(circuit: &C) -> Vec<String> {
+    region_rows(circuit).into_iter().map(|(name, _)| name).collect()
+}
+
+/// Lays out `circuit` without proving it, returning the name of each region in order with
+/// the number of rows it spans.
+pub(crate) fn region_rows<C: Circuit<Fp>>(circuit: &C) -> Vec<(String, usize)> {
+    record(circuit, &[])
+        .regions
+        .into_iter()
+        .map(|(name, rows)| (name, rows.map_or(0, |(first, last)| last - first + 1)))
+        .collect()
+}
+
+/// Lays out `circuit` against `instances` without proving it, returning the known value
+/// assigned to each advice cell, keyed by column index and row.
+pub(crate) fn assigned_advice<C: Circuit<Fp>>(circuit: &C, instances: &[Vec<Fp>]) -> BTreeMap<(usize, usize), Fp> {
+    record(circuit, instances).advice
+}
+
+/// The shape of a circuit, as its `configure` sets up the constraint system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CircuitStats {
+    pub(crate) advice_columns: usize,
+    pub(crate) fixed_columns: usize,
+    pub(crate) instance_columns: usize,
+    pub(crate) selectors: usize,
+    pub(crate) gates: usize,
+    /// The polynomial constraints across all gates.
+    pub(crate) constraints: usize,
+    pub(crate) lookups: usize,
+    /// The rows left to assign at `k` once the blinding rows are reserved.
+    pub(crate) usable_rows: usize,
+}
+
+/// Configures the circuit type of `circuit` and reports the size of its constraint system at `k`.
+pub(crate) fn report_stats<C: Circuit<Fp>>(k: u32, _circuit: &C) -> CircuitStats {
+    let mut meta = ConstraintSystem::default();
+    C::configure(&mut meta);
+
+    CircuitStats {
+        advice_columns: meta.num_advice_columns(),
+        fixed_columns: meta.num_fixed_columns(),
+        instance_columns: meta.num_instance_columns(),
+        selectors: meta.num_selectors(),
+        gates: meta.gates().len(),
+        constraints: meta.gates().iter().map(|gate| gate.polynomials().len()).sum(),
+        lookups: meta.lookups().len(),
+        usable_rows: (1 << k) - (meta.blinding_factors() + 1),
+    }
+}
+
+/// Creates a real proof of `circuit` at `k` against `instances`, one vector per instance
+/// column, and returns its length in bytes. Panics if the circuit cannot be proven.
+#[cfg(any(test, feature = "bench"))]
+pub(crate) fn proof_size_bytes<C: Circuit<Fp>>(k: u32, circuit: &C, instances: Vec<Vec<Fp>>) -> usize {
+    let params: Params<EqAffine> = Params::new(k);
+    let vk = keygen_vk(&params, circuit).expect("the verifying key is generated");
+    let pk = keygen_pk(&params, vk, circuit).expect("the proving key is generated");
+
+    let instances: Vec<&[Fp]> = instances.iter().map(|instance| &instance[..]).collect();
+    let mut transcript = Blake2bWrite::<_, EqAffine, Challenge255<_>>::init(vec![]);
+    create_proof(&params, &pk, std::slice::from_ref(circuit), &[&instances[..]], OsRng, &mut transcript)
+        .expect("the proof is created");
+
+    transcript.finalize().len()
+}
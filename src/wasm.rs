@@ -0,0 +1,15 @@
+//! Proving entry points for the browser. The crate is an rlib only, so the wasm module is
+//! built as a cdylib on demand:
+//! `cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib`,
+//! then bound with `wasm-bindgen --target web`.
+use wasm_bindgen::prelude::*;
+
+use crate::fibonacci::example1;
+
+/// Proves the Fibonacci circuit of example1 for the seeds `a` and `b`, verifies the proof,
+/// and returns its bytes as a `Uint8Array`.
+#[wasm_bindgen]
+pub fn fibonacci_prove_and_verify(a: u32, b: u32) -> Result<Vec<u8>, JsValue> {
+    example1::prove_and_verify(a as u64, b as u64)
+        .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+}
@@ -1 +1,11 @@
+mod add;
+mod asm;
+mod bitwise;
 mod instructions;
+mod pc;
+mod program;
+mod register;
+mod shift;
+mod step;
+mod syscall;
+mod trace;
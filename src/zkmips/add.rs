@@ -0,0 +1,265 @@
+/// This chip constrains a MIPS ADD, `rd = rs + rt (mod 2^WORD_BITS)`, for words of 16, 32
+/// or 64 bits, so that the narrower words can be tested cheaply.
+/// Each word is decomposed into `WORD_BITS / 8` bytes which are looked up in an 8-bit
+/// range-check table, and the bit lost to the modulus is kept in a boolean carry column.
+/// With 32-bit words:
+///```txt
+///        word    |  byte_0  |  byte_1  |  byte_2  |  byte_3  |  carry  |  q_add  |  q_decompose
+///       ------------------------------------------------------------------------------------------
+///        rs      |    ..    |    ..    |    ..    |    ..    |    c    |    1    |      1
+///        rt      |    ..    |    ..    |    ..    |    ..    |         |    0    |      1
+///        rd      |    ..    |    ..    |    ..    |    ..    |         |    0    |      1
+///```
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+use crate::range_check::example2::table::RangeCheckTable;
+
+#[derive(Debug, Clone)]
+pub(crate) struct AddConfig<F: FieldExt> {
+    word: Column<Advice>,
+    /// `WORD_BITS / 8` columns; a const expression cannot size an array here.
+    bytes: Vec<Column<Advice>>,
+    carry: Column<Advice>,
+    q_add: Selector,
+    q_decompose: Selector,
+    table: RangeCheckTable<F, 8>,
+}
+
+pub(crate) struct AddChip<F: FieldExt, const WORD_BITS: usize> {
+    config: AddConfig<F>,
+}
+
+impl<F: FieldExt, const WORD_BITS: usize> AddChip<F, WORD_BITS> {
+    pub(crate) fn construct(config: AddConfig<F>) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> AddConfig<F> {
+        assert!(matches!(WORD_BITS, 16 | 32 | 64), "words are 16, 32 or 64 bits, not {}", WORD_BITS);
+
+        let word = meta.advice_column();
+        let bytes: Vec<_> = (0..WORD_BITS / 8).map(|_| meta.advice_column()).collect();
+        let carry = meta.advice_column();
+        let q_add = meta.selector();
+        let q_decompose = meta.complex_selector();
+        let table = RangeCheckTable::configure(meta);
+
+        meta.enable_equality(word);
+
+        // word = byte_0 + byte_1 * 2^8 + .. + byte_{n-1} * 2^(8(n-1))
+        meta.create_gate("decompose", |meta| {
+            let s = meta.query_selector(q_decompose);
+            let word = meta.query_advice(word, Rotation::cur());
+            let sum = bytes.iter().rev().fold(Expression::Constant(F::zero()), |acc, byte| {
+                acc * Expression::Constant(F::from(1u64 << 8)) + meta.query_advice(*byte, Rotation::cur())
+            });
+
+            Constraints::with_selector(s, [("word = sum of bytes", word - sum)])
+        });
+
+        // every byte is in [0, 2^8)
+        for byte in bytes.iter().copied() {
+            meta.lookup(|meta| {
+                let s = meta.query_selector(q_decompose);
+                let byte = meta.query_advice(byte, Rotation::cur());
+
+                vec![
+                    (s * byte, table.value)
+                ]
+            });
+        }
+
+        // rs + rt = rd + carry * 2^WORD_BITS
+        meta.create_gate("add", |meta| {
+            let s = meta.query_selector(q_add);
+            let rs = meta.query_advice(word, Rotation::cur());
+            let rt = meta.query_advice(word, Rotation::next());
+            let rd = meta.query_advice(word, Rotation(2));
+            let carry = meta.query_advice(carry, Rotation::cur());
+            let modulus = Expression::Constant(F::from_u128(1u128 << WORD_BITS));
+
+            Constraints::with_selector(s, [
+                ("carry is boolean", carry.clone() * (Expression::Constant(F::one()) - carry.clone())),
+                ("rs + rt = rd + carry * 2^WORD_BITS", rs + rt - rd - carry * modulus),
+            ])
+        });
+
+        AddConfig {
+            word,
+            bytes,
+            carry,
+            q_add,
+            q_decompose,
+            table,
+        }
+    }
+
+    pub(crate) fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.table.assign(layouter)
+    }
+
+    fn assign_word(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        word: Value<u64>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.config.q_decompose.enable(region, offset)?;
+        for (i, byte) in self.config.bytes.iter().enumerate() {
+            region.assign_advice(
+                || format!("byte {}", i),
+                *byte,
+                offset,
+                || word.map(|word| F::from(((word >> (8 * i)) & 0xff) as u64))
+            )?;
+        }
+        region.assign_advice(|| "word", self.config.word, offset, || word.map(F::from))
+    }
+
+    /// Assigns `rs`, `rt` and `rd`, words of `WORD_BITS` bits, returning the cell of `rd`.
+    pub(crate) fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        rs_val: Value<u64>,
+        rt_val: Value<u64>,
+        rd_val: Value<u64>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "add",
+            |mut region| {
+                self.config.q_add.enable(&mut region, 0)?;
+
+                self.assign_word(&mut region, 0, rs_val)?;
+                self.assign_word(&mut region, 1, rt_val)?;
+                let rd_cell = self.assign_word(&mut region, 2, rd_val)?;
+
+                let carry = rs_val.zip(rt_val).map(|(rs, rt)| {
+                    if (rs as u128 + rt as u128) >> WORD_BITS == 1 { F::one() } else { F::zero() }
+                });
+                region.assign_advice(|| "carry", self.config.carry, 0, || carry)?;
+
+                Ok(rd_cell)
+            }
+        )
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<const WORD_BITS: usize> {
+    rs_val: u64,
+    rt_val: u64,
+    rd_val: u64,
+}
+
+impl<F: FieldExt, const WORD_BITS: usize> Circuit<F> for MyCircuit<WORD_BITS> {
+    type Config = AddConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        AddChip::<F, WORD_BITS>::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = AddChip::<F, WORD_BITS>::construct(config);
+        chip.load_table(&mut layouter)?;
+
+        chip.assign(
+            layouter.namespace(|| "add"),
+            Value::known(self.rs_val),
+            Value::known(self.rt_val),
+            Value::known(self.rd_val),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::zkmips::add::MyCircuit;
+
+    #[test]
+    fn test_add() {
+        let circuit = MyCircuit::<32> {
+            rs_val: 12,
+            rt_val: 30,
+            rd_val: 42,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_add_overflow() {
+        let circuit = MyCircuit::<32> {
+            rs_val: u32::MAX as u64,
+            rt_val: 2,
+            rd_val: 1,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // the unreduced sum does not fit in the word
+        let circuit = MyCircuit::<32> {
+            rs_val: u32::MAX as u64,
+            rt_val: 2,
+            rd_val: u32::MAX as u64 + 2,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_add_wrong_result() {
+        let circuit = MyCircuit::<32> {
+            rs_val: 12,
+            rt_val: 30,
+            rd_val: 43,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_add_16() {
+        let circuit = MyCircuit::<16> {
+            rs_val: 1200,
+            rt_val: 34,
+            rd_val: 1234,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_add_16_overflow() {
+        let circuit = MyCircuit::<16> {
+            rs_val: u16::MAX as u64,
+            rt_val: 2,
+            rd_val: 1,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // 2^16 + 1 is the 32-bit sum, not the 16-bit one
+        let circuit = MyCircuit::<16> {
+            rs_val: u16::MAX as u64,
+            rt_val: 2,
+            rd_val: u16::MAX as u64 + 2,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
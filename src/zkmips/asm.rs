@@ -0,0 +1,217 @@
+/// Assembles MIPS assembly text into the words `Instruction::encode` emits, for writing test
+/// programs. Every line holds one instruction in the syntax `Instruction` displays, such as
+/// `add $3, $1, $2`, `addi $29, $29, -16` or `j 0x100`; `#` starts a comment and blank lines
+/// are skipped. Only the instructions `Instruction::decode` models are accepted.
+use crate::zkmips::instructions::{Instruction, Register, Syntax};
+
+/// Why a line could not be assembled by [`assemble`]; `line` counts from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsmError {
+    /// The mnemonic is not an instruction we model.
+    UnknownMnemonic { line: usize, mnemonic: String },
+    /// The mnemonic takes `expected` operands but `found` were given.
+    OperandCount { line: usize, expected: usize, found: usize },
+    /// The operand is not a register.
+    BadRegister { line: usize, operand: String },
+    /// The operand is not a number that fits its field.
+    BadImmediate { line: usize, operand: String },
+}
+
+/// The conventional names of the registers, by number.
+const REGISTER_NAMES: [&str; 32] = [
+    "zero", "at", "v0", "v1", "a0", "a1", "a2", "a3",
+    "t0", "t1", "t2", "t3", "t4", "t5", "t6", "t7",
+    "s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7",
+    "t8", "t9", "k0", "k1", "gp", "sp", "fp", "ra",
+];
+
+/// Assembles `src` into one encoded word per instruction.
+pub fn assemble(src: &str) -> Result<Vec<u32>, AsmError> {
+    src.lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line_text = line.split('#').next().unwrap().trim();
+            (!line_text.is_empty()).then(|| assemble_line(i + 1, line_text))
+        })
+        .collect()
+}
+
+fn assemble_line(line: usize, text: &str) -> Result<u32, AsmError> {
+    let (mnemonic, operands) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let operands: Vec<_> = operands
+        .split(',')
+        .map(str::trim)
+        .filter(|operand| !operand.is_empty())
+        .collect();
+
+    let (template, syntax) = template(mnemonic)
+        .ok_or_else(|| AsmError::UnknownMnemonic { line, mnemonic: mnemonic.to_string() })?;
+
+    let expected = match syntax {
+        Syntax::NoOperand => 0,
+        Syntax::JumpReg | Syntax::Jump => 1,
+        Syntax::BranchZero => 2,
+        Syntax::ThreeReg | Syntax::Shift | Syntax::Immediate | Syntax::LogicalImmediate | Syntax::Branch => 3,
+    };
+    if operands.len() != expected {
+        return Err(AsmError::OperandCount { line, expected, found: operands.len() });
+    }
+
+    let reg = |i: usize| register(operands[i])
+        .ok_or_else(|| AsmError::BadRegister { line, operand: operands[i].to_string() });
+    let imm = |i: usize, min: i64, max: i64| number(operands[i])
+        .filter(|value| (min..=max).contains(value))
+        .ok_or_else(|| AsmError::BadImmediate { line, operand: operands[i].to_string() });
+
+    let instruction = match (template, syntax) {
+        (Instruction::RType { funct, .. }, Syntax::ThreeReg) =>
+            Instruction::RType { rd: reg(0)?, rs: reg(1)?, rt: reg(2)?, shamt: 0, funct },
+        (Instruction::RType { funct, .. }, Syntax::Shift) =>
+            Instruction::RType { rd: reg(0)?, rs: Register::ZERO, rt: reg(1)?, shamt: imm(2, 0, 31)? as u8, funct },
+        (Instruction::RType { funct, .. }, Syntax::JumpReg) =>
+            Instruction::RType { rs: reg(0)?, rt: Register::ZERO, rd: Register::ZERO, shamt: 0, funct },
+        (instruction, Syntax::NoOperand) => instruction,
+        // a sign-extended immediate may be spelled as its 16 bits too
+        (Instruction::IType { opcode, .. }, Syntax::Immediate) =>
+            Instruction::IType { opcode, rt: reg(0)?, rs: reg(1)?, imm: imm(2, i16::MIN as i64, u16::MAX as i64)? as u16 },
+        (Instruction::IType { opcode, .. }, Syntax::LogicalImmediate) =>
+            Instruction::IType { opcode, rt: reg(0)?, rs: reg(1)?, imm: imm(2, 0, u16::MAX as i64)? as u16 },
+        (Instruction::IType { opcode, .. }, Syntax::Branch) =>
+            Instruction::IType { opcode, rs: reg(0)?, rt: reg(1)?, imm: imm(2, i16::MIN as i64, u16::MAX as i64)? as u16 },
+        // the REGIMM branches keep the rt of their template
+        (Instruction::IType { opcode, rt, .. }, Syntax::BranchZero) =>
+            Instruction::IType { opcode, rs: reg(0)?, rt, imm: imm(1, i16::MIN as i64, u16::MAX as i64)? as u16 },
+        (Instruction::JType { opcode, .. }, Syntax::Jump) =>
+            Instruction::JType { opcode, addr: imm(0, 0, (1 << 26) - 1)? as u32 },
+        _ => unreachable!("syntax is chosen per format"),
+    };
+
+    Ok(instruction.encode())
+}
+
+/// An instruction displayed as `mnemonic`, with its operand fields zeroed, and its syntax.
+/// The templates come from the decoder's own table, so the two cannot drift apart.
+fn template(mnemonic: &str) -> Option<(Instruction, Syntax)> {
+    let r_types = (0..64).map(|funct| Instruction::RType {
+        rs: Register::ZERO,
+        rt: Register::ZERO,
+        rd: Register::ZERO,
+        shamt: 0,
+        funct,
+    });
+    // rt tells the REGIMM branches apart
+    let i_types = (0..64).flat_map(|opcode| (0..32).map(move |rt| Instruction::IType {
+        opcode,
+        rs: Register::ZERO,
+        rt: Register(rt),
+        imm: 0,
+    }));
+    let j_types = (0..64).map(|opcode| Instruction::JType { opcode, addr: 0 });
+
+    r_types
+        .chain(i_types)
+        .chain(j_types)
+        .find_map(|instruction| match instruction.syntax() {
+            Some((name, syntax)) if name == mnemonic => Some((instruction, syntax)),
+            _ => None,
+        })
+}
+
+/// `$3` or `$sp`.
+fn register(operand: &str) -> Option<Register> {
+    let name = operand.strip_prefix('$')?;
+    match name.parse::<u8>() {
+        Ok(index) => Register::new(index),
+        Err(_) => REGISTER_NAMES.iter().position(|n| *n == name).map(|index| Register(index as u8)),
+    }
+}
+
+/// A decimal or `0x` hexadecimal number, optionally negative.
+fn number(operand: &str) -> Option<i64> {
+    let (negative, digits) = match operand.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, operand),
+    };
+    let value = match digits.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16).ok()?,
+        None => digits.parse::<i64>().ok()?,
+    };
+    Some(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::zkmips::asm::{assemble, AsmError};
+    use crate::zkmips::instructions::{Instruction, Register};
+
+    #[test]
+    fn test_assemble() {
+        let words = assemble("
+            add $3, $1, $2      # 0x00221820
+            addi $sp, $sp, -16
+            sll $8, $9, 4
+            beq $4, $5, 3
+            bgez $4, 8
+            jr $ra
+            syscall
+            j 0x100
+        ").unwrap();
+
+        assert_eq!(words, vec![
+            0x00221820,
+            0x23bdfff0,
+            0x00094100,
+            0x10850003,
+            0x04810008,
+            0x03e00008,
+            0x0000000c,
+            0x08000100,
+        ]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let src = "add $3, $1, $2\nori $1, $1, 255\nbltzal $4, 8\njal 0x40";
+        let words = assemble(src).unwrap();
+
+        // decoding and displaying gives back the source
+        let lines: Vec<_> = words.iter().map(|word| Instruction::decode(*word).unwrap().to_string()).collect();
+        assert_eq!(lines, src.lines().collect::<Vec<_>>());
+
+        assert_eq!(
+            Instruction::decode(words[0]),
+            Ok(Instruction::RType {
+                rs: Register(1),
+                rt: Register(2),
+                rd: Register(3),
+                shamt: 0,
+                funct: 0b100000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            assemble("nop"),
+            Err(AsmError::UnknownMnemonic { line: 1, mnemonic: "nop".to_string() })
+        );
+        assert_eq!(
+            assemble("add $3, $1\n"),
+            Err(AsmError::OperandCount { line: 1, expected: 3, found: 2 })
+        );
+        assert_eq!(
+            assemble("syscall\nadd $3, $1, $32"),
+            Err(AsmError::BadRegister { line: 2, operand: "$32".to_string() })
+        );
+        assert_eq!(
+            assemble("j 0x4000000"),
+            Err(AsmError::BadImmediate { line: 1, operand: "0x4000000".to_string() })
+        );
+        // the logical immediates are zero-extended, so they are never negative
+        assert_eq!(
+            assemble("ori $1, $1, -1"),
+            Err(AsmError::BadImmediate { line: 1, operand: "-1".to_string() })
+        );
+    }
+}
@@ -0,0 +1,257 @@
+/// This chip constrains the MIPS AND, OR and XOR, `out = lhs op rhs`.
+/// Each word is decomposed into bytes, and every triple of bytes at the same position
+/// is looked up together with the op in a table of `(op, lhs, rhs, lhs op rhs)`. The op is
+/// witnessed, but constrained equal to `op_tag`, a fixed column set by the circuit, so that a
+/// prover cannot look the bytes up under another op.
+///```txt
+///        word    |  byte_0  |  byte_1  |  byte_2  |  byte_3  |  op  |  op_tag  |  q_bitwise  |  q_decompose
+///       -----------------------------------------------------------------------------------------------------
+///        lhs     |    ..    |    ..    |    ..    |    ..    |  op  |    op    |      1      |      1
+///        rhs     |    ..    |    ..    |    ..    |    ..    |      |          |      0      |      1
+///        out     |    ..    |    ..    |    ..    |    ..    |      |          |      0      |      1
+///```
+/// The table holds `3 * 2^16` rows, so circuits using this chip need `k >= 18`.
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+
+pub(crate) mod table;
+use table::{BitwiseOp, BitwiseTable};
+
+/// Bytes in a MIPS word.
+const WORD_BYTES: usize = 4;
+
+#[derive(Debug, Clone)]
+pub(crate) struct BitwiseConfig<F: FieldExt> {
+    word: Column<Advice>,
+    bytes: [Column<Advice>; WORD_BYTES],
+    op: Column<Advice>,
+    op_tag: Column<Fixed>,
+    q_bitwise: Selector,
+    q_decompose: Selector,
+    table: BitwiseTable<F>,
+}
+
+pub(crate) struct BitwiseChip<F: FieldExt> {
+    config: BitwiseConfig<F>,
+}
+
+impl<F: FieldExt> BitwiseChip<F> {
+    pub(crate) fn construct(config: BitwiseConfig<F>) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> BitwiseConfig<F> {
+        let word = meta.advice_column();
+        let bytes = [(); WORD_BYTES].map(|_| meta.advice_column());
+        let op = meta.advice_column();
+        let op_tag = meta.fixed_column();
+        let q_bitwise = meta.complex_selector();
+        let q_decompose = meta.selector();
+        let table = BitwiseTable::configure(meta);
+
+        meta.enable_equality(word);
+
+        // word = byte_0 + byte_1 * 2^8 + byte_2 * 2^16 + byte_3 * 2^24
+        meta.create_gate("decompose", |meta| {
+            let s = meta.query_selector(q_decompose);
+            let word = meta.query_advice(word, Rotation::cur());
+            let sum = bytes.iter().rev().fold(Expression::Constant(F::zero()), |acc, byte| {
+                acc * Expression::Constant(F::from(1u64 << 8)) + meta.query_advice(*byte, Rotation::cur())
+            });
+
+            Constraints::with_selector(s, [("word = sum of bytes", word - sum)])
+        });
+
+        meta.create_gate("op is pinned", |meta| {
+            let s = meta.query_selector(q_bitwise);
+            let op = meta.query_advice(op, Rotation::cur());
+            let op_tag = meta.query_fixed(op_tag, Rotation::cur());
+
+            Constraints::with_selector(s, [("op = op_tag", op - op_tag)])
+        });
+
+        // (op, lhs_i, rhs_i, out_i) is a row of the table, which also range checks the bytes;
+        // a disabled row looks up (And, 0, 0, 0)
+        for byte in bytes {
+            meta.lookup(|meta| {
+                let s = meta.query_selector(q_bitwise);
+                let op = meta.query_advice(op, Rotation::cur());
+                let lhs = meta.query_advice(byte, Rotation::cur());
+                let rhs = meta.query_advice(byte, Rotation::next());
+                let out = meta.query_advice(byte, Rotation(2));
+
+                vec![
+                    (s.clone() * op, table.op),
+                    (s.clone() * lhs, table.lhs),
+                    (s.clone() * rhs, table.rhs),
+                    (s * out, table.out),
+                ]
+            });
+        }
+
+        BitwiseConfig {
+            word,
+            bytes,
+            op,
+            op_tag,
+            q_bitwise,
+            q_decompose,
+            table,
+        }
+    }
+
+    pub(crate) fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.table.assign(layouter)
+    }
+
+    fn assign_word(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        word: Value<u32>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.config.q_decompose.enable(region, offset)?;
+        for (i, byte) in self.config.bytes.iter().enumerate() {
+            region.assign_advice(
+                || format!("byte {}", i),
+                *byte,
+                offset,
+                || word.map(|word| F::from(((word >> (8 * i)) & 0xff) as u64))
+            )?;
+        }
+        region.assign_advice(|| "word", self.config.word, offset, || word.map(|word| F::from(word as u64)))
+    }
+
+    /// Assigns `lhs`, `rhs` and `out` for `op`, returning the cell of `out`.
+    pub(crate) fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        op: BitwiseOp,
+        lhs: Value<u32>,
+        rhs: Value<u32>,
+        out: Value<u32>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.assign_with_tag(layouter, op, Value::known(op.tag()), lhs, rhs, out)
+    }
+
+    /// `assign` with the op witnessed as `tag`, which only a cheating prover makes differ
+    /// from the tag of `op`.
+    fn assign_with_tag(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: BitwiseOp,
+        tag: Value<u64>,
+        lhs: Value<u32>,
+        rhs: Value<u32>,
+        out: Value<u32>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "bitwise",
+            |mut region| {
+                self.config.q_bitwise.enable(&mut region, 0)?;
+                region.assign_fixed(|| "op tag", self.config.op_tag, 0, || Value::known(F::from(op.tag())))?;
+                region.assign_advice(|| "op", self.config.op, 0, || tag.map(F::from))?;
+
+                self.assign_word(&mut region, 0, lhs)?;
+                self.assign_word(&mut region, 1, rhs)?;
+                self.assign_word(&mut region, 2, out)
+            }
+        )
+    }
+}
+
+/// `forged_tag` overrides the witnessed tag of `op`.
+#[derive(Default)]
+struct MyCircuit {
+    op: Option<BitwiseOp>,
+    lhs: u32,
+    rhs: u32,
+    out: u32,
+    forged_tag: Option<BitwiseOp>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit {
+    type Config = BitwiseConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            op: self.op,
+            ..Default::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        BitwiseChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = BitwiseChip::construct(config);
+        chip.load_table(&mut layouter)?;
+
+        let op = self.op.unwrap_or(BitwiseOp::And);
+        chip.assign_with_tag(
+            layouter.namespace(|| "bitwise"),
+            op,
+            Value::known(self.forged_tag.unwrap_or(op).tag()),
+            Value::known(self.lhs),
+            Value::known(self.rhs),
+            Value::known(self.out),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::zkmips::bitwise::MyCircuit;
+    use crate::zkmips::bitwise::table::BitwiseOp;
+
+    fn run(op: BitwiseOp, lhs: u32, rhs: u32, out: u32) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            op: Some(op),
+            lhs,
+            rhs,
+            out,
+            forged_tag: None,
+        };
+        MockProver::run(18, &circuit, vec![]).unwrap()
+    }
+
+    #[test]
+    fn test_bitwise() {
+        run(BitwiseOp::And, 0xff00, 0x0ff0, 0x0f00).assert_satisfied();
+        run(BitwiseOp::Or, 0xff00, 0x0ff0, 0xfff0).assert_satisfied();
+        run(BitwiseOp::Xor, 0xff00, 0x0ff0, 0xf0f0).assert_satisfied();
+        run(BitwiseOp::Xor, 0xdead_beef, 0xffff_ffff, 0x2152_4110).assert_satisfied();
+    }
+
+    #[test]
+    fn test_bitwise_wrong_output() {
+        // the lowest output byte is 0x01 rather than 0x00 & 0xf0
+        assert!(run(BitwiseOp::And, 0xff00, 0x0ff0, 0x0f01).verify().is_err());
+    }
+
+    #[test]
+    fn test_bitwise_forged_op() {
+        // 0xff00 | 0x0ff0 is in the table under Or, but the circuit is fixed to And
+        let circuit = MyCircuit {
+            op: Some(BitwiseOp::And),
+            lhs: 0xff00,
+            rhs: 0x0ff0,
+            out: 0xfff0,
+            forged_tag: Some(BitwiseOp::Or),
+        };
+        let prover = MockProver::<Fp>::run(18, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    arithmetic::FieldExt,
+};
+
+/// A bitwise operation on bytes, tagged in the table by its discriminant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BitwiseOp {
+    And = 0,
+    Or = 1,
+    Xor = 2,
+}
+
+impl BitwiseOp {
+    pub(crate) const ALL: [BitwiseOp; 3] = [BitwiseOp::And, BitwiseOp::Or, BitwiseOp::Xor];
+
+    pub(crate) fn tag(self) -> u64 {
+        self as u64
+    }
+
+    pub(crate) fn apply(self, lhs: u32, rhs: u32) -> u32 {
+        match self {
+            BitwiseOp::And => lhs & rhs,
+            BitwiseOp::Or => lhs | rhs,
+            BitwiseOp::Xor => lhs ^ rhs,
+        }
+    }
+}
+
+/// A lookup table of `(op, lhs, rhs, lhs op rhs)` for every op and every pair of bytes.
+/// e.g. (And, 0xf0, 0x3c, 0x30)
+#[derive(Debug, Clone)]
+pub(crate) struct BitwiseTable<F: FieldExt> {
+    pub(crate) op: TableColumn,
+    pub(crate) lhs: TableColumn,
+    pub(crate) rhs: TableColumn,
+    pub(crate) out: TableColumn,
+    pub(crate) _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> BitwiseTable<F> {
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            op: meta.lookup_table_column(),
+            lhs: meta.lookup_table_column(),
+            rhs: meta.lookup_table_column(),
+            out: meta.lookup_table_column(),
+            _marker: PhantomData
+        }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>
+    ) -> Result<(), Error> {
+        layouter.assign_table(|| "assign bitwise table", |mut table| {
+            let mut offset = 0;
+            for op in BitwiseOp::ALL {
+                for lhs in 0..(1u32 << 8) {
+                    for rhs in 0..(1u32 << 8) {
+                        table.assign_cell(|| "op", self.op, offset, || Value::known(F::from(op.tag())))?;
+                        table.assign_cell(|| "lhs", self.lhs, offset, || Value::known(F::from(lhs as u64)))?;
+                        table.assign_cell(|| "rhs", self.rhs, offset, || Value::known(F::from(rhs as u64)))?;
+                        table.assign_cell(|| "out", self.out, offset, || Value::known(F::from(op.apply(lhs, rhs) as u64)))?;
+                        offset += 1;
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
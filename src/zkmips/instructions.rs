@@ -1,10 +1,35 @@
-use std::fmt::Display;
+use std::fmt::{self, Display};
+use std::marker::PhantomData;
 
 pub trait OpCode {
     const OP_CODE: u8;
     const FUNCT: Option<u8>;
 }
 
+pub struct J<R, D>(PhantomData<(R, D)>);
+pub struct JAL<R, D>(PhantomData<(R, D)>);
+pub struct BGEZ<R, D>(PhantomData<(R, D)>);
+pub struct BGEZAL<R, D>(PhantomData<(R, D)>);
+pub struct BLTZ<R, D>(PhantomData<(R, D)>);
+pub struct BLTZAL<R, D>(PhantomData<(R, D)>);
+pub struct BEQ<R, D>(PhantomData<(R, D)>);
+pub struct BNE<R, D>(PhantomData<(R, D)>);
+pub struct BLZE<R, D>(PhantomData<(R, D)>);
+pub struct BGTZ<R, D>(PhantomData<(R, D)>);
+pub struct ADD<R, D>(PhantomData<(R, D)>);
+pub struct ADDU<R, D>(PhantomData<(R, D)>);
+pub struct SUB<R, D>(PhantomData<(R, D)>);
+pub struct SUBU<R, D>(PhantomData<(R, D)>);
+pub struct AND<R, D>(PhantomData<(R, D)>);
+pub struct OR<R, D>(PhantomData<(R, D)>);
+pub struct XOR<R, D>(PhantomData<(R, D)>);
+pub struct NOR<R, D>(PhantomData<(R, D)>);
+pub struct SLT<R, D>(PhantomData<(R, D)>);
+pub struct SLTU<R, D>(PhantomData<(R, D)>);
+pub struct ADDI<R, D>(PhantomData<(R, D)>);
+pub struct ANDI<R, D>(PhantomData<(R, D)>);
+pub struct ORI<R, D>(PhantomData<(R, D)>);
+
 impl<R, D> OpCode for J<R, D> {
     const OP_CODE: u8 = 0b000010;
     const FUNCT: Option<u8> = None;
@@ -55,6 +80,72 @@ impl<R, D> OpCode for BGTZ<R, D> {
     const FUNCT: Option<u8> = None;
 }
 
+impl<R, D> OpCode for ADD<R, D> {
+    const OP_CODE: u8 = 0b000000;
+    const FUNCT: Option<u8> = Some(0b100000);
+}
+
+impl<R, D> OpCode for ADDU<R, D> {
+    const OP_CODE: u8 = 0b000000;
+    const FUNCT: Option<u8> = Some(0b100001);
+}
+
+impl<R, D> OpCode for SUB<R, D> {
+    const OP_CODE: u8 = 0b000000;
+    const FUNCT: Option<u8> = Some(0b100010);
+}
+
+impl<R, D> OpCode for SUBU<R, D> {
+    const OP_CODE: u8 = 0b000000;
+    const FUNCT: Option<u8> = Some(0b100011);
+}
+
+impl<R, D> OpCode for AND<R, D> {
+    const OP_CODE: u8 = 0b000000;
+    const FUNCT: Option<u8> = Some(0b100100);
+}
+
+impl<R, D> OpCode for OR<R, D> {
+    const OP_CODE: u8 = 0b000000;
+    const FUNCT: Option<u8> = Some(0b100101);
+}
+
+impl<R, D> OpCode for XOR<R, D> {
+    const OP_CODE: u8 = 0b000000;
+    const FUNCT: Option<u8> = Some(0b100110);
+}
+
+impl<R, D> OpCode for NOR<R, D> {
+    const OP_CODE: u8 = 0b000000;
+    const FUNCT: Option<u8> = Some(0b100111);
+}
+
+impl<R, D> OpCode for SLT<R, D> {
+    const OP_CODE: u8 = 0b000000;
+    const FUNCT: Option<u8> = Some(0b101010);
+}
+
+impl<R, D> OpCode for SLTU<R, D> {
+    const OP_CODE: u8 = 0b000000;
+    const FUNCT: Option<u8> = Some(0b101011);
+}
+
+impl<R, D> OpCode for ADDI<R, D> {
+    const OP_CODE: u8 = 0b001000;
+    const FUNCT: Option<u8> = None;
+}
+
+impl<R, D> OpCode for ANDI<R, D> {
+    const OP_CODE: u8 = 0b001100;
+    const FUNCT: Option<u8> = None;
+}
+
+impl<R, D> OpCode for ORI<R, D> {
+    const OP_CODE: u8 = 0b001101;
+    const FUNCT: Option<u8> = None;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SyscallNumber {
     MMAP = 4090,
     BRK = 4045,
@@ -65,4 +156,546 @@ pub enum SyscallNumber {
     FCNTL = 4055,
 }
 
-pub enum Instruction {}
+
+/// A general purpose register, `$0` to `$31`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Register(pub u8);
+
+impl Register {
+    /// `$zero`, hardwired to 0.
+    pub const ZERO: Register = Register(0);
+
+    pub fn new(index: u8) -> Option<Self> {
+        if index < 32 {
+            Some(Register(index))
+        } else {
+            None
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.0 < 32
+    }
+
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl TryFrom<u8> for Register {
+    type Error = DecodeError;
+
+    fn try_from(index: u8) -> Result<Self, DecodeError> {
+        Register::new(index).ok_or(DecodeError::InvalidRegister(index))
+    }
+}
+
+impl Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "${}", self.0)
+    }
+}
+
+/// A MIPS instruction in one of the three encoding formats.
+///```txt
+///   format |  31..26  |  25..21  |  20..16  |  15..11  |  10..6   |   5..0   |
+///  --------+----------+----------+----------+----------+----------+----------+
+///   R      |  000000  |    rs    |    rt    |    rd    |  shamt   |  funct   |
+///   I      |  opcode  |    rs    |    rt    |          imm                   |
+///   J      |  opcode  |                     addr                             |
+///```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    RType {
+        rs: Register,
+        rt: Register,
+        rd: Register,
+        shamt: u8,
+        funct: u8,
+    },
+    IType {
+        opcode: u8,
+        rs: Register,
+        rt: Register,
+        imm: u16,
+    },
+    JType {
+        opcode: u8,
+        addr: u32,
+    },
+}
+
+/// Why a word could not be decoded into an [`Instruction`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    UnknownOpcode(u8),
+    UnknownFunct(u8),
+    /// The `rt` field of a `0b000001` branch selects no known branch.
+    UnknownRegImm(u8),
+    /// A register index outside `$0..$31`; decoded fields are 5 bits, so only registers
+    /// built from a wider index are invalid.
+    InvalidRegister(u8),
+    /// The opcode is one MIPS32 reserves, `0b011000` to `0b011011`, which raises a reserved
+    /// instruction exception rather than being merely unmodelled here.
+    ReservedInstruction,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnknownOpcode(opcode) => write!(f, "unknown opcode {:#08b}", opcode),
+            DecodeError::UnknownFunct(funct) => write!(f, "unknown funct {:#08b}", funct),
+            DecodeError::UnknownRegImm(rt) => write!(f, "unknown branch selector rt = {:#07b}", rt),
+            DecodeError::InvalidRegister(index) => write!(f, "invalid register ${}", index),
+            DecodeError::ReservedInstruction => write!(f, "reserved instruction"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// How the operands of an instruction are written in assembly.
+pub(crate) enum Syntax {
+    /// `op $rd, $rs, $rt`
+    ThreeReg,
+    /// `op $rd, $rt, shamt`
+    Shift,
+    /// `op $rs`
+    JumpReg,
+    /// `op`
+    NoOperand,
+    /// `op $rt, $rs, imm`, the immediate sign-extended
+    Immediate,
+    /// `op $rt, $rs, imm`, the immediate zero-extended
+    LogicalImmediate,
+    /// `op $rs, $rt, imm`
+    Branch,
+    /// `op $rs, imm`
+    BranchZero,
+    /// `op addr`
+    Jump,
+}
+
+impl Instruction {
+    /// Splits a 32-bit word into the fields of its format and checks it is an instruction we model.
+    pub fn decode(word: u32) -> Result<Instruction, DecodeError> {
+        let opcode = (word >> 26) as u8;
+        let rs = Register(((word >> 21) & 0x1f) as u8);
+        let rt = Register(((word >> 16) & 0x1f) as u8);
+
+        let instruction = match opcode {
+            0b000000 => Instruction::RType {
+                rs,
+                rt,
+                rd: Register(((word >> 11) & 0x1f) as u8),
+                shamt: ((word >> 6) & 0x1f) as u8,
+                funct: (word & 0x3f) as u8,
+            },
+            op if op == J::<(), ()>::OP_CODE || op == JAL::<(), ()>::OP_CODE => Instruction::JType {
+                opcode,
+                addr: word & 0x03ff_ffff,
+            },
+            _ => Instruction::IType {
+                opcode,
+                rs,
+                rt,
+                imm: word as u16,
+            },
+        };
+
+        match instruction {
+            _ if instruction.syntax().is_some() => Ok(instruction),
+            _ if (0b011000..=0b011011).contains(&opcode) => Err(DecodeError::ReservedInstruction),
+            Instruction::RType { funct, .. } => Err(DecodeError::UnknownFunct(funct)),
+            Instruction::IType { opcode, rt, .. } if opcode == BGEZ::<(), ()>::OP_CODE =>
+                Err(DecodeError::UnknownRegImm(rt.0)),
+            _ => Err(DecodeError::UnknownOpcode(opcode)),
+        }
+    }
+
+    /// Packs the fields back into a 32-bit word, the inverse of `decode`.
+    /// Panics if a register, `shamt` or `addr` does not fit its field.
+    pub fn encode(&self) -> u32 {
+        match *self {
+            Instruction::RType { rs, rt, rd, shamt, funct } => {
+                assert!(rs.is_valid() && rt.is_valid() && rd.is_valid(), "register out of range");
+                assert!(shamt < 32, "shamt out of range");
+                assert!(funct < 64, "funct out of range");
+                (rs.0 as u32) << 21 | (rt.0 as u32) << 16 | (rd.0 as u32) << 11 | (shamt as u32) << 6 | funct as u32
+            }
+            Instruction::IType { opcode, rs, rt, imm } => {
+                // for the 0b000001 branches rt is the discriminator, so it is packed like a register
+                assert!(rs.is_valid() && rt.is_valid(), "register out of range");
+                assert!(opcode < 64, "opcode out of range");
+                (opcode as u32) << 26 | (rs.0 as u32) << 21 | (rt.0 as u32) << 16 | imm as u32
+            }
+            Instruction::JType { opcode, addr } => {
+                assert!(opcode < 64, "opcode out of range");
+                assert!(addr < 1 << 26, "addr out of range");
+                (opcode as u32) << 26 | addr
+            }
+        }
+    }
+
+    /// The immediate of an I-type instruction sign-extended to 32 bits, as `addi` and the
+    /// branches read it; `None` for the logical immediates and the other formats.
+    pub fn imm_signed(&self) -> Option<i32> {
+        match (*self, self.syntax()?.1) {
+            (Instruction::IType { imm, .. }, Syntax::Immediate | Syntax::Branch | Syntax::BranchZero) =>
+                Some(imm as i16 as i32),
+            _ => None,
+        }
+    }
+
+    /// The immediate of `andi`, `ori` and `xori` zero-extended to 32 bits; `None` for the
+    /// other instructions.
+    pub fn imm_unsigned(&self) -> Option<u32> {
+        match (*self, self.syntax()?.1) {
+            (Instruction::IType { imm, .. }, Syntax::LogicalImmediate) => Some(imm as u32),
+            _ => None,
+        }
+    }
+
+    /// The assembly mnemonic, if the instruction is one we model.
+    pub fn mnemonic(&self) -> Option<&'static str> {
+        self.syntax().map(|(mnemonic, _)| mnemonic)
+    }
+
+    /// The assembly mnemonic and operand syntax, if the instruction is one we model.
+    pub(crate) fn syntax(&self) -> Option<(&'static str, Syntax)> {
+        match *self {
+            Instruction::RType { funct, .. } => match funct {
+                0b000000 => Some(("sll", Syntax::Shift)),
+                0b000010 => Some(("srl", Syntax::Shift)),
+                0b000011 => Some(("sra", Syntax::Shift)),
+                0b001000 => Some(("jr", Syntax::JumpReg)),
+                0b001100 => Some(("syscall", Syntax::NoOperand)),
+                f if Some(f) == ADD::<(), ()>::FUNCT => Some(("add", Syntax::ThreeReg)),
+                f if Some(f) == ADDU::<(), ()>::FUNCT => Some(("addu", Syntax::ThreeReg)),
+                f if Some(f) == SUB::<(), ()>::FUNCT => Some(("sub", Syntax::ThreeReg)),
+                f if Some(f) == SUBU::<(), ()>::FUNCT => Some(("subu", Syntax::ThreeReg)),
+                f if Some(f) == AND::<(), ()>::FUNCT => Some(("and", Syntax::ThreeReg)),
+                f if Some(f) == OR::<(), ()>::FUNCT => Some(("or", Syntax::ThreeReg)),
+                f if Some(f) == XOR::<(), ()>::FUNCT => Some(("xor", Syntax::ThreeReg)),
+                f if Some(f) == NOR::<(), ()>::FUNCT => Some(("nor", Syntax::ThreeReg)),
+                f if Some(f) == SLT::<(), ()>::FUNCT => Some(("slt", Syntax::ThreeReg)),
+                f if Some(f) == SLTU::<(), ()>::FUNCT => Some(("sltu", Syntax::ThreeReg)),
+                _ => None,
+            },
+            Instruction::IType { opcode, rt, .. } => match opcode {
+                // the REGIMM branches share an opcode and are told apart by rt
+                op if op == BGEZ::<(), ()>::OP_CODE => match rt.0 {
+                    0b00000 => Some(("bltz", Syntax::BranchZero)),
+                    0b00001 => Some(("bgez", Syntax::BranchZero)),
+                    0b10000 => Some(("bltzal", Syntax::BranchZero)),
+                    0b10001 => Some(("bgezal", Syntax::BranchZero)),
+                    _ => None,
+                },
+                op if op == BEQ::<(), ()>::OP_CODE => Some(("beq", Syntax::Branch)),
+                op if op == BNE::<(), ()>::OP_CODE => Some(("bne", Syntax::Branch)),
+                op if op == BLZE::<(), ()>::OP_CODE => Some(("blez", Syntax::BranchZero)),
+                op if op == BGTZ::<(), ()>::OP_CODE => Some(("bgtz", Syntax::BranchZero)),
+                op if op == ADDI::<(), ()>::OP_CODE => Some(("addi", Syntax::Immediate)),
+                0b001001 => Some(("addiu", Syntax::Immediate)),
+                op if op == ANDI::<(), ()>::OP_CODE => Some(("andi", Syntax::LogicalImmediate)),
+                op if op == ORI::<(), ()>::OP_CODE => Some(("ori", Syntax::LogicalImmediate)),
+                0b001110 => Some(("xori", Syntax::LogicalImmediate)),
+                _ => None,
+            },
+            Instruction::JType { opcode, .. } => match opcode {
+                op if op == J::<(), ()>::OP_CODE => Some(("j", Syntax::Jump)),
+                op if op == JAL::<(), ()>::OP_CODE => Some(("jal", Syntax::Jump)),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (mnemonic, syntax) = match self.syntax() {
+            Some(syntax) => syntax,
+            None => return write!(f, "<unknown instruction {:?}>", self),
+        };
+
+        match (*self, syntax) {
+            (Instruction::RType { rs, rt, rd, .. }, Syntax::ThreeReg) =>
+                write!(f, "{} {}, {}, {}", mnemonic, rd, rs, rt),
+            (Instruction::RType { rt, rd, shamt, .. }, Syntax::Shift) =>
+                write!(f, "{} {}, {}, {}", mnemonic, rd, rt, shamt),
+            (Instruction::RType { rs, .. }, Syntax::JumpReg) =>
+                write!(f, "{} {}", mnemonic, rs),
+            (_, Syntax::NoOperand) =>
+                write!(f, "{}", mnemonic),
+            (Instruction::IType { rs, rt, .. }, Syntax::LogicalImmediate) =>
+                write!(f, "{} {}, {}, {}", mnemonic, rt, rs, self.imm_unsigned().unwrap()),
+            (Instruction::IType { rs, rt, .. }, Syntax::Immediate) =>
+                write!(f, "{} {}, {}, {}", mnemonic, rt, rs, self.imm_signed().unwrap()),
+            (Instruction::IType { rs, rt, .. }, Syntax::Branch) =>
+                write!(f, "{} {}, {}, {}", mnemonic, rs, rt, self.imm_signed().unwrap()),
+            (Instruction::IType { rs, .. }, Syntax::BranchZero) =>
+                write!(f, "{} {}, {}", mnemonic, rs, self.imm_signed().unwrap()),
+            (Instruction::JType { addr, .. }, Syntax::Jump) =>
+                write!(f, "{} {:#x}", mnemonic, addr),
+            _ => unreachable!("syntax is chosen per format"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::zkmips::instructions::*;
+
+    #[test]
+    fn test_register() {
+        assert_eq!(Register::new(0), Some(Register::ZERO));
+        assert_eq!(Register::new(31), Some(Register(31)));
+        assert_eq!(Register::new(32), None);
+        assert!(!Register(40).is_valid());
+    }
+
+    #[test]
+    fn test_display_r_type() {
+        let add = Instruction::RType {
+            rs: Register(1),
+            rt: Register(2),
+            rd: Register(3),
+            shamt: 0,
+            funct: 0b100000,
+        };
+        assert_eq!(add.to_string(), "add $3, $1, $2");
+
+        let sll = Instruction::RType {
+            rs: Register(0),
+            rt: Register(9),
+            rd: Register(8),
+            shamt: 4,
+            funct: 0b000000,
+        };
+        assert_eq!(sll.to_string(), "sll $8, $9, 4");
+    }
+
+    #[test]
+    fn test_display_i_type() {
+        let addi = Instruction::IType {
+            opcode: 0b001000,
+            rs: Register(29),
+            rt: Register(29),
+            imm: 16,
+        };
+        assert_eq!(addi.to_string(), "addi $29, $29, 16");
+
+        let beq = Instruction::IType {
+            opcode: 0b000100,
+            rs: Register(4),
+            rt: Register(5),
+            imm: 3,
+        };
+        assert_eq!(beq.to_string(), "beq $4, $5, 3");
+
+        let bgez = Instruction::IType {
+            opcode: 0b000001,
+            rs: Register(4),
+            rt: Register(1),
+            imm: 8,
+        };
+        assert_eq!(bgez.to_string(), "bgez $4, 8");
+    }
+
+    #[test]
+    fn test_negative_immediate() {
+        // addi $29, $29, -16
+        let addi = Instruction::decode(0x23bdfff0).unwrap();
+        assert_eq!(addi.imm_signed(), Some(-16));
+        assert_eq!(addi.to_string(), "addi $29, $29, -16");
+
+        // bne $4, $5, -1
+        let bne = Instruction::decode(0x1485ffff).unwrap();
+        assert_eq!(bne.imm_signed(), Some(-1));
+        assert_eq!(bne.to_string(), "bne $4, $5, -1");
+
+        // ori $1, $1, 0xffff zero-extends
+        let ori = Instruction::decode(0x3421ffff).unwrap();
+        assert_eq!(ori.imm_signed(), None);
+        assert_eq!(ori.imm_unsigned(), Some(0xffff));
+        assert_eq!(ori.to_string(), "ori $1, $1, 65535");
+        assert_eq!(addi.imm_unsigned(), None);
+
+        assert_eq!(Instruction::decode(0x08000100).unwrap().imm_signed(), None);
+    }
+
+    #[test]
+    fn test_display_j_type() {
+        let j = Instruction::JType {
+            opcode: 0b000010,
+            addr: 0x100,
+        };
+        assert_eq!(j.to_string(), "j 0x100");
+
+        let jal = Instruction::JType {
+            opcode: 0b000011,
+            addr: 0x40,
+        };
+        assert_eq!(jal.to_string(), "jal 0x40");
+    }
+
+    #[test]
+    fn test_decode() {
+        // add $3, $1, $2
+        assert_eq!(
+            Instruction::decode(0x00221820),
+            Ok(Instruction::RType {
+                rs: Register(1),
+                rt: Register(2),
+                rd: Register(3),
+                shamt: 0,
+                funct: 0b100000,
+            })
+        );
+
+        // syscall
+        assert_eq!(Instruction::decode(0x0000000c).unwrap().to_string(), "syscall");
+
+        // addi $29, $29, -16
+        assert_eq!(
+            Instruction::decode(0x23bdfff0),
+            Ok(Instruction::IType {
+                opcode: 0b001000,
+                rs: Register(29),
+                rt: Register(29),
+                imm: 0xfff0,
+            })
+        );
+
+        // beq $4, $5, 3
+        assert_eq!(
+            Instruction::decode(0x10850003),
+            Ok(Instruction::IType {
+                opcode: 0b000100,
+                rs: Register(4),
+                rt: Register(5),
+                imm: 3,
+            })
+        );
+
+        // j 0x100 and jal 0x40
+        assert_eq!(
+            Instruction::decode(0x08000100),
+            Ok(Instruction::JType {
+                opcode: 0b000010,
+                addr: 0x100,
+            })
+        );
+        assert_eq!(
+            Instruction::decode(0x0c000040),
+            Ok(Instruction::JType {
+                opcode: 0b000011,
+                addr: 0x40,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_regimm() {
+        // bgez, bltz, bgezal and bltzal all use opcode 0b000001
+        assert_eq!(Instruction::decode(0x04810008).unwrap().to_string(), "bgez $4, 8");
+        assert_eq!(Instruction::decode(0x04800008).unwrap().to_string(), "bltz $4, 8");
+        assert_eq!(Instruction::decode(0x04910008).unwrap().to_string(), "bgezal $4, 8");
+        assert_eq!(Instruction::decode(0x04900008).unwrap().to_string(), "bltzal $4, 8");
+        assert_eq!(Instruction::decode(0x04820008), Err(DecodeError::UnknownRegImm(0b00010)));
+    }
+
+    #[test]
+    fn test_decode_unknown() {
+        assert_eq!(Instruction::decode(0x00000001), Err(DecodeError::UnknownFunct(1)));
+        assert_eq!(Instruction::decode(0xfc000000), Err(DecodeError::UnknownOpcode(0b111111)));
+        assert_eq!(Instruction::decode(0x60000000), Err(DecodeError::ReservedInstruction));
+    }
+
+    #[test]
+    fn test_decode_error() {
+        let error = Instruction::decode(0xfc000000).unwrap_err();
+        assert!(matches!(error, DecodeError::UnknownOpcode(0b111111)));
+        assert_eq!(error.to_string(), "unknown opcode 0b111111");
+
+        let error = Instruction::decode(0x00000001).unwrap_err();
+        assert!(matches!(error, DecodeError::UnknownFunct(1)));
+        assert_eq!(error.to_string(), "unknown funct 0b000001");
+
+        assert_eq!(Register::try_from(32), Err(DecodeError::InvalidRegister(32)));
+        assert_eq!(Register::try_from(31), Ok(Register(31)));
+
+        // usable as a boxed error
+        let error: Box<dyn std::error::Error> = Box::new(DecodeError::ReservedInstruction);
+        assert_eq!(error.to_string(), "reserved instruction");
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let words = [
+            0x00221820, // add $3, $1, $2
+            0x00221822, // sub $3, $1, $2
+            0x00221824, // and $3, $1, $2
+            0x0000000c, // syscall
+            0x03e00008, // jr $31
+            0x00094100, // sll $8, $9, 4
+            0x23bdfff0, // addi $29, $29, -16
+            0x342100ff, // ori $1, $1, 0xff
+            0x10850003, // beq $4, $5, 3
+            0x14850003, // bne $4, $5, 3
+            0x18800003, // blez $4, 3
+            0x1c800003, // bgtz $4, 3
+            0x04800008, // bltz $4, 8
+            0x04810008, // bgez $4, 8
+            0x04900008, // bltzal $4, 8
+            0x04910008, // bgezal $4, 8
+            0x08000100, // j 0x100
+            0x0c000040, // jal 0x40
+        ];
+
+        for word in words {
+            let instruction = Instruction::decode(word).unwrap();
+            assert_eq!(instruction.encode(), word, "{}", instruction);
+            assert_eq!(Instruction::decode(instruction.encode()), Ok(instruction));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "register out of range")]
+    fn test_encode_invalid_register() {
+        Instruction::RType {
+            rs: Register(1),
+            rt: Register(2),
+            rd: Register(32),
+            shamt: 0,
+            funct: 0b100000,
+        }.encode();
+    }
+
+    #[test]
+    fn test_alu_opcodes() {
+        let r_type = [
+            (ADD::<(), ()>::OP_CODE, ADD::<(), ()>::FUNCT, 0x20),
+            (ADDU::<(), ()>::OP_CODE, ADDU::<(), ()>::FUNCT, 0x21),
+            (SUB::<(), ()>::OP_CODE, SUB::<(), ()>::FUNCT, 0x22),
+            (SUBU::<(), ()>::OP_CODE, SUBU::<(), ()>::FUNCT, 0x23),
+            (AND::<(), ()>::OP_CODE, AND::<(), ()>::FUNCT, 0x24),
+            (OR::<(), ()>::OP_CODE, OR::<(), ()>::FUNCT, 0x25),
+            (XOR::<(), ()>::OP_CODE, XOR::<(), ()>::FUNCT, 0x26),
+            (NOR::<(), ()>::OP_CODE, NOR::<(), ()>::FUNCT, 0x27),
+            (SLT::<(), ()>::OP_CODE, SLT::<(), ()>::FUNCT, 0x2a),
+            (SLTU::<(), ()>::OP_CODE, SLTU::<(), ()>::FUNCT, 0x2b),
+        ];
+        for (op_code, funct, expected) in r_type {
+            assert_eq!(op_code, 0);
+            assert_eq!(funct, Some(expected));
+        }
+
+        assert_eq!(ADDI::<(), ()>::OP_CODE, 0x08);
+        assert_eq!(ANDI::<(), ()>::OP_CODE, 0x0c);
+        assert_eq!(ORI::<(), ()>::OP_CODE, 0x0d);
+        assert_eq!(ADDI::<(), ()>::FUNCT, None);
+        assert_eq!(ANDI::<(), ()>::FUNCT, None);
+        assert_eq!(ORI::<(), ()>::FUNCT, None);
+    }
+}
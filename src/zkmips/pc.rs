@@ -0,0 +1,309 @@
+/// This chip constrains the control flow of a run, one row per step. Every instruction is
+/// followed by the next one in memory, `pc_next = pc_cur + 4`, unless it is a conditional
+/// branch, where `pc_next = taken ? pc_cur + 4 + offset * 4 : pc_cur + 4` for the
+/// sign-extended word `offset` of the instruction. Like the Fibonacci examples, each step
+/// reads the row below it through a rotation, and a branch chooses between its two targets
+/// with a `SelectChip` on its own row:
+///```txt
+///        pc           |  offset  |  cond   |  a                  |  b         |  out      |  q_step  |  q_branch
+///       ------------------------------------------------------------------------------------------------------------
+///        pc_0         |          |         |                     |            |           |    1     |    0
+///        pc_1         |    o     |  taken  |  pc_1 + 4 + 4 * o   |  pc_1 + 4  |  pc_2     |    0     |    1
+///        pc_2         |          |         |                     |            |           |    0     |    0
+///```
+/// Jumps, which set `pc_next` from the instruction or a register, are left to later chips.
+use std::marker::PhantomData;
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+use crate::is_zero::select::{SelectChip, SelectConfig};
+
+/// The size in bytes of a MIPS instruction.
+const INSTRUCTION_BYTES: u64 = 4;
+
+/// The control flow of one instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Step {
+    /// Falls through to the next instruction.
+    Straight,
+    /// A conditional branch by `offset` instructions from the delay slot.
+    Branch { taken: bool, offset: i32 },
+}
+
+impl Step {
+    /// The pc after this step, from the pc of its instruction.
+    fn next_pc(&self, pc: u64) -> u64 {
+        let fall_through = pc.wrapping_add(INSTRUCTION_BYTES);
+        match self {
+            Step::Branch { taken: true, offset } => {
+                fall_through.wrapping_add((*offset as i64 * INSTRUCTION_BYTES as i64) as u64)
+            }
+            _ => fall_through,
+        }
+    }
+}
+
+/// The sign-extended `offset` as a field element.
+fn signed<F: FieldExt>(offset: i32) -> F {
+    if offset < 0 {
+        -F::from(offset.unsigned_abs() as u64)
+    } else {
+        F::from(offset as u64)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PcConfig {
+    pc: Column<Advice>,
+    offset: Column<Advice>,
+    q_step: Selector,
+    q_branch: Selector,
+    select: SelectConfig,
+    instance: Column<Instance>,
+}
+
+pub(crate) struct PcChip<F: FieldExt> {
+    config: PcConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> PcChip<F> {
+    pub(crate) fn construct(config: PcConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> PcConfig {
+        let pc = meta.advice_column();
+        let offset = meta.advice_column();
+        let q_step = meta.selector();
+        let q_branch = meta.selector();
+        let select = SelectChip::configure(meta);
+        let instance = meta.instance_column();
+
+        meta.enable_equality(pc);
+        meta.enable_equality(instance);
+
+        meta.create_gate("pc advance", |meta| {
+            let s = meta.query_selector(q_step);
+            let pc_cur = meta.query_advice(pc, Rotation::cur());
+            let pc_next = meta.query_advice(pc, Rotation::next());
+
+            Constraints::with_selector(s, [
+                ("pc_next = pc_cur + 4", pc_next - pc_cur - Expression::Constant(F::from(INSTRUCTION_BYTES))),
+            ])
+        });
+
+        // the select on the same row picks between the two targets
+        meta.create_gate("branch", |meta| {
+            let s = meta.query_selector(q_branch);
+            let pc_cur = meta.query_advice(pc, Rotation::cur());
+            let pc_next = meta.query_advice(pc, Rotation::next());
+            let offset = meta.query_advice(offset, Rotation::cur());
+            let taken_target = meta.query_advice(select.a, Rotation::cur());
+            let fall_through = meta.query_advice(select.b, Rotation::cur());
+            let out = meta.query_advice(select.out, Rotation::cur());
+            let four = Expression::Constant(F::from(INSTRUCTION_BYTES));
+
+            Constraints::with_selector(s, [
+                ("b = pc_cur + 4", fall_through - pc_cur.clone() - four.clone()),
+                ("a = pc_cur + 4 + offset * 4", taken_target - pc_cur - four.clone() - offset * four),
+                ("pc_next = out", pc_next - out),
+            ])
+        });
+
+        PcConfig {
+            pc,
+            offset,
+            q_step,
+            q_branch,
+            select,
+            instance,
+        }
+    }
+
+    /// Lays out `steps` straight-line instructions from `start`, returning the cell of the
+    /// pc after the last one.
+    pub(crate) fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        start: Value<u64>,
+        steps: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.assign_steps(layouter, start, &vec![Step::Straight; steps])
+    }
+
+    /// Lays out `steps` from `start`, returning the cell of the pc after the last one.
+    pub(crate) fn assign_steps(
+        &self,
+        layouter: impl Layouter<F>,
+        start: Value<u64>,
+        steps: &[Step],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let pcs: Vec<_> = (0..=steps.len())
+            .map(|i| start.map(|start| steps[..i].iter().fold(start, |pc, step| step.next_pc(pc))))
+            .collect();
+        self.assign_pcs(layouter, steps, &pcs)
+    }
+
+    /// Assigns the pc of every row, `steps.len() + 1` of them, as given.
+    fn assign_pcs(
+        &self,
+        mut layouter: impl Layouter<F>,
+        steps: &[Step],
+        pcs: &[Value<u64>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let select_chip = SelectChip::construct(self.config.select.clone());
+
+        layouter.assign_region(
+            || "pc",
+            |mut region| {
+                let mut pc_cell = region.assign_advice(|| "pc 0", self.config.pc, 0, || pcs[0].map(F::from))?;
+
+                for (row, step) in steps.iter().enumerate() {
+                    let pc = pcs[row].map(F::from);
+
+                    match step {
+                        Step::Straight => self.config.q_step.enable(&mut region, row)?,
+                        Step::Branch { taken, offset } => {
+                            self.config.q_branch.enable(&mut region, row)?;
+
+                            region.assign_advice(|| "offset", self.config.offset, row, || Value::known(signed::<F>(*offset)))?;
+
+                            let four = F::from(INSTRUCTION_BYTES);
+                            select_chip.assign(
+                                &mut region,
+                                row,
+                                Value::known(if *taken { F::one() } else { F::zero() }),
+                                pc.map(|pc| pc + four + signed::<F>(*offset) * four),
+                                pc.map(|pc| pc + four),
+                            )?;
+                        }
+                    }
+
+                    pc_cell = region.assign_advice(
+                        || format!("pc {}", row + 1),
+                        self.config.pc,
+                        row + 1,
+                        || pcs[row + 1].map(F::from)
+                    )?;
+                }
+
+                Ok(pc_cell)
+            }
+        )
+    }
+
+    pub(crate) fn expose_public(&self, mut layouter: impl Layouter<F>, cell: &AssignedCell<F, F>, row: usize)
+        -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Runs `steps` from `start` and exposes the final pc, which a malicious prover may
+/// replace with `final_pc_override`.
+#[derive(Default)]
+struct MyCircuit {
+    start: u64,
+    steps: Vec<Step>,
+    final_pc_override: Option<u64>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit {
+    type Config = PcConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            start: 0,
+            steps: self.steps.clone(),
+            final_pc_override: None,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        PcChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = PcChip::construct(config);
+
+        let mut pcs = vec![self.start];
+        for step in &self.steps {
+            pcs.push(step.next_pc(*pcs.last().unwrap()));
+        }
+        if let Some(pc) = self.final_pc_override {
+            *pcs.last_mut().unwrap() = pc;
+        }
+        let pcs: Vec<_> = pcs.into_iter().map(Value::known).collect();
+
+        let pc_cell = chip.assign_pcs(layouter.namespace(|| "pc"), &self.steps, &pcs)?;
+        chip.expose_public(layouter.namespace(|| "final pc"), &pc_cell, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::zkmips::pc::{MyCircuit, Step};
+
+    #[test]
+    fn test_straight_line() {
+        let circuit = MyCircuit {
+            start: 0x0040_0000,
+            steps: vec![Step::Straight; 5],
+            final_pc_override: None,
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(0x0040_0014)]]).unwrap();
+        prover.assert_satisfied();
+
+        // one instruction short
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(0x0040_0010)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_branch_taken() {
+        // 0x400004 + 4 + 3 * 4, then back 2 instructions from 0x400014 + 4
+        let circuit = MyCircuit {
+            start: 0x0040_0000,
+            steps: vec![
+                Step::Straight,
+                Step::Branch { taken: true, offset: 3 },
+                Step::Branch { taken: true, offset: -2 },
+            ],
+            final_pc_override: None,
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(0x0040_0010)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_branch_not_taken() {
+        let circuit = MyCircuit {
+            start: 0x0040_0000,
+            steps: vec![Step::Straight, Step::Branch { taken: false, offset: 3 }],
+            final_pc_override: None,
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(0x0040_0008)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_branch_inconsistent() {
+        // falling through a taken branch
+        let circuit = MyCircuit {
+            start: 0x0040_0000,
+            steps: vec![Step::Branch { taken: true, offset: 3 }],
+            final_pc_override: Some(0x0040_0004),
+        };
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(0x0040_0004)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
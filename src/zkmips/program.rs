@@ -0,0 +1,65 @@
+/// Loads a flat binary of big-endian 32-bit words into the instructions it encodes.
+use crate::zkmips::instructions::{DecodeError, Instruction};
+
+/// Why a binary could not be loaded by [`load_program`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadError {
+    /// The binary ends with a partial word of this many bytes.
+    Truncated(usize),
+    /// The word at byte `offset` does not decode.
+    Decode { offset: usize, error: DecodeError },
+}
+
+/// Decodes every word of `bytes`, reporting the byte offset of the first that does not decode.
+pub fn load_program(bytes: &[u8]) -> Result<Vec<Instruction>, LoadError> {
+    let chunks = bytes.chunks_exact(4);
+    if !chunks.remainder().is_empty() {
+        return Err(LoadError::Truncated(chunks.remainder().len()));
+    }
+
+    chunks
+        .enumerate()
+        .map(|(i, word)| {
+            let word = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+            Instruction::decode(word).map_err(|error| LoadError::Decode { offset: 4 * i, error })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::zkmips::instructions::{DecodeError, Instruction, Register};
+    use crate::zkmips::program::{load_program, LoadError};
+
+    #[test]
+    fn test_load_program() {
+        let bytes = [
+            0x00, 0x22, 0x18, 0x20, // add $3, $1, $2
+            0x00, 0x63, 0x20, 0x20, // add $4, $3, $3
+            0x08, 0x00, 0x01, 0x00, // j 0x100
+        ];
+        let program = load_program(&bytes).unwrap();
+
+        assert_eq!(program, vec![
+            Instruction::RType { rs: Register(1), rt: Register(2), rd: Register(3), shamt: 0, funct: 0b100000 },
+            Instruction::RType { rs: Register(3), rt: Register(3), rd: Register(4), shamt: 0, funct: 0b100000 },
+            Instruction::JType { opcode: 0b000010, addr: 0x100 },
+        ]);
+        assert_eq!(program[2].to_string(), "j 0x100");
+    }
+
+    #[test]
+    fn test_load_program_errors() {
+        // the second word has an unknown funct
+        let bytes = [
+            0x00, 0x22, 0x18, 0x20,
+            0x00, 0x00, 0x00, 0x01,
+        ];
+        assert_eq!(
+            load_program(&bytes),
+            Err(LoadError::Decode { offset: 4, error: DecodeError::UnknownFunct(1) })
+        );
+
+        assert_eq!(load_program(&[0x00, 0x22, 0x18, 0x20, 0x00]), Err(LoadError::Truncated(1)));
+    }
+}
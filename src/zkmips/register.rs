@@ -0,0 +1,317 @@
+/// The register file of the MIPS machine and a chip constraining accesses to it.
+/// The register state is committed as a lookup table of `(index, value)` pairs;
+/// a read looks its pair up in the table, and a write stores `result` unless it targets `$zero`.
+///```txt
+///        idx   |  value  |  written  |  value_inv  |  q_read  |  q_write
+///       -------------------------------------------------------------------
+///        rs    |  reg[rs]|           |             |    1     |    0
+///        rd    |  result |  result'  |   1/rd      |    0     |    1
+///```
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+use crate::is_zero::{IsZeroChip, IsZeroConfig};
+use crate::zkmips::instructions::Register;
+
+/// The 32 general purpose registers; `$zero` always reads as 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterFile {
+    regs: [u32; 32],
+}
+
+impl Default for RegisterFile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegisterFile {
+    pub fn new() -> Self {
+        RegisterFile {
+            regs: [0; 32]
+        }
+    }
+
+    pub fn read(&self, idx: Register) -> u32 {
+        self.regs[idx.index()]
+    }
+
+    /// Writes to `$zero` are ignored.
+    pub fn write(&mut self, idx: Register, val: u32) {
+        if idx != Register::ZERO {
+            self.regs[idx.index()] = val;
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RegisterFileConfig<F: FieldExt> {
+    idx: Column<Advice>,
+    value: Column<Advice>,
+    written: Column<Advice>,
+    q_read: Selector,
+    q_write: Selector,
+    rd_is_zero: IsZeroConfig<F>,
+    table_idx: TableColumn,
+    table_value: TableColumn,
+}
+
+pub(crate) struct RegisterFileChip<F: FieldExt> {
+    config: RegisterFileConfig<F>,
+}
+
+impl<F: FieldExt> RegisterFileChip<F> {
+    pub(crate) fn construct(config: RegisterFileConfig<F>) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> RegisterFileConfig<F> {
+        let [idx, value, written, value_inv] = [(); 4].map(|_| meta.advice_column());
+        let q_read = meta.complex_selector();
+        let q_write = meta.complex_selector();
+        let table_idx = meta.lookup_table_column();
+        let table_value = meta.lookup_table_column();
+
+        meta.enable_equality(value);
+        meta.enable_equality(written);
+
+        // (idx, value) is a pair of the committed state;
+        // a disabled row looks up (0, 0), which `$zero` always provides
+        meta.lookup(|meta| {
+            let q_read = meta.query_selector(q_read);
+            let idx = meta.query_advice(idx, Rotation::cur());
+            let value = meta.query_advice(value, Rotation::cur());
+
+            vec![
+                (q_read.clone() * idx, table_idx),
+                (q_read * value, table_value),
+            ]
+        });
+
+        // the written register is one of the indices of the state
+        meta.lookup(|meta| {
+            let q_write = meta.query_selector(q_write);
+            let idx = meta.query_advice(idx, Rotation::cur());
+
+            vec![
+                (q_write * idx, table_idx),
+            ]
+        });
+
+        let rd_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_write),
+            |meta| meta.query_advice(idx, Rotation::cur()),
+            value_inv
+        );
+
+        meta.create_gate("write", |meta| {
+            let s = meta.query_selector(q_write);
+            let value = meta.query_advice(value, Rotation::cur());
+            let written = meta.query_advice(written, Rotation::cur());
+
+            Constraints::with_selector(s, [
+                ("written = rd == 0 ? 0 : value", written - (Expression::Constant(F::one()) - rd_is_zero.expr()) * value),
+            ])
+        });
+
+        RegisterFileConfig {
+            idx,
+            value,
+            written,
+            q_read,
+            q_write,
+            rd_is_zero,
+            table_idx,
+            table_value,
+        }
+    }
+
+    /// Commits to `state` by loading it into the lookup table.
+    pub(crate) fn load_state(&self, layouter: &mut impl Layouter<F>, state: &RegisterFile) -> Result<(), Error> {
+        layouter.assign_table(|| "register state", |mut table| {
+            for (i, value) in state.regs.iter().enumerate() {
+                table.assign_cell(|| "idx", self.config.table_idx, i, || Value::known(F::from(i as u64)))?;
+                table.assign_cell(|| "value", self.config.table_value, i, || Value::known(F::from(*value as u64)))?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Reads `reg[rs]`, constrained to match the committed state.
+    pub(crate) fn read(
+        &self,
+        mut layouter: impl Layouter<F>,
+        rs: Register,
+        value: Value<u32>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "read register",
+            |mut region| {
+                self.config.q_read.enable(&mut region, 0)?;
+                region.assign_advice(|| "rs", self.config.idx, 0, || Value::known(F::from(rs.0 as u64)))?;
+                region.assign_advice(|| "reg[rs]", self.config.value, 0, || value.map(|v| F::from(v as u64)))
+            }
+        )
+    }
+
+    /// Writes `result` to `reg[rd]`, returning the cell of the value actually stored.
+    pub(crate) fn write(
+        &self,
+        mut layouter: impl Layouter<F>,
+        rd: Register,
+        result: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let is_zero_chip = IsZeroChip::construct(self.config.rd_is_zero.clone());
+
+        layouter.assign_region(
+            || "write register",
+            |mut region| {
+                self.config.q_write.enable(&mut region, 0)?;
+
+                let rd = F::from(rd.0 as u64);
+                region.assign_advice(|| "rd", self.config.idx, 0, || Value::known(rd))?;
+                is_zero_chip.assign(&mut region, 0, Value::known(rd))?;
+
+                result.copy_advice(|| "result", &mut region, self.config.value, 0)?;
+
+                let written = if rd == F::zero() {
+                    Value::known(F::zero())
+                } else {
+                    result.value().copied()
+                };
+                region.assign_advice(|| "written", self.config.written, 0, || written)
+            }
+        )
+    }
+}
+
+/// Moves `reg[rs]` to `reg[rd]`, exposing the value written at instance row 0.
+#[derive(Default)]
+struct MyCircuit {
+    state: RegisterFile,
+    rs: u8,
+    rs_val: u32,
+    rd: u8,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit {
+    type Config = (RegisterFileConfig<F>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            state: self.state,
+            ..Default::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (RegisterFileChip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = RegisterFileChip::construct(config.0);
+        chip.load_state(&mut layouter, &self.state)?;
+
+        // move reg[rs] to reg[rd]
+        let value = chip.read(
+            layouter.namespace(|| "read rs"),
+            Register(self.rs),
+            Value::known(self.rs_val)
+        )?;
+        let written = chip.write(
+            layouter.namespace(|| "write rd"),
+            Register(self.rd),
+            &value
+        )?;
+
+        layouter.constrain_instance(written.cell(), config.1, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::zkmips::instructions::Register;
+    use crate::zkmips::register::{MyCircuit, RegisterFile};
+
+    #[test]
+    fn test_register_file() {
+        let mut state = RegisterFile::new();
+        state.write(Register(8), 42);
+        assert_eq!(state.read(Register(8)), 42);
+
+        state.write(Register::ZERO, 42);
+        assert_eq!(state.read(Register::ZERO), 0);
+    }
+
+    #[test]
+    fn test_read_write() {
+        let mut state = RegisterFile::new();
+        state.write(Register(8), 42);
+
+        let circuit = MyCircuit {
+            state,
+            rs: 8,
+            rs_val: 42,
+            rd: 9,
+        };
+        let prover = MockProver::<Fp>::run(6, &circuit, vec![vec![Fp::from(42)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_write_zero() {
+        let mut state = RegisterFile::new();
+        state.write(Register(8), 42);
+
+        let circuit = MyCircuit {
+            state,
+            rs: 8,
+            rs_val: 42,
+            rd: 0,
+        };
+        let prover = MockProver::<Fp>::run(6, &circuit, vec![vec![Fp::from(0)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_read_wrong_value() {
+        let mut state = RegisterFile::new();
+        state.write(Register(8), 42);
+
+        let circuit = MyCircuit {
+            state,
+            rs: 8,
+            rs_val: 41,
+            rd: 9,
+        };
+        let prover = MockProver::<Fp>::run(6, &circuit, vec![vec![Fp::from(41)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_wrong_written_value() {
+        let mut state = RegisterFile::new();
+        state.write(Register(8), 42);
+
+        let circuit = MyCircuit {
+            state,
+            rs: 8,
+            rs_val: 42,
+            rd: 9,
+        };
+        let prover = MockProver::<Fp>::run(6, &circuit, vec![vec![Fp::from(41)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,275 @@
+/// This chip constrains a MIPS SLL, `out = in << shamt (mod 2^32)`.
+/// `2^shamt` is looked up in a table of `(shamt, 2^shamt)` for `shamt` in `[0, 32)`, and the
+/// product `in * 2^shamt` is split into the low word `out` and the bits shifted out, `hi`.
+/// Each word is decomposed into bytes which are looked up in an 8-bit range-check table.
+///```txt
+///        word    |  byte_0  |  ..  |  byte_3  |  shamt  |  pow      |  q_shift  |  q_decompose
+///       ----------------------------------------------------------------------------------------
+///        in      |    ..    |  ..  |    ..    |    s    |  2^s      |     1     |      1
+///        out     |    ..    |  ..  |    ..    |         |           |     0     |      1
+///        hi      |    ..    |  ..  |    ..    |         |           |     0     |      1
+///```
+use halo2_proofs::{
+    circuit::*,
+    plonk::*,
+    poly::Rotation,
+    arithmetic::FieldExt,
+};
+use crate::range_check::example2::table::RangeCheckTable;
+
+/// Bytes in a MIPS word.
+const WORD_BYTES: usize = 4;
+/// Bits in a MIPS word; `shamt` is less than this.
+const WORD_BITS: u32 = 32;
+
+#[derive(Debug, Clone)]
+pub(crate) struct ShiftLeftConfig<F: FieldExt> {
+    word: Column<Advice>,
+    bytes: [Column<Advice>; WORD_BYTES],
+    shamt: Column<Advice>,
+    pow: Column<Advice>,
+    q_shift: Selector,
+    q_decompose: Selector,
+    table: RangeCheckTable<F, 8>,
+    table_shamt: TableColumn,
+    table_pow: TableColumn,
+}
+
+pub(crate) struct ShiftLeftChip<F: FieldExt> {
+    config: ShiftLeftConfig<F>,
+}
+
+impl<F: FieldExt> ShiftLeftChip<F> {
+    pub(crate) fn construct(config: ShiftLeftConfig<F>) -> Self {
+        Self {
+            config
+        }
+    }
+
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> ShiftLeftConfig<F> {
+        let word = meta.advice_column();
+        let bytes = [(); WORD_BYTES].map(|_| meta.advice_column());
+        let shamt = meta.advice_column();
+        let pow = meta.advice_column();
+        let q_shift = meta.complex_selector();
+        let q_decompose = meta.complex_selector();
+        let table = RangeCheckTable::configure(meta);
+        let table_shamt = meta.lookup_table_column();
+        let table_pow = meta.lookup_table_column();
+
+        meta.enable_equality(word);
+        meta.enable_equality(shamt);
+
+        // word = byte_0 + byte_1 * 2^8 + byte_2 * 2^16 + byte_3 * 2^24
+        meta.create_gate("decompose", |meta| {
+            let s = meta.query_selector(q_decompose);
+            let word = meta.query_advice(word, Rotation::cur());
+            let sum = bytes.iter().rev().fold(Expression::Constant(F::zero()), |acc, byte| {
+                acc * Expression::Constant(F::from(1u64 << 8)) + meta.query_advice(*byte, Rotation::cur())
+            });
+
+            Constraints::with_selector(s, [("word = sum of bytes", word - sum)])
+        });
+
+        // every byte is in [0, 2^8)
+        for byte in bytes {
+            meta.lookup(|meta| {
+                let s = meta.query_selector(q_decompose);
+                let byte = meta.query_advice(byte, Rotation::cur());
+
+                vec![
+                    (s * byte, table.value)
+                ]
+            });
+        }
+
+        // (shamt, pow) is a row of the power table;
+        // a disabled row looks up (0, 1), which the table always provides
+        meta.lookup(|meta| {
+            let s = meta.query_selector(q_shift);
+            let shamt = meta.query_advice(shamt, Rotation::cur());
+            let pow = meta.query_advice(pow, Rotation::cur());
+
+            vec![
+                (s.clone() * shamt, table_shamt),
+                (s.clone() * pow + (Expression::Constant(F::one()) - s), table_pow),
+            ]
+        });
+
+        // in * 2^shamt = out + hi * 2^32
+        meta.create_gate("shift left", |meta| {
+            let s = meta.query_selector(q_shift);
+            let input = meta.query_advice(word, Rotation::cur());
+            let out = meta.query_advice(word, Rotation::next());
+            let hi = meta.query_advice(word, Rotation(2));
+            let pow = meta.query_advice(pow, Rotation::cur());
+            let modulus = Expression::Constant(F::from(1u64 << WORD_BITS));
+
+            Constraints::with_selector(s, [
+                ("in * 2^shamt = out + hi * 2^32", input * pow - out - hi * modulus),
+            ])
+        });
+
+        ShiftLeftConfig {
+            word,
+            bytes,
+            shamt,
+            pow,
+            q_shift,
+            q_decompose,
+            table,
+            table_shamt,
+            table_pow,
+        }
+    }
+
+    /// Loads the byte table and the table of `(shamt, 2^shamt)`.
+    pub(crate) fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.table.assign(layouter)?;
+
+        layouter.assign_table(|| "powers of two", |mut table| {
+            for shamt in 0..WORD_BITS {
+                table.assign_cell(|| "shamt", self.config.table_shamt, shamt as usize, || Value::known(F::from(shamt as u64)))?;
+                table.assign_cell(|| "pow", self.config.table_pow, shamt as usize, || Value::known(F::from(1u64 << shamt)))?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn assign_word(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        word: Value<u32>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.config.q_decompose.enable(region, offset)?;
+        for (i, byte) in self.config.bytes.iter().enumerate() {
+            region.assign_advice(
+                || format!("byte {}", i),
+                *byte,
+                offset,
+                || word.map(|word| F::from(((word >> (8 * i)) & 0xff) as u64))
+            )?;
+        }
+        region.assign_advice(|| "word", self.config.word, offset, || word.map(|word| F::from(word as u64)))
+    }
+
+    /// Assigns `in` and `shamt`, returning the cell of `out`.
+    pub(crate) fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input: Value<u32>,
+        shamt: Value<u32>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "shift left",
+            |mut region| {
+                self.config.q_shift.enable(&mut region, 0)?;
+
+                // a shamt of 32 or more fails the power lookup, whatever the product
+                let product = input.zip(shamt).map(|(input, shamt)| (input as u128) << shamt.min(64));
+                let out = product.map(|product| product as u32);
+                let hi = product.map(|product| (product >> WORD_BITS) as u32);
+
+                self.assign_word(&mut region, 0, input)?;
+                let out_cell = self.assign_word(&mut region, 1, out)?;
+                self.assign_word(&mut region, 2, hi)?;
+
+                region.assign_advice(|| "shamt", self.config.shamt, 0, || shamt.map(|shamt| F::from(shamt as u64)))?;
+                region.assign_advice(
+                    || "2^shamt",
+                    self.config.pow,
+                    0,
+                    || shamt.map(|shamt| F::from(2u64).pow_vartime(&[shamt as u64]))
+                )?;
+
+                Ok(out_cell)
+            }
+        )
+    }
+}
+
+/// Shifts `input` left by `shamt`, exposing the output at instance row 0.
+#[derive(Default)]
+struct MyCircuit {
+    input: u32,
+    shamt: u32,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit {
+    type Config = (ShiftLeftConfig<F>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (ShiftLeftChip::configure(meta), instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = ShiftLeftChip::construct(config.0);
+        chip.load_table(&mut layouter)?;
+
+        let out = chip.assign(
+            layouter.namespace(|| "sll"),
+            Value::known(self.input),
+            Value::known(self.shamt),
+        )?;
+
+        layouter.constrain_instance(out.cell(), config.1, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use crate::zkmips::shift::MyCircuit;
+
+    #[test]
+    fn test_sll() {
+        let circuit = MyCircuit {
+            input: 1,
+            shamt: 4,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(16)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_sll_wrapping() {
+        // the top four bits are shifted out
+        let circuit = MyCircuit {
+            input: 0xf000_0001,
+            shamt: 4,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(0x10)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_sll_shamt_out_of_range() {
+        let circuit = MyCircuit {
+            input: 1,
+            shamt: 32,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(0)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_sll_wrong_output() {
+        // 1 << 4 is not 17
+        let circuit = MyCircuit {
+            input: 1,
+            shamt: 4,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(17)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
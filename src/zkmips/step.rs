@@ -0,0 +1,199 @@
+/// The off-circuit reference interpreter; circuit tests check their witnesses against it.
+/// Branch delay slots are out of scope: a taken branch or jump moves `pc` straight to its target.
+use crate::zkmips::instructions::{Instruction, Register, SyscallNumber};
+use crate::zkmips::register::RegisterFile;
+use crate::zkmips::syscall::{handle_syscall, SyscallError, V0};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+/// `$ra`, the link register of `jal`, `bltzal` and `bgezal`.
+pub(crate) const RA: Register = Register(31);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MipsState {
+    pub regs: RegisterFile,
+    pub pc: u32,
+    /// Byte-addressed memory; unwritten bytes read as 0.
+    pub memory: HashMap<u32, u8>,
+    /// Bytes written to a file descriptor by the WRITE syscall.
+    pub output: Vec<u8>,
+    /// Set by the EXIT syscall, together with the exit code.
+    pub halted: bool,
+    pub exit_code: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepError {
+    /// `add`, `sub` or `addi` overflowed as signed integers.
+    Overflow(Instruction),
+    /// The instruction decodes but the interpreter does not execute it.
+    Unsupported(Instruction),
+    Syscall(SyscallError),
+}
+
+/// Executes `inst` on `state`, advancing `pc` to the next instruction to run.
+pub fn step(state: &mut MipsState, inst: &Instruction) -> Result<(), StepError> {
+    let mnemonic = inst.mnemonic().ok_or(StepError::Unsupported(*inst))?;
+    let next_pc = state.pc.wrapping_add(4);
+
+    match *inst {
+        Instruction::RType { rs, rt, rd, shamt, .. } => {
+            let (s, t) = (state.regs.read(rs), state.regs.read(rt));
+            let result = match mnemonic {
+                "add" => (s as i32).checked_add(t as i32).ok_or(StepError::Overflow(*inst))? as u32,
+                "addu" => s.wrapping_add(t),
+                "sub" => (s as i32).checked_sub(t as i32).ok_or(StepError::Overflow(*inst))? as u32,
+                "subu" => s.wrapping_sub(t),
+                "and" => s & t,
+                "or" => s | t,
+                "xor" => s ^ t,
+                "nor" => !(s | t),
+                "slt" => ((s as i32) < (t as i32)) as u32,
+                "sltu" => (s < t) as u32,
+                "sll" => t << shamt,
+                "srl" => t >> shamt,
+                "sra" => ((t as i32) >> shamt) as u32,
+                "jr" => {
+                    state.pc = s;
+                    return Ok(());
+                }
+                "syscall" => {
+                    let num = SyscallNumber::try_from(state.regs.read(V0)).map_err(StepError::Syscall)?;
+                    handle_syscall(state, num).map_err(StepError::Syscall)?;
+                    // an EXIT leaves pc on the syscall
+                    if !state.halted {
+                        state.pc = next_pc;
+                    }
+                    return Ok(());
+                }
+                _ => return Err(StepError::Unsupported(*inst)),
+            };
+            state.regs.write(rd, result);
+            state.pc = next_pc;
+        }
+        Instruction::IType { rs, rt, imm, .. } => {
+            let s = state.regs.read(rs);
+            let t = state.regs.read(rt);
+            let signed_imm = imm as i16 as i32;
+            let taken = match mnemonic {
+                "addi" => {
+                    let result = (s as i32).checked_add(signed_imm).ok_or(StepError::Overflow(*inst))?;
+                    state.regs.write(rt, result as u32);
+                    false
+                }
+                "addiu" => {
+                    state.regs.write(rt, s.wrapping_add(signed_imm as u32));
+                    false
+                }
+                "andi" => {
+                    state.regs.write(rt, s & imm as u32);
+                    false
+                }
+                "ori" => {
+                    state.regs.write(rt, s | imm as u32);
+                    false
+                }
+                "xori" => {
+                    state.regs.write(rt, s ^ imm as u32);
+                    false
+                }
+                "beq" => s == t,
+                "bne" => s != t,
+                "blez" => (s as i32) <= 0,
+                "bgtz" => (s as i32) > 0,
+                "bltz" => (s as i32) < 0,
+                "bgez" => (s as i32) >= 0,
+                "bltzal" | "bgezal" => {
+                    state.regs.write(RA, next_pc);
+                    if mnemonic == "bltzal" { (s as i32) < 0 } else { (s as i32) >= 0 }
+                }
+                _ => return Err(StepError::Unsupported(*inst)),
+            };
+            state.pc = if taken {
+                next_pc.wrapping_add((signed_imm << 2) as u32)
+            } else {
+                next_pc
+            };
+        }
+        Instruction::JType { addr, .. } => {
+            if mnemonic == "jal" {
+                state.regs.write(RA, next_pc);
+            }
+            state.pc = (next_pc & 0xf000_0000) | (addr << 2);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::zkmips::instructions::{Instruction, Register};
+    use crate::zkmips::step::{step, MipsState, StepError};
+
+    #[test]
+    fn test_add() {
+        let mut state = MipsState::default();
+        state.regs.write(Register(1), 12);
+        state.regs.write(Register(2), 30);
+
+        // add $3, $1, $2
+        step(&mut state, &Instruction::decode(0x00221820).unwrap()).unwrap();
+        assert_eq!(state.regs.read(Register(3)), 42);
+        assert_eq!(state.pc, 4);
+
+        // add traps on signed overflow
+        state.regs.write(Register(1), i32::MAX as u32);
+        state.regs.write(Register(2), 1);
+        let add = Instruction::decode(0x00221820).unwrap();
+        assert_eq!(step(&mut state, &add), Err(StepError::Overflow(add)));
+    }
+
+    #[test]
+    fn test_beq() {
+        // beq $4, $5, 3
+        let beq = Instruction::decode(0x10850003).unwrap();
+
+        let mut state = MipsState {
+            pc: 0x100,
+            ..Default::default()
+        };
+        state.regs.write(Register(4), 7);
+        state.regs.write(Register(5), 7);
+        step(&mut state, &beq).unwrap();
+        assert_eq!(state.pc, 0x100 + 4 + 3 * 4);
+
+        let mut state = MipsState {
+            pc: 0x100,
+            ..Default::default()
+        };
+        state.regs.write(Register(4), 7);
+        state.regs.write(Register(5), 8);
+        step(&mut state, &beq).unwrap();
+        assert_eq!(state.pc, 0x104);
+
+        // a negative offset branches backwards: beq $0, $0, -2
+        let mut state = MipsState {
+            pc: 0x100,
+            ..Default::default()
+        };
+        step(&mut state, &Instruction::decode(0x1000fffe).unwrap()).unwrap();
+        assert_eq!(state.pc, 0x100 + 4 - 2 * 4);
+    }
+
+    #[test]
+    fn test_j() {
+        let mut state = MipsState {
+            pc: 0x1000_0000,
+            ..Default::default()
+        };
+        // j 0x100
+        step(&mut state, &Instruction::decode(0x08000100).unwrap()).unwrap();
+        assert_eq!(state.pc, 0x1000_0400);
+
+        // jal 0x40 links the return address
+        step(&mut state, &Instruction::decode(0x0c000040).unwrap()).unwrap();
+        assert_eq!(state.pc, 0x1000_0100);
+        assert_eq!(state.regs.read(Register(31)), 0x1000_0404);
+    }
+}
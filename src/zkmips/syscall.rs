@@ -0,0 +1,109 @@
+/// Syscalls follow the MIPS o32 convention: the number is in `$v0`, the arguments in
+/// `$a0..$a2`, and the result is returned in `$v0`.
+use std::convert::TryFrom;
+use crate::zkmips::instructions::{Register, SyscallNumber};
+use crate::zkmips::step::MipsState;
+
+pub const V0: Register = Register(2);
+pub const A0: Register = Register(4);
+pub const A1: Register = Register(5);
+pub const A2: Register = Register(6);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyscallError {
+    /// `$v0` holds no syscall number we know of.
+    UnknownNumber(u32),
+    /// The syscall is known but the interpreter does not implement it.
+    Unsupported(SyscallNumber),
+}
+
+pub type SyscallResult = Result<(), SyscallError>;
+
+impl TryFrom<u32> for SyscallNumber {
+    type Error = SyscallError;
+
+    fn try_from(num: u32) -> Result<Self, Self::Error> {
+        match num {
+            4090 => Ok(SyscallNumber::MMAP),
+            4045 => Ok(SyscallNumber::BRK),
+            4120 => Ok(SyscallNumber::CLONE),
+            4246 => Ok(SyscallNumber::EXIT),
+            4003 => Ok(SyscallNumber::READ),
+            4004 => Ok(SyscallNumber::WRITE),
+            4055 => Ok(SyscallNumber::FCNTL),
+            _ => Err(SyscallError::UnknownNumber(num)),
+        }
+    }
+}
+
+pub fn handle_syscall(state: &mut MipsState, num: SyscallNumber) -> SyscallResult {
+    match num {
+        // exit(code)
+        SyscallNumber::EXIT => {
+            state.halted = true;
+            state.exit_code = state.regs.read(A0);
+        }
+        // write(fd, buf, count): every descriptor goes to the same output buffer
+        SyscallNumber::WRITE => {
+            let buf = state.regs.read(A1);
+            let count = state.regs.read(A2);
+            for i in 0..count {
+                let byte = state.memory.get(&buf.wrapping_add(i)).copied().unwrap_or(0);
+                state.output.push(byte);
+            }
+            state.regs.write(V0, count);
+        }
+        _ => return Err(SyscallError::Unsupported(num)),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use crate::zkmips::instructions::{Instruction, SyscallNumber};
+    use crate::zkmips::step::{step, MipsState, StepError};
+    use crate::zkmips::syscall::{handle_syscall, SyscallError, A0, A1, A2, V0};
+
+    #[test]
+    fn test_syscall_number() {
+        assert_eq!(SyscallNumber::try_from(4246), Ok(SyscallNumber::EXIT));
+        assert_eq!(SyscallNumber::try_from(4004), Ok(SyscallNumber::WRITE));
+        assert_eq!(SyscallNumber::try_from(1), Err(SyscallError::UnknownNumber(1)));
+    }
+
+    #[test]
+    fn test_exit() {
+        let mut state = MipsState::default();
+        state.regs.write(A0, 3);
+        handle_syscall(&mut state, SyscallNumber::EXIT).unwrap();
+        assert!(state.halted);
+        assert_eq!(state.exit_code, 3);
+    }
+
+    #[test]
+    fn test_write() {
+        let mut state = MipsState::default();
+        for (i, byte) in b"hi".iter().enumerate() {
+            state.memory.insert(0x100 + i as u32, *byte);
+        }
+        state.regs.write(A0, 1);
+        state.regs.write(A1, 0x100);
+        state.regs.write(A2, 2);
+        handle_syscall(&mut state, SyscallNumber::WRITE).unwrap();
+        assert_eq!(state.output, b"hi");
+        assert_eq!(state.regs.read(V0), 2);
+    }
+
+    #[test]
+    fn test_unknown_syscall() {
+        let mut state = MipsState::default();
+        state.regs.write(V0, 1);
+        let syscall = Instruction::decode(0x0000000c).unwrap();
+        assert_eq!(
+            step(&mut state, &syscall),
+            Err(StepError::Syscall(SyscallError::UnknownNumber(1)))
+        );
+    }
+}
@@ -0,0 +1,159 @@
+/// Runs a program on the reference interpreter, recording every step for a step circuit to consume.
+/// The program is loaded at address 0, so the instruction at `pc` is `program[pc / 4]`.
+use crate::zkmips::instructions::{Instruction, Register, Syntax};
+use crate::zkmips::step::{step, MipsState, StepError, RA};
+use crate::zkmips::syscall::{A0, A1, A2, V0};
+
+/// One executed instruction and the register accesses it made.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepRecord {
+    pub pc: u32,
+    pub instruction: Instruction,
+    /// The registers read, with their values before the step.
+    pub reads: Vec<(Register, u32)>,
+    /// The registers written, with their values after the step; a write to `$zero` stores 0.
+    pub writes: Vec<(Register, u32)>,
+    pub next_pc: u32,
+}
+
+/// The registers `inst` reads, by its syntax. A syscall reads its number from `$v0` and the
+/// arguments the syscalls take from `$a0`-`$a2`.
+fn sources(inst: &Instruction) -> Vec<Register> {
+    let syntax = match inst.syntax() {
+        Some((_, syntax)) => syntax,
+        None => return vec![],
+    };
+
+    match (*inst, syntax) {
+        (Instruction::RType { rs, rt, .. }, Syntax::ThreeReg) => vec![rs, rt],
+        (Instruction::RType { rt, .. }, Syntax::Shift) => vec![rt],
+        (Instruction::RType { rs, .. }, Syntax::JumpReg) => vec![rs],
+        (_, Syntax::NoOperand) => vec![V0, A0, A1, A2],
+        (Instruction::IType { rs, rt, .. }, Syntax::Branch) => vec![rs, rt],
+        (Instruction::IType { rs, .. }, Syntax::Immediate | Syntax::LogicalImmediate | Syntax::BranchZero) => vec![rs],
+        _ => vec![],
+    }
+}
+
+/// The register `inst` writes, if any.
+fn destination(inst: &Instruction) -> Option<Register> {
+    match (*inst, inst.mnemonic()?) {
+        (Instruction::RType { .. }, "jr") | (Instruction::RType { .. }, "syscall") => None,
+        (Instruction::RType { rd, .. }, _) => Some(rd),
+        (Instruction::IType { rt, .. }, "addi" | "addiu" | "andi" | "ori" | "xori") => Some(rt),
+        (Instruction::IType { .. }, "bltzal" | "bgezal") | (Instruction::JType { .. }, "jal") => Some(RA),
+        _ => None,
+    }
+}
+
+/// Executes `program` until it halts, `pc` leaves the program, or `max_steps` steps have run.
+/// Returns the trace and whether it ended on an EXIT syscall, the last step recorded; only
+/// then is the trace complete, rather than cut short.
+pub fn run_trace(program: &[Instruction], max_steps: usize) -> Result<(Vec<StepRecord>, bool), StepError> {
+    let mut state = MipsState::default();
+    let mut trace = vec![];
+
+    while trace.len() < max_steps && !state.halted {
+        let pc = state.pc;
+        let instruction = match program.get((pc / 4) as usize) {
+            Some(instruction) if pc % 4 == 0 => *instruction,
+            _ => break,
+        };
+
+        let reads = sources(&instruction)
+            .into_iter()
+            .map(|reg| (reg, state.regs.read(reg)))
+            .collect();
+        step(&mut state, &instruction)?;
+        let writes = destination(&instruction)
+            .into_iter()
+            .map(|reg| (reg, state.regs.read(reg)))
+            .collect();
+
+        trace.push(StepRecord {
+            pc,
+            instruction,
+            reads,
+            writes,
+            next_pc: state.pc,
+        });
+    }
+
+    Ok((trace, state.halted))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::zkmips::instructions::{Instruction, Register};
+    use crate::zkmips::register::RegisterFile;
+    use crate::zkmips::syscall::{A0, A1, A2, V0};
+    use crate::zkmips::trace::run_trace;
+
+    #[test]
+    fn test_run_trace() {
+        let program = [
+            0x24020003, // addiu $2, $0, 3
+            0x24210001, // loop: addiu $1, $1, 1
+            0x1422fffe, // bne $1, $2, loop
+            0x00221821, // addu $3, $1, $2
+            0x00032040, // sll $4, $3, 1
+        ].map(|word| Instruction::decode(word).unwrap());
+
+        let (trace, halted) = run_trace(&program, 100).unwrap();
+        // pc runs off the end of the program without an EXIT
+        assert!(!halted);
+        // the loop body runs three times
+        assert_eq!(trace.len(), 1 + 3 * 2 + 2);
+        assert_eq!(trace[2].pc, 0x08);
+        assert_eq!(trace[2].reads, vec![(Register(1), 1), (Register(2), 3)]);
+        assert_eq!(trace[2].next_pc, 0x04);
+        assert_eq!(trace.last().unwrap().next_pc, 0x14);
+
+        // replaying the writes gives the final register state
+        let mut regs = RegisterFile::new();
+        for (reg, value) in trace.iter().flat_map(|record| record.writes.iter()) {
+            regs.write(*reg, *value);
+        }
+        assert_eq!(regs.read(Register(1)), 3);
+        assert_eq!(regs.read(Register(2)), 3);
+        assert_eq!(regs.read(Register(3)), 6);
+        assert_eq!(regs.read(Register(4)), 12);
+
+        // the trace stops at max_steps
+        let (trace, halted) = run_trace(&program, 4).unwrap();
+        assert_eq!(trace.len(), 4);
+        assert!(!halted);
+    }
+
+    #[test]
+    fn test_exit() {
+        let program = [
+            0x24021096, // addiu $2, $0, 4246
+            0x24040007, // addiu $4, $0, 7
+            0x0000000c, // syscall
+            0x24030001, // addiu $3, $0, 1
+        ].map(|word| Instruction::decode(word).unwrap());
+
+        let (trace, halted) = run_trace(&program, 100).unwrap();
+        assert!(halted);
+        assert_eq!(trace.len(), 3);
+        // EXIT leaves pc on the syscall, and nothing after it runs
+        assert_eq!(trace[2].pc, 0x08);
+        assert_eq!(trace[2].next_pc, 0x08);
+        assert_eq!(trace[2].reads, vec![(V0, 4246), (A0, 7), (A1, 0), (A2, 0)]);
+    }
+
+    #[test]
+    fn test_sources() {
+        let program = [
+            0x24090005, // addiu $9, $0, 5
+            0x00094100, // sll $8, $9, 4
+            0x01000008, // jr $8
+        ].map(|word| Instruction::decode(word).unwrap());
+
+        let (trace, _) = run_trace(&program, 3).unwrap();
+        // sll reads rt only, and jr rs only
+        assert_eq!(trace[1].reads, vec![(Register(9), 5)]);
+        assert_eq!(trace[2].reads, vec![(Register(8), 80)]);
+    }
+}